@@ -0,0 +1,20 @@
+//! Encodes arbitrary constructor arguments, which must only ever fail with an error.
+//!
+//! Inputs are read as `type:value` to encode against a type hint, or as a bare `value`.
+
+#![no_main]
+
+use huff_codegen::Codegen;
+use huff_utils::prelude::{EToken, PrimitiveEVMType};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        if let Some((ty, value)) = input.split_once(':') {
+            if let Ok(ty) = PrimitiveEVMType::try_from(ty.to_string()) {
+                let _ = EToken::try_from_typed(value.to_string(), &ty);
+            }
+        }
+        let _ = Codegen::encode_constructor_args(vec![input.to_string()], &[]);
+    }
+});
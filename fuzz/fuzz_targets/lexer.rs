@@ -0,0 +1,14 @@
+//! Feeds arbitrary source to the lexer, which must only ever fail with a `LexicalError`.
+
+#![no_main]
+
+use huff_lexer::Lexer;
+use huff_utils::prelude::FullFileSource;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(source) = std::str::from_utf8(data) {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        for _ in Lexer::new(flattened_source) {}
+    }
+});
@@ -10,7 +10,8 @@
 use clap::Parser as ClapParser;
 use huff_core::Compiler;
 use huff_utils::prelude::{
-    unpack_files, AstSpan, CodegenError, CodegenErrorKind, CompilerError, FileSource, Span,
+    unpack_files, Artifact, AstSpan, CodegenError, CodegenErrorKind, CompilerError, FileSource,
+    Span,
 };
 use isatty::stdout_isatty;
 use spinners::{Spinner, Spinners};
@@ -56,6 +57,22 @@ struct Huff {
     #[clap(short = 'p', long = "print")]
     print: bool,
 
+    /// Prefix bytecode with 0x.
+    #[clap(long = "hex-prefix")]
+    hex_prefix: bool,
+
+    /// Append the compiler version to the runtime as CBOR metadata.
+    #[clap(long = "metadata")]
+    metadata: bool,
+
+    /// A JSON file mapping constant names to values, injected at compile time.
+    #[clap(long = "constants")]
+    constants: Option<String>,
+
+    /// Print the ABI as solc-compatible JSON.
+    #[clap(long = "abi")]
+    abi: bool,
+
     /// Verbose output.
     #[clap(short = 'v', long = "verbose")]
     verbose: bool,
@@ -88,6 +105,10 @@ fn main() {
         construct_args: cli.inputs,
         optimize: cli.optimize,
         bytecode: cli.bytecode,
+        hex_prefix: cli.hex_prefix,
+        metadata: cli.metadata,
+        constants_json: cli.constants.clone(),
+        ..Default::default()
     };
 
     // Create compiling spinner
@@ -139,6 +160,15 @@ fn main() {
                         .for_each(|a| println!("\"{}\" bytecode: {}", a.file.path, a.bytecode)),
                 }
             }
+            if cli.abi {
+                let solc_abi = |a: &Arc<Artifact>| a.abi.clone().unwrap_or_default().to_solc_json();
+                match sources.len() {
+                    1 => println!("{}", solc_abi(&artifacts[0])),
+                    _ => artifacts
+                        .iter()
+                        .for_each(|a| println!("\"{}\" abi: {}", a.file.path, solc_abi(a))),
+                }
+            }
         }
         Err(e) => {
             tracing::error!(target: "core", "COMPILER ERRORED: {:?}", e);
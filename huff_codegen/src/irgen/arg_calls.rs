@@ -1,3 +1,7 @@
+use crate::{
+    irgen::constants::{constant_value, immutable_placeholder},
+    Codegen,
+};
 use huff_utils::prelude::*;
 use std::str::FromStr;
 
@@ -18,6 +22,8 @@ pub fn bubble_arg_call(
     // mis: Parent macro invocations and their indices
     mis: &mut Vec<(usize, MacroInvocation)>,
     jump_table: &mut JumpTable,
+    // scope_depth: How many expansions up the caller passing the argument is
+    scope_depth: usize,
 ) -> Result<(), CodegenError> {
     let starting_offset = *offset;
 
@@ -27,8 +33,7 @@ pub fn bubble_arg_call(
         tracing::info!(target: "codegen", "ARGCALL IS CONSTANT: {:?}", constant);
         let push_bytes = match &constant.value {
             ConstVal::Literal(l) => {
-                let hex_literal: String = bytes32_to_string(l, false);
-                format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal)
+                Codegen::push_literal(&bytes32_to_string(l, false), constant.span.clone())?
             }
             ConstVal::FreeStoragePointer(fsp) => {
                 // If this is reached in codegen stage,
@@ -41,6 +46,9 @@ pub fn bubble_arg_call(
                     token: None,
                 })
             }
+            ConstVal::Immutable => format!("7f{}", immutable_placeholder(arg_name)),
+            // Arrays have no single value to push, so this raises their error
+            ConstVal::Array(_) => constant_value(arg_name, contract, constant.span.clone())?,
         };
         *offset += push_bytes.len() / 2;
         tracing::info!(target: "codegen", "OFFSET: {}, PUSH BYTES: {:?}", offset, push_bytes);
@@ -106,6 +114,7 @@ pub fn bubble_arg_call(
                                 offset,
                                 &mut Vec::from(&mis[..mis.len().saturating_sub(1)]),
                                 jump_table,
+                                scope_depth + 1,
                             )
                         } else {
                             bubble_arg_call(
@@ -117,6 +126,7 @@ pub fn bubble_arg_call(
                                 offset,
                                 mis,
                                 jump_table,
+                                scope_depth + 1,
                             )
                         }
                     }
@@ -125,7 +135,8 @@ pub fn bubble_arg_call(
                         tracing::debug!(target: "codegen", "Macro invocation index: {}", macro_invoc.0);
                         tracing::debug!(target: "codegen", "At offset: {}", *offset);
 
-                        // This should be equivalent to a label call.
+                        // This should be equivalent to a label call, resolved in the scope the
+                        // label was passed from.
                         bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
                         jump_table.insert(
                             *offset,
@@ -133,6 +144,7 @@ pub fn bubble_arg_call(
                                 label: iden.to_owned(),
                                 bytecode_index: 0,
                                 span: macro_invoc.1.span.clone(),
+                                scope_depth,
                             }],
                         );
                         *offset += 3;
@@ -153,7 +165,12 @@ pub fn bubble_arg_call(
         };
         jump_table.insert(
             mis.last().map(|mi| mi.0).unwrap_or_else(|| 0),
-            vec![Jump { label: arg_name.to_owned(), bytecode_index: 0, span: new_span }],
+            vec![Jump {
+                label: arg_name.to_owned(),
+                bytecode_index: 0,
+                span: new_span,
+                scope_depth: 0,
+            }],
         );
         bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
         *offset += 3;
@@ -1,5 +1,8 @@
+use crate::Codegen;
+use ethers_core::{types::U256, utils::keccak256};
 use huff_utils::prelude::{
-    bytes32_to_string, AstSpan, CodegenError, CodegenErrorKind, ConstVal, Contract,
+    bytes32_to_string, AstSpan, CodegenError, CodegenErrorKind, ConstVal, ConstantDefinition,
+    Contract,
 };
 
 /// Transforms a constant definition into it's respective bytecode
@@ -7,6 +10,28 @@ pub fn constant_gen(
     name: &str,
     contract: &Contract,
     ir_byte_span: AstSpan,
+) -> Result<String, CodegenError> {
+    // Immutables are read from a PUSH32 the constructor patches
+    if contract.constants.iter().any(|c| c.name == name && c.value == ConstVal::Immutable) {
+        return Ok(format!("7f{}", immutable_placeholder(name)))
+    }
+    let hex_literal = constant_value(name, contract, ir_byte_span.clone())?;
+    Codegen::push_literal(&hex_literal, ir_byte_span)
+}
+
+/// The 32 byte placeholder pushed for an immutable until the constructor patches it,
+/// `keccak256("immutable.<name>")`
+pub fn immutable_placeholder(name: &str) -> String {
+    hex::encode(keccak256(format!("immutable.{}", name)))
+}
+
+/// Resolves a constant definition into its raw hex value, without a PUSH opcode
+///
+/// Used when a constant is inlined into a data context, such as a table.
+pub fn constant_value(
+    name: &str,
+    contract: &Contract,
+    ir_byte_span: AstSpan,
 ) -> Result<String, CodegenError> {
     // Get the first `ConstantDefinition` that matches the constant's name
     let constant =
@@ -22,26 +47,83 @@ pub fn constant_gen(
             })
         };
 
-    // Generate bytecode for the constant
     // Should always be a `Literal` if storage pointers were derived in the AST
     // prior to generating the IR bytes.
     tracing::info!(target: "codegen", "FOUND CONSTANT DEFINITION: {}", constant.name);
-    let push_bytes = match &constant.value {
-        ConstVal::Literal(l) => {
-            let hex_literal: String = bytes32_to_string(l, false);
-            format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal)
-        }
+    match &constant.value {
+        ConstVal::Literal(l) => Ok(bytes32_to_string(l, false)),
         ConstVal::FreeStoragePointer(fsp) => {
             // If this is reached in codegen stage, the `derive_storage_pointers`
             // method was not called on the AST.
             tracing::error!(target: "codegen", "STORAGE POINTERS INCORRECTLY DERIVED FOR \"{:?}\"", fsp);
-            return Err(CodegenError {
+            Err(CodegenError {
                 kind: CodegenErrorKind::StoragePointersNotDerived,
                 span: constant.span.clone(),
                 token: None,
             })
         }
-    };
+        ConstVal::Array(_) => {
+            tracing::error!(target: "codegen", "CONSTANT ARRAY \"{}\" USED OUTSIDE OF A CODE TABLE", name);
+            Err(CodegenError {
+                kind: CodegenErrorKind::InvalidArguments(format!(
+                    "Constant array \"{}\" can only be used in a code table",
+                    name
+                )),
+                span: ir_byte_span,
+                token: None,
+            })
+        }
+        ConstVal::Immutable => {
+            tracing::error!(target: "codegen", "IMMUTABLE \"{}\" USED OUTSIDE OF CODE", name);
+            Err(CodegenError {
+                kind: CodegenErrorKind::InvalidArguments(format!(
+                    "Immutable \"{}\" has no value at compile time",
+                    name
+                )),
+                span: ir_byte_span,
+                token: None,
+            })
+        }
+    }
+}
+
+/// Resolves a constant into the raw hex it inlines into a code table, where the elements of a
+/// constant array are laid out back to back
+pub fn constant_table_code(
+    name: &str,
+    contract: &Contract,
+    ir_byte_span: AstSpan,
+) -> Result<String, CodegenError> {
+    match contract.constants.iter().find(|const_def| const_def.name.eq(&name)) {
+        Some(ConstantDefinition { value: ConstVal::Array(elements), .. }) => {
+            Ok(elements.iter().map(|l| bytes32_to_string(l, false)).collect())
+        }
+        _ => constant_value(name, contract, ir_byte_span),
+    }
+}
 
-    Ok(push_bytes)
+/// Resolves an operand of a compile time expression into its value
+///
+/// Operands are `0x` prefixed literals, decimal numbers or the names of constants.
+pub fn constant_operand(
+    operand: &str,
+    contract: &Contract,
+    ir_byte_span: AstSpan,
+) -> Result<U256, CodegenError> {
+    let value = match operand.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None if operand.chars().all(|c| c.is_ascii_digit()) => U256::from_dec_str(operand).ok(),
+        None => {
+            let hex = constant_value(operand, contract, ir_byte_span.clone())?;
+            U256::from_str_radix(&hex, 16).ok()
+        }
+    };
+    value.ok_or_else(|| {
+        tracing::error!(target: "codegen", "INVALID OPERAND \"{}\"", operand);
+        CodegenError {
+            kind: CodegenErrorKind::InvalidArguments(format!("Invalid operand \"{}\"", operand)),
+            span: ir_byte_span,
+            token: None,
+        }
+    })
 }
@@ -1,6 +1,58 @@
 use huff_utils::prelude::*;
 
-use crate::Codegen;
+use crate::{irgen::constants::constant_operand, Codegen};
+
+/// The size of the EIP-1167 clone emitted by `__minimal_proxy`
+const MINIMAL_PROXY_SIZE: usize = 45;
+
+/// The label marking the shared body of an `@outline` macro
+pub fn outlined_label(macro_name: &str) -> String {
+    format!("__outlined_{}", macro_name)
+}
+
+/// Scopes the labels of an inlined macro expansion to the expansion.
+///
+/// Labels are suffixed with the expansion's scope, its index among its siblings for inlined
+/// macros, so labels defined by different expansions (or by the caller) never collide once the
+/// bodies are inlined. The expansion's own jumps were already resolved against its unscoped
+/// labels.
+///
+/// Indices are counted in expansion order, so nested labels get the same names on every compile
+/// of the same source, e.g. `loop.1.0` for a `loop` in the second expansion within the first.
+pub fn scope_labels(label_indices: LabelIndices, scope: &str) -> LabelIndices {
+    label_indices
+        .into_iter()
+        .map(|(label, index)| (format!("{}.{}", label, scope), index))
+        .collect()
+}
+
+/// Hands the unmatched jumps of an expansion over to its caller.
+///
+/// Jumps to labels passed in as arguments move one scope up, and jumps into outlined bodies are
+/// resolved once the bodies are appended. Any other label missing from the expansion is an
+/// error: a macro can only jump to a caller's label that was passed to it as an argument.
+pub fn caller_jumps(jumps: Vec<Jump>, macro_name: &str) -> Result<Vec<Jump>, CodegenError> {
+    let (passed, unmatched): (Vec<Jump>, Vec<Jump>) = jumps
+        .into_iter()
+        .partition(|j| j.scope_depth > 0 || j.label.starts_with(&outlined_label("")));
+    if !unmatched.is_empty() {
+        tracing::error!(
+            target: "codegen",
+            "Labels \"{}\" are not defined in macro \"{}\", caller labels must be passed as arguments",
+            unmatched.iter().map(|j| j.label.to_string()).collect::<Vec<String>>().join(", "),
+            macro_name
+        );
+        return Err(CodegenError {
+            kind: CodegenErrorKind::UnmatchedJumpLabel,
+            span: AstSpan(unmatched.into_iter().flat_map(|j| j.span.0).collect()),
+            token: None,
+        })
+    }
+    Ok(passed
+        .into_iter()
+        .map(|j| Jump { scope_depth: j.scope_depth.saturating_sub(1), ..j })
+        .collect())
+}
 
 /// Generates the respective Bytecode for a given Statement
 #[allow(clippy::too_many_arguments)]
@@ -14,6 +66,7 @@ pub fn statement_gen(
     jump_table: &mut JumpTable,
     label_indices: &mut LabelIndices,
     table_instances: &mut Jumps,
+    expansions: &mut Vec<MacroExpansion>,
     starting_offset: usize,
 ) -> Result<Vec<(usize, Bytes)>, CodegenError> {
     let mut bytes = vec![];
@@ -40,11 +93,51 @@ pub fn statement_gen(
 
             tracing::info!(target: "codegen", "FOUND INNER MACRO: {}", ir_macro.name);
 
+            // Outlined macros are emitted once after the code, so jump to the body and back
+            if ir_macro.outlined {
+                if !ir_macro.parameters.is_empty() {
+                    tracing::error!(target: "codegen", "OUTLINED MACRO \"{}\" TAKES ARGUMENTS", ir_macro.name);
+                    return Err(CodegenError {
+                        kind: CodegenErrorKind::InvalidArguments(format!(
+                            "Outlined macro \"{}\" can't take arguments",
+                            ir_macro.name
+                        )),
+                        span: mi.span.clone(),
+                        token: None,
+                    })
+                }
+
+                // PUSH2 <return> PUSH2 <body> JUMP JUMPDEST
+                let return_offset = *offset + 7;
+                bytes.push((*offset, Bytes(format!("{}{:04x}", Opcode::Push2, return_offset))));
+                jump_table.insert(
+                    *offset + 3,
+                    vec![Jump {
+                        label: outlined_label(&ir_macro.name),
+                        bytecode_index: 0,
+                        span: mi.span.clone(),
+                        scope_depth: 0,
+                    }],
+                );
+                bytes.push((*offset + 3, Bytes(format!("{}xxxx", Opcode::Push2))));
+                bytes.push((*offset + 6, Bytes(Opcode::Jump.to_string())));
+                bytes.push((return_offset, Bytes(Opcode::Jumpdest.to_string())));
+                *offset += 8;
+                expansions.push(MacroExpansion {
+                    macro_name: ir_macro.name.clone(),
+                    start: starting_offset,
+                    end: *offset,
+                    children: vec![],
+                });
+                return Ok(bytes)
+            }
+
             // Recurse into macro invocation
+            let expansion_id = expansions.len();
             scope.push(ir_macro.clone());
             mis.push((*offset, mi.clone()));
 
-            let mut res: BytecodeRes =
+            let res: BytecodeRes =
                 match Codegen::macro_to_bytecode(ir_macro.clone(), contract, scope, *offset, mis) {
                     Ok(r) => r,
                     Err(e) => {
@@ -59,7 +152,7 @@ pub fn statement_gen(
 
             // Set jump table values
             tracing::debug!(target: "codegen", "Unmatched jumps: {:?}", res.unmatched_jumps.iter().map(|uj| uj.label.clone()).collect::<Vec<String>>());
-            for j in res.unmatched_jumps.iter_mut() {
+            for mut j in caller_jumps(res.unmatched_jumps, &ir_macro.name)? {
                 let new_index = j.bytecode_index;
                 j.bytecode_index = 0;
                 let mut new_jumps = if let Some(jumps) = jump_table.get(&new_index) {
@@ -71,10 +164,16 @@ pub fn statement_gen(
                 jump_table.insert(new_index, new_jumps);
             }
             table_instances.extend(res.table_instances);
-            label_indices.extend(res.label_indices);
+            label_indices.extend(scope_labels(res.label_indices, &expansion_id.to_string()));
 
             // Increase offset by byte length of recursed macro
             *offset += res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
+            expansions.push(MacroExpansion {
+                macro_name: ir_macro.name.clone(),
+                start: starting_offset,
+                end: *offset,
+                children: res.expansions,
+            });
             // Add the macro's bytecode to the final result
             bytes = [bytes, res.bytes].concat()
         }
@@ -92,14 +191,21 @@ pub fn statement_gen(
             tracing::info!(target: "codegen", "RECURSE BYTECODE GOT LABEL CALL: {}", label);
             jump_table.insert(
                 *offset,
-                vec![Jump { label: label.to_string(), bytecode_index: 0, span: s.span.clone() }],
+                vec![Jump {
+                    label: label.to_string(),
+                    bytecode_index: 0,
+                    span: s.span.clone(),
+                    scope_depth: 0,
+                }],
             );
             bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
             *offset += 3;
         }
         StatementType::BuiltinFunctionCall(bf) => {
             // Generate code for a `BuiltinFunctionCall`
-            // __codesize, __tablesize, or __tablestart
+            // __codesize, __tablesize, __tablestart, __calldata_offset, __proxy_slot,
+            // __assert, __set_immutable, __linked, __pc, __mapping_slot, __minimal_proxy or
+            // __nonpayable_guard
             // TODO: Inline docs
             tracing::info!(target: "codegen", "RECURSE BYTECODE GOT BUILTIN FUNCTION CALL: {:?}", bf);
             match bf.kind {
@@ -141,6 +247,8 @@ pub fn statement_gen(
                         }
                     };
 
+                    // Only the expanded code counts, outlined bodies and tables are appended
+                    // later and metadata and constructor arguments once the artifact is built
                     let size = format_even_bytes(format!(
                         "{:02x}",
                         (res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2)
@@ -181,11 +289,317 @@ pub fn statement_gen(
                         label: bf.args[0].name.as_ref().unwrap().to_owned(),
                         bytecode_index: *offset,
                         span: bf.span.clone(),
+                        scope_depth: 0,
                     });
 
                     bytes.push((*offset, Bytes(format!("{}xxxx", Opcode::Push2))));
                     *offset += 3;
                 }
+                BuiltinFunctionKind::CalldataOffset => {
+                    let (func_name, index) = match (bf.args.first(), bf.args.get(1)) {
+                        (
+                            Some(Argument { name: Some(f), .. }),
+                            Some(Argument { name: Some(i), .. }),
+                        ) => (f, i),
+                        _ => {
+                            tracing::error!(target: "codegen", "__calldata_offset EXPECTS A FUNCTION AND A PARAMETER INDEX");
+                            return Err(CodegenError {
+                                kind: CodegenErrorKind::InvalidArguments(
+                                    "__calldata_offset expects a function and a parameter index"
+                                        .to_string(),
+                                ),
+                                span: bf.span.clone(),
+                                token: None,
+                            })
+                        }
+                    };
+
+                    let func = if let Some(f) = contract.find_function_by_name(func_name) {
+                        f
+                    } else {
+                        tracing::error!(target: "codegen", "MISSING FUNCTION PASSED TO __calldata_offset \"{}\"", func_name);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::MissingFunctionDefinition(
+                                func_name.to_string(),
+                            ),
+                            span: bf.span.clone(),
+                            token: None,
+                        })
+                    };
+
+                    let index = match index.parse::<usize>() {
+                        Ok(i) if i < func.inputs.len() => i,
+                        _ => {
+                            tracing::error!(target: "codegen", "INVALID PARAMETER INDEX \"{}\" FOR FUNCTION \"{}\"", index, func.name);
+                            return Err(CodegenError {
+                                kind: CodegenErrorKind::InvalidArguments(format!(
+                                    "\"{}\" is not a parameter index of \"{}\"",
+                                    index, func.name
+                                )),
+                                span: bf.span.clone(),
+                                token: None,
+                            })
+                        }
+                    };
+
+                    // Heads follow the 4 byte selector and each take up their static size
+                    let calldata_offset = 4 + func.inputs[..index]
+                        .iter()
+                        .map(|i| abi_head_size(i.arg_type.as_deref().unwrap_or_default()))
+                        .sum::<usize>();
+
+                    let offset_hex = format_even_bytes(format!("{:02x}", calldata_offset));
+                    let push_bytes = format!("{:02x}{}", 95 + offset_hex.len() / 2, offset_hex);
+
+                    *offset += push_bytes.len() / 2;
+                    bytes.push((starting_offset, Bytes(push_bytes)));
+                }
+                BuiltinFunctionKind::ProxySlot => {
+                    let name = match bf.args.first().and_then(|a| a.name.as_deref()) {
+                        Some(n) if EIP1967_SLOTS.contains(&n) => n,
+                        n => {
+                            tracing::error!(target: "codegen", "UNKNOWN EIP-1967 SLOT PASSED TO __proxy_slot \"{}\"", n.unwrap_or_default());
+                            return Err(CodegenError {
+                                kind: CodegenErrorKind::InvalidArguments(format!(
+                                    "__proxy_slot expects one of {}",
+                                    EIP1967_SLOTS.join(", ")
+                                )),
+                                span: bf.span.clone(),
+                                token: None,
+                            })
+                        }
+                    };
+
+                    // keccak256("eip1967.proxy.<name>") - 1
+                    let hash = ethers_core::utils::keccak256(format!("eip1967.proxy.{}", name));
+                    let slot = ethers_core::types::U256::from_big_endian(&hash) - 1;
+                    let slot_hex = format_even_bytes(format!("{:x}", slot));
+                    let push_bytes = format!("{:02x}{}", 95 + slot_hex.len() / 2, slot_hex);
+
+                    *offset += push_bytes.len() / 2;
+                    bytes.push((starting_offset, Bytes(push_bytes)));
+                }
+                BuiltinFunctionKind::Assert => {
+                    let terms = bf.args.iter().filter_map(|a| a.name.clone()).collect::<Vec<_>>();
+                    let condition = match terms.as_slice() {
+                        [lhs, op @ .., rhs] => format!("{} {} {}", lhs, op.concat(), rhs),
+                        _ => terms.concat(),
+                    };
+                    let holds = match terms.as_slice() {
+                        [value] => !constant_operand(value, contract, bf.span.clone())?.is_zero(),
+                        [lhs, op @ .., rhs] => {
+                            let lhs = constant_operand(lhs, contract, bf.span.clone())?;
+                            let rhs = constant_operand(rhs, contract, bf.span.clone())?;
+                            match op.concat().as_str() {
+                                "<" => lhs < rhs,
+                                "<=" => lhs <= rhs,
+                                ">" => lhs > rhs,
+                                ">=" => lhs >= rhs,
+                                "==" => lhs == rhs,
+                                _ => {
+                                    tracing::error!(target: "codegen", "INVALID __assert CONDITION \"{}\"", condition);
+                                    return Err(CodegenError {
+                                        kind: CodegenErrorKind::InvalidArguments(format!(
+                                            "Invalid __assert condition \"{}\"",
+                                            condition
+                                        )),
+                                        span: bf.span.clone(),
+                                        token: None,
+                                    })
+                                }
+                            }
+                        }
+                        [] => false,
+                    };
+                    if !holds {
+                        tracing::error!(target: "codegen", "ASSERTION FAILED \"{}\"", condition);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::AssertionFailed(condition),
+                            span: bf.span.clone(),
+                            token: None,
+                        })
+                    }
+                }
+                BuiltinFunctionKind::SetImmutable => {
+                    let name = bf.args.first().and_then(|a| a.name.clone()).unwrap_or_default();
+                    let index = contract
+                        .constants
+                        .iter()
+                        .filter(|c| c.value == ConstVal::Immutable)
+                        .position(|c| c.name == name);
+                    let Some(index) = index else {
+                        tracing::error!(target: "codegen", "NON IMMUTABLE PASSED TO __set_immutable \"{}\"", name);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::InvalidArguments(format!(
+                                "__set_immutable expects an IMMUTABLE() constant, got \"{}\"",
+                                name
+                            )),
+                            span: bf.span.clone(),
+                            token: None,
+                        })
+                    };
+
+                    // Stage the value in memory for the bootstrap to patch into the runtime
+                    let slot_hex = format_even_bytes(format!("{:02x}", index * 32));
+                    let push_bytes =
+                        format!("{:02x}{}{}", 95 + slot_hex.len() / 2, slot_hex, Opcode::Mstore);
+
+                    *offset += push_bytes.len() / 2;
+                    bytes.push((starting_offset, Bytes(push_bytes)));
+                }
+                BuiltinFunctionKind::Linked => {
+                    let Some(name) = bf.args.first().and_then(|a| a.name.as_deref()) else {
+                        tracing::error!(target: "codegen", "MISSING LIBRARY NAME PASSED TO __linked");
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::InvalidArguments(
+                                "__linked expects a library name".to_string(),
+                            ),
+                            span: bf.span.clone(),
+                            token: None,
+                        })
+                    };
+
+                    // The placeholder is as wide as the address it is patched with
+                    *offset += 21;
+                    bytes.push((
+                        starting_offset,
+                        Bytes(format!("{}{}", Opcode::Push20, link_placeholder(name))),
+                    ));
+                }
+                BuiltinFunctionKind::Pc => {
+                    // Jump to a label at the push itself, so the offset is filled in wherever
+                    // the macro ends up in the final layout
+                    let label = format!("__pc_{}", starting_offset);
+                    label_indices.insert(label.clone(), starting_offset);
+                    jump_table.insert(
+                        starting_offset,
+                        vec![Jump {
+                            label,
+                            bytecode_index: 0,
+                            span: bf.span.clone(),
+                            scope_depth: 0,
+                        }],
+                    );
+                    bytes.push((starting_offset, Bytes(format!("{}xxxx", Opcode::Push2))));
+                    *offset += 3;
+                }
+                BuiltinFunctionKind::MappingSlot => {
+                    let terms =
+                        bf.args.iter().filter_map(|a| a.name.as_deref()).collect::<Vec<_>>();
+                    let [base, key] = terms.as_slice() else {
+                        tracing::error!(target: "codegen", "__mapping_slot EXPECTS A CONSTANT BASE SLOT AND KEY");
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::InvalidArguments(
+                                "__mapping_slot expects a constant base slot and key, runtime keys \
+                                 must be hashed with sha3"
+                                    .to_string(),
+                            ),
+                            span: bf.span.clone(),
+                            token: None,
+                        })
+                    };
+                    let base = constant_operand(base, contract, bf.span.clone())?;
+                    let key = constant_operand(key, contract, bf.span.clone())?;
+
+                    // keccak256(key . baseSlot), as solc lays out mapping values
+                    let mut preimage = [0u8; 64];
+                    key.to_big_endian(&mut preimage[..32]);
+                    base.to_big_endian(&mut preimage[32..]);
+                    let slot = ethers_core::utils::keccak256(preimage);
+                    let push_bytes =
+                        Codegen::push_literal(&bytes32_to_string(&slot, false), bf.span.clone())?;
+
+                    *offset += push_bytes.len() / 2;
+                    bytes.push((starting_offset, Bytes(push_bytes)));
+                }
+                BuiltinFunctionKind::MinimalProxy => {
+                    let Some(implementation) = bf.args.first().and_then(|a| a.name.as_deref())
+                    else {
+                        tracing::error!(target: "codegen", "MISSING IMPLEMENTATION PASSED TO __minimal_proxy");
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::InvalidArguments(
+                                "__minimal_proxy expects an implementation address".to_string(),
+                            ),
+                            span: bf.span.clone(),
+                            token: None,
+                        })
+                    };
+
+                    // The clone jumps to its own absolute offset, so it only runs from byte 0
+                    if starting_offset != 0 {
+                        tracing::error!(target: "codegen", "__minimal_proxy PLACED AT OFFSET {}", starting_offset);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::MisplacedBuiltin(
+                                "__minimal_proxy".to_string(),
+                                starting_offset,
+                            ),
+                            span: bf.span.clone(),
+                            token: None,
+                        })
+                    }
+
+                    // Literals and constants are inlined, any other name is a library to link
+                    let known = implementation.starts_with("0x") ||
+                        implementation.chars().all(|c| c.is_ascii_digit()) ||
+                        contract.constants.iter().any(|c| c.name == implementation);
+                    let address = if known {
+                        let value = constant_operand(implementation, contract, bf.span.clone())?;
+                        if value.bits() > 160 {
+                            tracing::error!(target: "codegen", "__minimal_proxy IMPLEMENTATION \"{}\" IS NOT AN ADDRESS", implementation);
+                            return Err(CodegenError {
+                                kind: CodegenErrorKind::InvalidArguments(format!(
+                                    "__minimal_proxy implementation \"{}\" is wider than an address",
+                                    implementation
+                                )),
+                                span: bf.span.clone(),
+                                token: None,
+                            })
+                        }
+                        let mut word = [0u8; 32];
+                        value.to_big_endian(&mut word);
+                        hex::encode(&word[12..])
+                    } else {
+                        link_placeholder(implementation)
+                    };
+
+                    let clone =
+                        format!("363d3d373d3d3d363d73{}5af43d82803e903d91602b57fd5bf3", address);
+                    *offset += MINIMAL_PROXY_SIZE;
+                    bytes.push((starting_offset, Bytes(clone)));
+                }
+                BuiltinFunctionKind::NonpayableGuard => {
+                    // callvalue iszero <ok> jumpi 0x00 dup1 revert ok:
+                    let push_offset = starting_offset + 2;
+                    let ok_offset = starting_offset + 10;
+                    let label = format!("__nonpayable_{}", ok_offset);
+                    label_indices.insert(label.clone(), ok_offset);
+                    jump_table.insert(
+                        push_offset,
+                        vec![Jump {
+                            label,
+                            bytecode_index: 0,
+                            span: bf.span.clone(),
+                            scope_depth: 0,
+                        }],
+                    );
+                    bytes.push((
+                        starting_offset,
+                        Bytes(format!("{}{}", Opcode::Callvalue, Opcode::Iszero)),
+                    ));
+                    bytes.push((push_offset, Bytes(format!("{}xxxx", Opcode::Push2))));
+                    bytes.push((
+                        push_offset + 3,
+                        Bytes(format!(
+                            "{}{}00{}{}{}",
+                            Opcode::Jumpi,
+                            Opcode::Push1,
+                            Opcode::Dup1,
+                            Opcode::Revert,
+                            Opcode::Jumpdest
+                        )),
+                    ));
+                    *offset += 11;
+                }
             }
         }
         sty => {
@@ -201,3 +615,29 @@ pub fn statement_gen(
 
     Ok(bytes)
 }
+
+/// The EIP-1967 storage slots pushed by `__proxy_slot`
+const EIP1967_SLOTS: [&str; 4] = ["implementation", "admin", "beacon", "rollback"];
+
+/// Returns the size of a parameter's ABI head in bytes.
+///
+/// Dynamic types are encoded as a 32 byte offset, while static arrays are encoded in place.
+fn abi_head_size(arg_type: &str) -> usize {
+    if let Some(inner) = arg_type.strip_suffix(']') {
+        let (elem, len) = inner.rsplit_once('[').unwrap_or((inner, ""));
+        match len.parse::<usize>() {
+            Ok(n) if !is_dynamic_type(elem) => n * abi_head_size(elem),
+            _ => 0x20,
+        }
+    } else {
+        0x20
+    }
+}
+
+/// Whether an ABI type is dynamically sized
+fn is_dynamic_type(arg_type: &str) -> bool {
+    match arg_type.strip_suffix(']').and_then(|inner| inner.rsplit_once('[')) {
+        Some((elem, len)) => len.is_empty() || is_dynamic_type(elem),
+        None => matches!(arg_type, "string" | "bytes"),
+    }
+}
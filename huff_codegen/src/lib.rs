@@ -4,18 +4,28 @@
 #![forbid(unsafe_code)]
 #![forbid(where_clauses_object_safety)]
 
+use ethers_core::types::U256;
+use huff_lexer::Lexer;
+use huff_parser::Parser;
 use huff_utils::{
     abi::*,
     artifact::*,
     ast::*,
     bytecode::*,
-    error::CodegenError,
+    error::{CodegenError, CodegenWarning, CompilerError, WarningSink},
     prelude::{
-        bytes32_to_string, format_even_bytes, pad_n_bytes, CodegenErrorKind, FileSource, Span,
+        build_cfg, disassemble, exit_stack_depth, find_unbalanced_branches, format_even_bytes,
+        pad_n_bytes, runs_past, CodegenErrorKind, FileSource, FullFileSource, Opcode, Span, Token,
     },
-    types::EToken,
+    types::{EToken, PrimitiveEVMType},
+};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::Path,
+    sync::Arc,
 };
-use std::{collections::HashMap, fs, path::Path, sync::Arc};
 
 mod irgen;
 use crate::irgen::prelude::*;
@@ -45,18 +55,705 @@ pub struct Codegen {
     pub main_bytecode: Option<String>,
     /// Intermediate constructor bytecode store
     pub constructor_bytecode: Option<String>,
+    /// Runtime code size limit, defaults to the EIP-170 limit if not set
+    pub code_size_limit: Option<usize>,
+    /// Warnings raised during code generation
+    pub warnings: Vec<CodegenWarning>,
+    /// Whether artifact bytecode and runtime strings are `0x` prefixed
+    pub hex_prefix: bool,
+    /// How MAIN expanded, recorded by
+    /// [generate_main_bytecode_traced](Codegen::generate_main_bytecode_traced)
+    pub expansion_trace: Option<MacroExpansion>,
+    /// The names of `IMMUTABLE()` constants, in definition order, patched into the runtime by
+    /// the bootstrap
+    pub immutables: Vec<String>,
+    /// Whether the runtime ends with the compiler version, see
+    /// [version_metadata](Codegen::version_metadata)
+    pub metadata: bool,
+    /// Restricts the opcodes the contract may use, see
+    /// [check_opcode_policy](Codegen::check_opcode_policy)
+    pub opcode_policy: Option<OpcodePolicy>,
+    /// Constant values injected at compile time, see
+    /// [with_injected_constants](Codegen::with_injected_constants)
+    pub injected_constants: Vec<(String, Literal)>,
+    /// The runtime offset of each MAIN label, recorded by
+    /// [generate_main_bytecode_traced](Codegen::generate_main_bytecode_traced)
+    pub symbols: BTreeMap<String, usize>,
+    /// Receives warnings as they are raised, alongside [warnings](Codegen::warnings)
+    pub warning_sink: Option<Arc<dyn WarningSink>>,
+}
+
+/// An allowed or denied set of opcodes, for deployers that sandbox the code they accept
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum OpcodePolicy {
+    /// Only these opcodes may be used
+    Allowed(Vec<Opcode>),
+    /// These opcodes may not be used
+    Denied(Vec<Opcode>),
+}
+
+impl OpcodePolicy {
+    /// Whether the policy permits the given opcode
+    pub fn permits(&self, opcode: &Opcode) -> bool {
+        match self {
+            OpcodePolicy::Allowed(allowed) => allowed.contains(opcode),
+            OpcodePolicy::Denied(denied) => !denied.contains(opcode),
+        }
+    }
 }
 
+/// The maximum runtime code size allowed by EIP-170
+pub const EIP170_CODE_SIZE_LIMIT: usize = 24576;
+
+/// The scratch space reserved by convention for hashing, `0x00` up to `0x40`
+pub const SCRATCH_SPACE: (usize, usize) = (0x00, 0x40);
+
 impl Codegen {
     /// Public associated function to instantiate a new Codegen instance.
     pub fn new() -> Self {
-        Self { ast: None, artifact: None, main_bytecode: None, constructor_bytecode: None }
+        Self {
+            ast: None,
+            artifact: None,
+            main_bytecode: None,
+            constructor_bytecode: None,
+            code_size_limit: None,
+            warnings: vec![],
+            hex_prefix: false,
+            expansion_trace: None,
+            immutables: vec![],
+            metadata: false,
+            opcode_policy: None,
+            injected_constants: vec![],
+            symbols: BTreeMap::new(),
+            warning_sink: None,
+        }
+    }
+
+    /// Sets the runtime code size limit, for chains with a limit other than EIP-170's.
+    pub fn with_code_size_limit(mut self, limit: usize) -> Self {
+        self.code_size_limit = Some(limit);
+        self
+    }
+
+    /// Installs a sink that receives warnings as they are raised
+    pub fn with_warning_sink(mut self, sink: Arc<dyn WarningSink>) -> Self {
+        self.warning_sink = Some(sink);
+        self
+    }
+
+    /// Sets whether artifact bytecode and runtime strings are `0x` prefixed
+    pub fn with_hex_prefix(mut self, hex_prefix: bool) -> Self {
+        self.hex_prefix = hex_prefix;
+        self
+    }
+
+    /// Sets whether the runtime ends with the compiler version, see
+    /// [version_metadata](Codegen::version_metadata)
+    pub fn with_metadata(mut self, metadata: bool) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Restricts the opcodes the contract may use, see
+    /// [check_opcode_policy](Codegen::check_opcode_policy)
+    pub fn with_opcode_policy(mut self, policy: OpcodePolicy) -> Self {
+        self.opcode_policy = Some(policy);
+        self
+    }
+
+    /// Sets the AST the [IR stages](Codegen::to_ir_artifact) generate from
+    pub fn with_ast(mut self, contract: Contract) -> Self {
+        self.ast = Some(contract);
+        self
+    }
+
+    /// Parses constant values to inject from JSON mapping constant names to values, so
+    /// deployment parameters can live outside the source.
+    ///
+    /// Values are `0x` prefixed hex strings, decimal strings or non-negative integers.
+    pub fn parse_constants_json(json: &str) -> Result<Vec<(String, Literal)>, CodegenError> {
+        let error = |kind| CodegenError { kind, span: AstSpan(vec![]), token: None };
+        let constants: Map<String, Value> = serde_json::from_str(json)
+            .map_err(|e| error(CodegenErrorKind::InvalidConstantsJson(e.to_string())))?;
+
+        let mut parsed_constants = vec![];
+        for (name, value) in constants {
+            let parsed = match &value {
+                Value::String(s) => match s.strip_prefix("0x") {
+                    Some(hex) => U256::from_str_radix(hex, 16).ok(),
+                    None => U256::from_dec_str(s).ok(),
+                },
+                Value::Number(n) => n.as_u64().map(U256::from),
+                _ => None,
+            };
+            let Some(parsed) = parsed else {
+                return Err(error(CodegenErrorKind::InvalidConstantsJson(format!(
+                    "\"{}\" has invalid value {}",
+                    name, value
+                ))))
+            };
+            let mut literal = [0u8; 32];
+            parsed.to_big_endian(&mut literal);
+            parsed_constants.push((name, literal));
+        }
+        Ok(parsed_constants)
+    }
+
+    /// Sets constant values to inject, replacing earlier values of the same name. They are
+    /// merged into a contract with [apply_injected_constants](Codegen::apply_injected_constants).
+    pub fn with_injected_constants(mut self, constants: Vec<(String, Literal)>) -> Self {
+        for (name, literal) in constants {
+            self.injected_constants.retain(|(n, _)| *n != name);
+            self.injected_constants.push((name, literal));
+        }
+        self
+    }
+
+    /// Merges the injected constants into a contract, replacing source definitions of the same
+    /// name with a [ConstantOverridden](CodegenWarning::ConstantOverridden) warning and adding
+    /// the rest as new constants.
+    pub fn apply_injected_constants(&mut self, contract: &mut Contract) {
+        let mut warnings = vec![];
+        for (name, literal) in &self.injected_constants {
+            match contract.constants.iter_mut().find(|c| c.name == *name) {
+                Some(constant) => {
+                    warnings.push(CodegenWarning::ConstantOverridden {
+                        name: name.clone(),
+                        span: constant.span.clone(),
+                    });
+                    constant.value = ConstVal::Literal(*literal);
+                }
+                None => contract.constants.push(ConstantDefinition {
+                    name: name.clone(),
+                    value: ConstVal::Literal(*literal),
+                    source_file: None,
+                    span: AstSpan(vec![]),
+                }),
+            }
+        }
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Sets the immutables the bootstrap patches into the runtime from the contract's
+    /// `IMMUTABLE()` constants
+    pub fn with_immutables(mut self, contract: &Contract) -> Self {
+        self.immutables = contract
+            .constants
+            .iter()
+            .filter(|c| c.value == ConstVal::Immutable)
+            .map(|c| c.name.clone())
+            .collect();
+        self
+    }
+
+    /// Warns about jumps whose destination can't be statically resolved to a label.
+    ///
+    /// A `jump` or `jumpi` is resolved when it directly follows a label call or a macro argument
+    /// (which is expected to be a label). Anything else, e.g. a destination computed from
+    /// calldata, is a dynamic jump and is flagged with a [DynamicJump](CodegenWarning::DynamicJump)
+    /// warning.
+    pub fn lint_dynamic_jumps(&mut self, contract: &Contract) {
+        let mut warnings = vec![];
+        for macro_def in &contract.macros {
+            walk_statements(&macro_def.statements, &mut |statement, previous| {
+                let resolved = matches!(
+                    previous.map(|p| &p.ty),
+                    Some(StatementType::LabelCall(_) | StatementType::ArgCall(_))
+                );
+                if matches!(statement.ty, StatementType::Opcode(Opcode::Jump | Opcode::Jumpi)) &&
+                    !resolved
+                {
+                    warnings.push(CodegenWarning::DynamicJump {
+                        macro_name: macro_def.name.clone(),
+                        span: statement.span.clone(),
+                    });
+                }
+            });
+        }
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Warns about deprecated or discouraged opcodes, see
+    /// [is_deprecated](huff_utils::evm::Opcode::is_deprecated).
+    pub fn lint_deprecated_opcodes(&mut self, contract: &Contract) {
+        let mut warnings = vec![];
+        for macro_def in &contract.macros {
+            walk_statements(&macro_def.statements, &mut |statement, _| {
+                if let StatementType::Opcode(o) = &statement.ty {
+                    if o.is_deprecated() {
+                        warnings.push(CodegenWarning::DeprecatedOpcode {
+                            opcode: *o,
+                            macro_name: macro_def.name.clone(),
+                            span: statement.span.clone(),
+                        });
+                    }
+                }
+            });
+        }
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Errors with [ForbiddenOpcode](CodegenErrorKind::ForbiddenOpcode) on the first opcode in
+    /// the contract's macros that the [opcode policy](Codegen::opcode_policy) doesn't permit.
+    pub fn check_opcode_policy(&self, contract: &Contract) -> Result<(), CodegenError> {
+        let Some(policy) = &self.opcode_policy else { return Ok(()) };
+        for macro_def in &contract.macros {
+            let mut forbidden: Option<&Statement> = None;
+            walk_statements(&macro_def.statements, &mut |statement, _| {
+                if let StatementType::Opcode(o) = &statement.ty {
+                    if forbidden.is_none() && !policy.permits(o) {
+                        forbidden = Some(statement);
+                    }
+                }
+            });
+            if let Some(Statement { ty: StatementType::Opcode(o), span }) = forbidden {
+                tracing::error!(target: "codegen", "FORBIDDEN OPCODE \"{}\"", o);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::ForbiddenOpcode(*o),
+                    span: span.clone(),
+                    token: None,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Warns about JUMPIs whose two paths rejoin with different stack depths, see
+    /// [find_unbalanced_branches](huff_utils::cfg::find_unbalanced_branches).
+    ///
+    /// The check runs over the generated bytecode, so the warning carries the JUMPI's offset
+    /// rather than a source span.
+    pub fn lint_unbalanced_branches(&mut self, bytecode: &str) {
+        for imbalance in find_unbalanced_branches(bytecode) {
+            let warning = CodegenWarning::UnbalancedBranch {
+                offset: imbalance.offset,
+                jump_depth: imbalance.jump_depth,
+                fallthrough_depth: imbalance.fallthrough_depth,
+            };
+            self.push_warning(warning);
+        }
+    }
+
+    /// Warns when the generated CONSTRUCTOR bytecode falls through to the bootstrap code with
+    /// items left on the stack, see [exit_stack_depth](huff_utils::cfg::exit_stack_depth).
+    ///
+    /// Constructors that halt on every path, or whose depth can't be simulated, are not flagged.
+    pub fn lint_constructor_stack(&mut self, contract: &Contract, constructor_bytecode: &str) {
+        let warnings = exit_stack_depth(constructor_bytecode)
+            .filter(|depth| *depth > 0)
+            .map(|depth| CodegenWarning::ConstructorStackResidue { depth })
+            .into_iter()
+            .collect();
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Warns when MAIN can run off the end of its code instead of ending every path in RETURN,
+    /// REVERT, STOP, INVALID or SELFDESTRUCT, see [runs_past](huff_utils::cfg::runs_past).
+    ///
+    /// MAIN's end is taken from the [expansion_trace](Codegen::expansion_trace) when one was
+    /// recorded, so falling into the outlined macros and tables after it is caught too.
+    /// Otherwise the whole bytecode is treated as MAIN.
+    pub fn lint_main_termination(&mut self, contract: &Contract, main_bytecode: &str) {
+        let end = match &self.expansion_trace {
+            Some(trace) => trace.end,
+            None => main_bytecode.len() / 2,
+        };
+        let warnings = match runs_past(main_bytecode, end) {
+            true => vec![CodegenWarning::MainFallsThrough { offset: end }],
+            false => vec![],
+        };
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Warns about `mstore`, `mstore8` and `mload` at a constant offset outside the declared
+    /// memory.
+    ///
+    /// The scratch space, [SCRATCH_SPACE], is always declared, and more regions can be declared
+    /// with `// huff-memory: <start>..<end>` directives. Only offsets pushed by a literal or
+    /// constant directly before the opcode are checked, so this is a heuristic rather than a
+    /// guarantee.
+    pub fn lint_memory_regions(&mut self, contract: &Contract) {
+        let mut warnings = vec![];
+        for macro_def in &contract.macros {
+            walk_statements(&macro_def.statements, &mut |statement, previous| {
+                let StatementType::Opcode(o @ (Opcode::Mstore | Opcode::Mstore8 | Opcode::Mload)) =
+                    statement.ty
+                else {
+                    return
+                };
+                let Some(offset) = static_offset(previous, contract) else { return };
+                let width = if o == Opcode::Mstore8 { 1 } else { 32 };
+                let declared = std::iter::once(&SCRATCH_SPACE).chain(&contract.memory_regions).any(
+                    |(start, end)| {
+                        offset >= U256::from(*start) &&
+                            offset.saturating_add(width.into()) <= U256::from(*end)
+                    },
+                );
+                if !declared {
+                    warnings.push(CodegenWarning::UndeclaredMemoryAccess {
+                        opcode: o,
+                        offset: offset.try_into().unwrap_or(usize::MAX),
+                        macro_name: macro_def.name.clone(),
+                        span: statement.span.clone(),
+                    });
+                }
+            });
+        }
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Warns about `mload` and `mstore` at a constant offset that isn't a multiple of 0x20, as
+    /// word accesses in hand written assembly are almost always meant to be aligned.
+    ///
+    /// Like [lint_memory_regions](Codegen::lint_memory_regions), only offsets pushed by a literal
+    /// or constant directly before the opcode are checked.
+    pub fn lint_memory_alignment(&mut self, contract: &Contract) {
+        let mut warnings = vec![];
+        for macro_def in &contract.macros {
+            walk_statements(&macro_def.statements, &mut |statement, previous| {
+                let StatementType::Opcode(o @ (Opcode::Mstore | Opcode::Mload)) = statement.ty
+                else {
+                    return
+                };
+                if let Some(offset) =
+                    static_offset(previous, contract).filter(|o| !(o % 0x20).is_zero())
+                {
+                    warnings.push(CodegenWarning::MisalignedMemoryAccess {
+                        opcode: o,
+                        offset: offset.try_into().unwrap_or(usize::MAX),
+                        macro_name: macro_def.name.clone(),
+                        span: statement.span.clone(),
+                    });
+                }
+            });
+        }
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Warns about RETURNs in MAIN whose length differs from the size declared with a
+    /// `// huff-returns: <bytes>` directive.
+    ///
+    /// MAIN is followed into the macros it invokes. A length is only known when it is pushed by
+    /// a literal or constant right before the offset, as in `0x20 0x00 return`.
+    pub fn lint_return_size(&mut self, contract: &Contract) {
+        fn lint(
+            macro_def: &MacroDefinition,
+            contract: &Contract,
+            expected: usize,
+            visited: &mut Vec<String>,
+            warnings: &mut Vec<CodegenWarning>,
+        ) {
+            if visited.contains(&macro_def.name) {
+                return
+            }
+            visited.push(macro_def.name.clone());
+
+            let mut statements: Vec<&Statement> = vec![];
+            for statement in &macro_def.statements {
+                match &statement.ty {
+                    StatementType::Label(label) => {
+                        statements.push(statement);
+                        statements.extend(label.inner.iter());
+                    }
+                    _ => statements.push(statement),
+                }
+            }
+
+            for (i, statement) in statements.iter().enumerate() {
+                match &statement.ty {
+                    StatementType::Opcode(Opcode::Return) if i >= 2 => {
+                        let actual = match &statements[i - 2].ty {
+                            StatementType::Literal(l) => Some(U256::from_big_endian(l)),
+                            StatementType::Constant(name) => {
+                                constant_operand(name, contract, statement.span.clone()).ok()
+                            }
+                            _ => None,
+                        };
+                        if let Some(actual) = actual.filter(|a| *a != U256::from(expected)) {
+                            warnings.push(CodegenWarning::ReturnSizeMismatch {
+                                expected,
+                                actual: actual.try_into().unwrap_or(usize::MAX),
+                                macro_name: macro_def.name.clone(),
+                                span: statement.span.clone(),
+                            });
+                        }
+                    }
+                    StatementType::MacroInvocation(mi) => {
+                        if let Some(m) = contract.macros.iter().find(|m| m.name == mi.macro_name) {
+                            lint(m, contract, expected, visited, warnings);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let Some(expected) = contract.return_size else { return };
+        let Some(main) = contract.macros.iter().find(|m| m.name == "MAIN") else { return };
+        let mut warnings = vec![];
+        lint(main, contract, expected, &mut vec![], &mut warnings);
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Errors with [StorageSlotCollision](CodegenErrorKind::StorageSlotCollision) if a
+    /// `FREE_STORAGE_POINTER()` would be assigned a slot that an explicit slot constant already
+    /// uses.
+    ///
+    /// A literal constant is taken to be an explicit slot when it is pushed directly before an
+    /// `sload` or `sstore`, or passed to a macro argument that is, as in `STORE(OWNER_SLOT)`.
+    /// Slots pushed earlier and reached through `dup` or `swap` aren't tracked. This must run
+    /// before [derive_storage_pointers](huff_utils::ast::Contract::derive_storage_pointers),
+    /// which replaces free storage pointers with their assigned literals.
+    pub fn check_storage_slots(contract: &Contract) -> Result<(), CodegenError> {
+        let is_storage_op =
+            |s: &Statement| matches!(s.ty, StatementType::Opcode(Opcode::Sload | Opcode::Sstore));
+        // The arguments an invocation passes to parameters used as slots
+        let slot_args = |mi: &MacroInvocation, slot_params: &HashMap<String, Vec<String>>| {
+            let params = contract
+                .find_macro_by_name(&mi.macro_name)
+                .map(|m| m.parameters)
+                .unwrap_or_default();
+            let slots = slot_params.get(&mi.macro_name).cloned().unwrap_or_default();
+            mi.args
+                .iter()
+                .zip(params)
+                .filter(|(_, param)| param.name.as_ref().is_some_and(|n| slots.contains(n)))
+                .map(|(arg, _)| arg.clone())
+                .collect::<Vec<MacroArg>>()
+        };
+
+        // Find the macro parameters used as slots, following arguments passed down to other
+        // macros until nothing changes
+        let mut slot_params: HashMap<String, Vec<String>> = HashMap::new();
+        loop {
+            let mut found = vec![];
+            for macro_def in &contract.macros {
+                walk_statements(&macro_def.statements, &mut |statement, previous| {
+                    let params = match (&statement.ty, previous.map(|p| &p.ty)) {
+                        (_, Some(StatementType::ArgCall(param))) if is_storage_op(statement) => {
+                            vec![param.clone()]
+                        }
+                        (StatementType::MacroInvocation(mi), _) => slot_args(mi, &slot_params)
+                            .into_iter()
+                            .filter_map(|arg| match arg {
+                                MacroArg::ArgCall(param) => Some(param),
+                                _ => None,
+                            })
+                            .collect(),
+                        _ => vec![],
+                    };
+                    let known = slot_params.get(&macro_def.name).cloned().unwrap_or_default();
+                    found.extend(
+                        params
+                            .into_iter()
+                            .filter(|p| !known.contains(p))
+                            .map(|p| (macro_def.name.clone(), p)),
+                    );
+                });
+            }
+            if found.is_empty() {
+                break
+            }
+            for (macro_name, param) in found {
+                let params = slot_params.entry(macro_name).or_default();
+                if !params.contains(&param) {
+                    params.push(param);
+                }
+            }
+        }
+
+        let mut slot_names: Vec<String> = vec![];
+        for macro_def in &contract.macros {
+            walk_statements(&macro_def.statements, &mut |statement, previous| match (
+                &statement.ty,
+                previous.map(|p| &p.ty),
+            ) {
+                (_, Some(StatementType::Constant(name))) if is_storage_op(statement) => {
+                    slot_names.push(name.clone())
+                }
+                (StatementType::MacroInvocation(mi), _) => {
+                    slot_names.extend(slot_args(mi, &slot_params).into_iter().filter_map(|arg| {
+                        match arg {
+                            MacroArg::Ident(name) => Some(name),
+                            _ => None,
+                        }
+                    }))
+                }
+                _ => {}
+            });
+        }
+        let explicit: Vec<(&str, &Literal)> = contract
+            .constants
+            .iter()
+            .filter(|c| slot_names.contains(&c.name))
+            .filter_map(|c| match &c.value {
+                ConstVal::Literal(l) => Some((c.name.as_str(), l)),
+                _ => None,
+            })
+            .collect();
+
+        for (name, slot) in contract.storage_pointers() {
+            let Some(pointer) = contract
+                .constants
+                .iter()
+                .find(|c| c.name == name && matches!(c.value, ConstVal::FreeStoragePointer(_)))
+            else {
+                continue
+            };
+            if let Some((explicit_name, _)) = explicit.iter().find(|(_, l)| **l == slot) {
+                tracing::error!(target: "codegen", "FREE STORAGE POINTER \"{}\" COLLIDES WITH \"{}\"", name, explicit_name);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::StorageSlotCollision(name, explicit_name.to_string()),
+                    span: pointer.span.clone(),
+                    token: None,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Errors if two distinct function declarations share the same 4 byte selector.
+    ///
+    /// A dispatcher can't tell colliding functions apart, so calls to one would silently be
+    /// routed to the other.
+    pub fn check_selector_collisions(contract: &Contract) -> Result<(), CodegenError> {
+        let canonical = |f: &huff_utils::ast::Function| {
+            format!(
+                "{}({})",
+                f.name,
+                f.inputs
+                    .iter()
+                    .map(|i| i.arg_type.clone().unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )
+        };
+
+        for (i, f) in contract.functions.iter().enumerate() {
+            let collision = contract.functions[..i]
+                .iter()
+                .find(|other| other.signature == f.signature && canonical(other) != canonical(f));
+            if let Some(other) = collision {
+                tracing::error!(target: "codegen", "SELECTOR COLLISION BETWEEN \"{}\" AND \"{}\"", canonical(other), canonical(f));
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::SelectorCollision(canonical(other), canonical(f)),
+                    span: f.span.clone(),
+                    token: None,
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// Warns about macros that are never invoked.
+    ///
+    /// A macro is used when another macro invokes it or passes it to `__codesize`. `MAIN`,
+    /// `CONSTRUCTOR` and `@facet` macros are entry points and always used.
+    pub fn lint_unused_macros(&mut self, contract: &Contract) {
+        let mut used = vec!["MAIN".to_string(), "CONSTRUCTOR".to_string()];
+        for macro_def in &contract.macros {
+            walk_statements(&macro_def.statements, &mut |statement, _| match &statement.ty {
+                StatementType::MacroInvocation(mi) => used.push(mi.macro_name.clone()),
+                StatementType::BuiltinFunctionCall(bf) => {
+                    used.extend(bf.args.iter().filter_map(|a| a.name.clone()))
+                }
+                _ => {}
+            });
+        }
+        let warnings = contract
+            .macros
+            .iter()
+            .filter(|m| !m.facet && !used.contains(&m.name))
+            .map(|m| CodegenWarning::UnusedMacro {
+                macro_name: m.name.clone(),
+                span: m.span.clone(),
+            })
+            .collect();
+        self.report_warnings(contract, warnings);
+    }
+
+    /// Records lint warnings, dropping those disabled on the macro they were raised in
+    fn report_warnings(&mut self, contract: &Contract, warnings: Vec<CodegenWarning>) {
+        for warning in warnings {
+            let suppressed = warning
+                .macro_name()
+                .and_then(|name| contract.macros.iter().find(|m| m.name == name))
+                .is_some_and(|m| m.suppressed_warnings.iter().any(|w| w == warning.name()));
+            if suppressed {
+                tracing::debug!(target: "codegen", "SUPPRESSED WARNING: {}", warning);
+                continue
+            }
+            self.push_warning(warning);
+        }
+    }
+
+    /// Records a warning, logging it and passing it to the [warning_sink](Codegen::warning_sink)
+    fn push_warning(&mut self, warning: CodegenWarning) {
+        tracing::warn!(target: "codegen", "{}", warning);
+        if let Some(sink) = &self.warning_sink {
+            sink.warn(&warning);
+        }
+        self.warnings.push(warning);
     }
 
     /// Generates main bytecode from a Contract AST
     pub fn generate_main_bytecode(contract: &Contract) -> Result<String, CodegenError> {
-        // Find the main macro
-        let m_macro = Codegen::get_macro_by_name("MAIN", contract)?;
+        Ok(Codegen::expand_main(contract)?.0)
+    }
+
+    /// Generates the standalone runtime of a `@facet` macro, expanded like MAIN with outlined
+    /// macros and tables appended
+    pub fn generate_facet_bytecode(
+        contract: &Contract,
+        facet_name: &str,
+    ) -> Result<String, CodegenError> {
+        Ok(Codegen::expand_entry(contract, facet_name)?.0)
+    }
+
+    /// Generates main bytecode from a Contract AST, recording the tree of macro expansions
+    /// with their byte ranges in [expansion_trace](Codegen::expansion_trace) and the offset of
+    /// each label in [symbols](Codegen::symbols).
+    pub fn generate_main_bytecode_traced(
+        &mut self,
+        contract: &Contract,
+    ) -> Result<String, CodegenError> {
+        let (bytecode, trace, labels) = Codegen::expand_main(contract)?;
+        self.expansion_trace = Some(trace);
+        self.symbols = source_symbols(labels);
+        Ok(bytecode)
+    }
+
+    /// Expands MAIN, returning the main bytecode, the expansion trace rooted at MAIN and the
+    /// label offsets
+    fn expand_main(
+        contract: &Contract,
+    ) -> Result<(String, MacroExpansion, LabelIndices), CodegenError> {
+        Codegen::expand_entry(contract, "MAIN")
+    }
+
+    /// Expands a runtime entry point, returning its bytecode, the expansion trace rooted at it
+    /// and the label offsets
+    fn expand_entry(
+        contract: &Contract,
+        entry: &str,
+    ) -> Result<(String, MacroExpansion, LabelIndices), CodegenError> {
+        let (bytecode_res, trace) = Codegen::expand_entry_code(contract, entry)?;
+        let labels = bytecode_res.label_indices.clone();
+
+        // Generate the fully baked bytecode
+        Ok((Codegen::gen_table_bytecode(bytecode_res, contract)?, trace, labels))
+    }
+
+    /// Expands a runtime entry point and appends the outlined macros, leaving tables to be laid
+    /// out
+    fn expand_entry_code(
+        contract: &Contract,
+        entry: &str,
+    ) -> Result<(BytecodeRes, MacroExpansion), CodegenError> {
+        Codegen::check_selector_collisions(contract)?;
+
+        // Find the entry macro
+        let m_macro = Codegen::get_macro_by_name(entry, contract)?;
 
         // For each MacroInvocation Statement, recurse into bytecode
         let bytecode_res: BytecodeRes = Codegen::macro_to_bytecode(
@@ -66,13 +763,23 @@ impl Codegen {
             0,
             &mut Vec::default(),
         )?;
-
-        // Generate the fully baked bytecode
-        Codegen::gen_table_bytecode(bytecode_res, contract)
+        let trace = MacroExpansion {
+            macro_name: entry.to_string(),
+            start: 0,
+            end: bytecode_res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2,
+            children: bytecode_res.expansions.clone(),
+        };
+        Ok((Codegen::append_outlined_macros(bytecode_res, contract)?, trace))
     }
 
     /// Generates constructor bytecode from a Contract AST
     pub fn generate_constructor_bytecode(contract: &Contract) -> Result<String, CodegenError> {
+        let bytecode_res = Codegen::expand_constructor_code(contract)?;
+        Codegen::gen_constructor_table_bytecode(bytecode_res, contract)
+    }
+
+    /// Expands the CONSTRUCTOR and appends the outlined macros, leaving tables to be laid out
+    fn expand_constructor_code(contract: &Contract) -> Result<BytecodeRes, CodegenError> {
         // Find the constructor macro
         let c_macro = Codegen::get_macro_by_name("CONSTRUCTOR", contract)?;
 
@@ -84,9 +791,462 @@ impl Codegen {
             0,
             &mut Vec::default(),
         )?;
+        Codegen::append_outlined_macros(bytecode_res, contract)
+    }
+
+    /// Lays out the tables an expanded CONSTRUCTOR references
+    fn gen_constructor_table_bytecode(
+        bytecode_res: BytecodeRes,
+        contract: &Contract,
+    ) -> Result<String, CodegenError> {
+        // The constructor only lays out the tables it references, and those can't jump to labels
+        // that only exist in the runtime
+        let tables = contract
+            .tables
+            .iter()
+            .filter(|t| bytecode_res.table_instances.iter().any(|j| j.label == t.name))
+            .cloned()
+            .collect::<Vec<_>>();
+        for table in &tables {
+            let runtime_label = table.statements.iter().find(|s| {
+                matches!(&s.ty, StatementType::LabelCall(label)
+                    if table_label_index(&bytecode_res.label_indices, label).is_none())
+            });
+            if let Some(s) = runtime_label {
+                tracing::error!(target: "codegen", "CONSTRUCTOR USES RUNTIME TABLE \"{}\"", table.name);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::TableScopeMismatch(table.name.clone()),
+                    span: s.span.clone(),
+                    token: None,
+                })
+            }
+        }
 
         // Generate the bytecode return string
-        Codegen::gen_table_bytecode(bytecode_res, contract)
+        Codegen::gen_table_bytecode(bytecode_res, &Contract { tables, ..contract.clone() })
+    }
+
+    /// Expands MAIN and CONSTRUCTOR of the [ast](Codegen::ast) into an [IrArtifact], the first
+    /// of two codegen stages. Invocations, labels and jumps are resolved, but no tables are laid
+    /// out, so external optimizers can rewrite the code before
+    /// [from_ir_artifact](Codegen::from_ir_artifact) finishes the layout. Jumps to undefined
+    /// labels are an [UnmatchedJumpLabel](CodegenErrorKind::UnmatchedJumpLabel) error.
+    pub fn to_ir_artifact(&self) -> Result<IrArtifact, CodegenError> {
+        let contract = self.ast.as_ref().ok_or(CodegenError {
+            kind: CodegenErrorKind::MissingAst,
+            span: AstSpan(vec![]),
+            token: None,
+        })?;
+
+        // Unmatched jumps aren't carried by the IR, so they're reported before it's built
+        let (main, _) = Codegen::expand_entry_code(contract, "MAIN")?;
+        Codegen::check_unmatched_jumps(&main.unmatched_jumps)?;
+        let constructor = match Codegen::expand_constructor_code(contract) {
+            Ok(res) => {
+                Codegen::check_unmatched_jumps(&res.unmatched_jumps)?;
+                Some(res.into())
+            }
+            Err(e) if e.kind == CodegenErrorKind::MissingMacroDefinition("CONSTRUCTOR".into()) => {
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        Ok(IrArtifact { main: main.into(), constructor })
+    }
+
+    /// Lays out the tables of an [IrArtifact], the second codegen stage, returning the main and
+    /// constructor bytecode. The tables are taken from the [ast](Codegen::ast).
+    pub fn from_ir_artifact(&self, ir: &IrArtifact) -> Result<(String, String), CodegenError> {
+        let contract = self.ast.as_ref().ok_or(CodegenError {
+            kind: CodegenErrorKind::MissingAst,
+            span: AstSpan(vec![]),
+            token: None,
+        })?;
+
+        let main_bytecode = Codegen::gen_table_bytecode(ir.main.clone().into(), contract)?;
+        let constructor_bytecode = match &ir.constructor {
+            Some(c) => Codegen::gen_constructor_table_bytecode(c.clone().into(), contract)?,
+            None => String::new(),
+        };
+        Ok((main_bytecode, constructor_bytecode))
+    }
+
+    /// Pushes a hex value, erroring with [PushTooLarge](CodegenErrorKind::PushTooLarge) if it
+    /// is wider than the 32 bytes PUSH32 can hold
+    pub fn push_literal(hex_literal: &str, span: AstSpan) -> Result<String, CodegenError> {
+        let width = hex_literal.len().div_ceil(2);
+        if width > 32 {
+            tracing::error!(target: "codegen", "PUSH OF {} BYTES EXCEEDS PUSH32", width);
+            return Err(CodegenError {
+                kind: CodegenErrorKind::PushTooLarge(width),
+                span,
+                token: None,
+            })
+        }
+        Ok(format!("{:02x}{}", 95 + width, pad_n_bytes(hex_literal, width)))
+    }
+
+    /// Generates the bytecode for a single macro, with nested invocations resolved.
+    ///
+    /// No tables are appended and jump labels are resolved relative to the start of the macro,
+    /// so the output matches the macro's portion of the full runtime when it contains no jumps.
+    /// Jumps to labels outside the macro are an
+    /// [UnmatchedJumpLabel](CodegenErrorKind::UnmatchedJumpLabel) error.
+    pub fn macro_bytecode(contract: &Contract, macro_name: &str) -> Result<String, CodegenError> {
+        let macro_def = Codegen::get_macro_by_name(macro_name, contract)?;
+
+        let bytecode_res: BytecodeRes = Codegen::macro_to_bytecode(
+            macro_def.clone(),
+            contract,
+            &mut vec![macro_def],
+            0,
+            &mut Vec::default(),
+        )?;
+        Codegen::check_unmatched_jumps(&bytecode_res.unmatched_jumps)?;
+
+        Ok(bytecode_res.bytes.into_iter().map(|(_, b)| b.0).collect::<String>())
+    }
+
+    /// Dumps the intermediate representation of a macro, one IRByte per line.
+    ///
+    /// This is the macro before lowering, so constants, argument calls and nested statements
+    /// such as macro invocations and labels are shown unresolved.
+    pub fn macro_ir(contract: &Contract, macro_name: &str) -> Result<String, CodegenError> {
+        let macro_def = Codegen::get_macro_by_name(macro_name, contract)?;
+        Ok(macro_def.to_irbytecode()?.to_string())
+    }
+
+    /// Verifies that every statically known jump lands on a JUMPDEST.
+    ///
+    /// Decodes `PUSHn <target> JUMP` and `PUSHn <target> JUMPI` patterns and checks each target
+    /// is inside the bytecode and points to a JUMPDEST that isn't part of PUSH data.
+    pub fn verify_jumpdests(bytecode: &str) -> Result<(), CodegenError> {
+        let invalid = |kind: CodegenErrorKind| CodegenError {
+            kind,
+            span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
+            token: None,
+        };
+        let bytes = hex::decode(bytecode.strip_prefix("0x").unwrap_or(bytecode))
+            .map_err(|e| invalid(CodegenErrorKind::InvalidBytecode(e.to_string())))?;
+
+        // Collect JUMPDESTs and static jump targets, skipping over PUSH data
+        let mut jumpdests = vec![false; bytes.len()];
+        let mut targets: Vec<usize> = vec![];
+        let mut pc = 0;
+        while pc < bytes.len() {
+            let op = bytes[pc];
+            if op == 0x5b {
+                jumpdests[pc] = true;
+            }
+            if (0x60..=0x7f).contains(&op) {
+                let end = pc + 1 + (op - 0x5f) as usize;
+                if matches!(bytes.get(end), Some(0x56) | Some(0x57)) {
+                    let target = bytes[pc + 1..end]
+                        .iter()
+                        .fold(0usize, |acc, b| acc.saturating_mul(256).saturating_add(*b as usize));
+                    targets.push(target);
+                }
+                pc = end;
+            } else {
+                pc += 1;
+            }
+        }
+
+        match targets.into_iter().find(|t| !jumpdests.get(*t).copied().unwrap_or(false)) {
+            Some(target) => {
+                tracing::error!(target: "codegen", "Jump target {:#x} is not a JUMPDEST", target);
+                Err(invalid(CodegenErrorKind::InvalidJumpTarget(target)))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Removes basic blocks that are unreachable from the entry point.
+    ///
+    /// Removing code shifts every following offset, so the pass only runs when all code offsets
+    /// are known: each jump must take its target from the PUSH directly before it, and no JUMPDEST
+    /// offset may be pushed anywhere else (as it is for jump tables or stored return addresses).
+    /// Bytecode that reads its own code or program counter (CODESIZE, CODECOPY, PC) is left alone
+    /// too. When any of these don't hold, the bytecode is returned unchanged.
+    pub fn eliminate_dead_code(bytecode: &str) -> String {
+        let bytes = match hex::decode(bytecode.strip_prefix("0x").unwrap_or(bytecode)) {
+            Ok(b) => b,
+            Err(_) => return bytecode.to_string(),
+        };
+
+        // Decode instructions as (offset, opcode, push width)
+        let mut instructions: Vec<(usize, u8, usize)> = vec![];
+        let mut pc = 0;
+        while pc < bytes.len() {
+            let op = bytes[pc];
+            let width = if (0x60..=0x7f).contains(&op) { (op - 0x5f) as usize } else { 0 };
+            instructions.push((pc, op, width));
+            pc += 1 + width;
+        }
+        if pc > bytes.len() {
+            return bytecode.to_string()
+        }
+        let push_value = |(offset, _, width): (usize, u8, usize)| {
+            bytes[offset + 1..offset + 1 + width]
+                .iter()
+                .fold(0usize, |acc, b| acc.saturating_mul(256).saturating_add(*b as usize))
+        };
+        let jumpdests: Vec<usize> =
+            instructions.iter().filter(|i| i.1 == 0x5b).map(|i| i.0).collect();
+
+        // Collect the PUSHes feeding jumps, bailing out if any code offset is used otherwise
+        let mut jump_pushes: Vec<(usize, usize, usize)> = vec![];
+        for (index, &instruction) in instructions.iter().enumerate() {
+            let next = instructions.get(index + 1).map(|n| n.1);
+            match instruction.1 {
+                0x38 | 0x39 | 0x58 => return bytecode.to_string(),
+                0x56 | 0x57 => {
+                    let static_push = index
+                        .checked_sub(1)
+                        .map(|p| instructions[p])
+                        .filter(|p| p.2 > 0 && jumpdests.contains(&push_value(*p)));
+                    if static_push.is_none() {
+                        return bytecode.to_string()
+                    }
+                }
+                0x60..=0x7f if jumpdests.contains(&push_value(instruction)) => {
+                    if !matches!(next, Some(0x56 | 0x57)) {
+                        return bytecode.to_string()
+                    }
+                    jump_pushes.push((instruction.0, instruction.2, push_value(instruction)));
+                }
+                _ => {}
+            }
+        }
+
+        // Walk the control flow graph from the entry block
+        let cfg = build_cfg(&hex::encode(&bytes));
+        let mut reachable: Vec<usize> = vec![];
+        let mut stack: Vec<usize> = cfg.blocks.first().map(|b| b.start).into_iter().collect();
+        while let Some(start) = stack.pop() {
+            if reachable.contains(&start) {
+                continue
+            }
+            reachable.push(start);
+            if let Some(block) = cfg.block_at(start) {
+                stack.extend(block.successors.iter().copied());
+            }
+        }
+        if reachable.len() == cfg.blocks.len() {
+            return bytecode.to_string()
+        }
+
+        // Map the surviving offsets into their new positions and rewrite the jump targets
+        let mut relocations: HashMap<usize, usize> = HashMap::new();
+        let mut optimized: Vec<u8> = vec![];
+        for block in cfg.blocks.iter().filter(|b| reachable.contains(&b.start)) {
+            relocations.insert(block.start, optimized.len());
+            optimized.extend_from_slice(&bytes[block.start..block.end]);
+        }
+        for (offset, width, target) in jump_pushes {
+            let block_start = cfg.blocks.iter().rev().find(|b| b.start <= offset).map(|b| b.start);
+            let (Some(new_block_start), Some(new_target)) =
+                (block_start.and_then(|b| relocations.get(&b)), relocations.get(&target))
+            else {
+                continue
+            };
+            let new_offset = new_block_start + offset - block_start.unwrap_or_default();
+            let encoded =
+                hex::decode(pad_n_bytes(&format!("{:x}", new_target), width)).unwrap_or_default();
+            optimized[new_offset + 1..new_offset + 1 + width].copy_from_slice(&encoded);
+        }
+
+        hex::encode(optimized)
+    }
+
+    /// Compiles a bare sequence of opcodes and literals into runtime bytecode.
+    ///
+    /// The snippet is wrapped in an implicit `MAIN` macro, so `0x01 0x02 add` compiles to
+    /// `6001600201`. Useful for quick experiments without writing a full contract.
+    pub fn compile_snippet(opcodes: &str) -> Result<String, CodegenError> {
+        let source = format!("#define macro MAIN() = takes(0) returns(0) {{\n{}\n}}", opcodes);
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+
+        // Lex the snippet
+        let tokens =
+            Lexer::new(flattened_source).collect::<Result<Vec<Token>, _>>().map_err(|e| {
+                CodegenError {
+                    kind: CodegenErrorKind::InvalidSnippet(format!("{:?}", e.kind)),
+                    span: AstSpan(vec![e.span]),
+                    token: None,
+                }
+            })?;
+
+        // Parse the snippet
+        let mut contract = Parser::new(tokens, None).parse().map_err(|e| CodegenError {
+            kind: CodegenErrorKind::InvalidSnippet(format!("{:?}", e.kind)),
+            span: e.spans,
+            token: None,
+        })?;
+        contract.derive_storage_pointers();
+        contract.size_code_tables();
+
+        Codegen::generate_main_bytecode(&contract)
+    }
+
+    /// Compiles a parsed contract into an [Artifact] with its ABI. This is the pipeline shared by
+    /// the compiler and [compile_standard_json](Codegen::compile_standard_json).
+    ///
+    /// Storage slots are checked and derived and injected constants applied, resizing the code
+    /// tables that inline them. The opcode policy and lints run, then MAIN and CONSTRUCTOR are
+    /// generated. With `optimize`, the runtime goes
+    /// through [dead code elimination](Codegen::eliminate_dead_code). A missing CONSTRUCTOR
+    /// compiles to an empty constructor unless constructor arguments are given, which are
+    /// encoded against the [declared input types](Codegen::constructor_input_types).
+    pub fn compile_contract(
+        &mut self,
+        mut contract: Contract,
+        file: Arc<FileSource>,
+        constructor_args: Vec<String>,
+        optimize: bool,
+    ) -> Result<Artifact, CodegenError> {
+        Codegen::check_storage_slots(&contract)?;
+        contract.derive_storage_pointers();
+        self.apply_injected_constants(&mut contract);
+        contract.size_code_tables();
+        *self = std::mem::take(self).with_immutables(&contract);
+
+        self.check_opcode_policy(&contract)?;
+        self.lint_dynamic_jumps(&contract);
+        self.lint_deprecated_opcodes(&contract);
+        self.lint_unused_macros(&contract);
+        self.lint_memory_regions(&contract);
+        self.lint_memory_alignment(&contract);
+        self.lint_return_size(&contract);
+
+        let main_bytecode = self.generate_main_bytecode_traced(&contract)?;
+        tracing::info!(target: "codegen", "MAIN BYTECODE GENERATED [{}]", main_bytecode);
+        self.lint_unbalanced_branches(&main_bytecode);
+        self.lint_main_termination(&contract, &main_bytecode);
+        let main_bytecode = match optimize {
+            true => {
+                let optimized = Codegen::eliminate_dead_code(&main_bytecode);
+                // Removed blocks shift the labels after them
+                if optimized != main_bytecode {
+                    self.symbols.clear();
+                }
+                optimized
+            }
+            false => main_bytecode,
+        };
+
+        let constructor_bytecode = match Codegen::generate_constructor_bytecode(&contract) {
+            Ok(cb) => cb,
+            Err(e)
+                if constructor_args.is_empty() &&
+                    e.kind == CodegenErrorKind::MissingMacroDefinition("CONSTRUCTOR".into()) =>
+            {
+                tracing::warn!(target: "codegen", "Contract has no \"CONSTRUCTOR\" macro definition!");
+                String::new()
+            }
+            Err(e) => {
+                tracing::error!(target: "codegen", "Constructor Failed with CodegenError: {:?}", e);
+                return Err(e)
+            }
+        };
+        tracing::info!(target: "codegen", "CONSTRUCTOR BYTECODE GENERATED [{}]", constructor_bytecode);
+        self.lint_constructor_stack(&contract, &constructor_bytecode);
+
+        tracing::info!(target: "codegen", "ENCODING {} INPUTS", constructor_args.len());
+        let encoded_args = Codegen::encode_constructor_args(
+            constructor_args,
+            &Codegen::constructor_input_types(&contract),
+        )?;
+
+        let mut artifact = self.churn(file, encoded_args, &main_bytecode, &constructor_bytecode)?;
+        artifact.abi = Some(self.abi_gen(contract, None)?);
+        Ok(artifact)
+    }
+
+    /// Compiles a solc-style standard-json input into a standard-json output.
+    ///
+    /// Every entry of `sources` is compiled on its own with its `content`, so `#include`s are not
+    /// resolved. Setting `settings.optimizer.enabled` applies
+    /// [dead code elimination](Codegen::eliminate_dead_code). Compilation failures and lint
+    /// warnings are reported in the output's `errors` rather than failing the whole call.
+    pub fn compile_standard_json(input: &str) -> String {
+        let input: Value = match serde_json::from_str(input) {
+            Ok(input) => input,
+            Err(e) => {
+                let error = standard_json_error("JSONError", "error", &e.to_string(), None);
+                return json!({ "errors": [error] }).to_string()
+            }
+        };
+        let optimize = input["settings"]["optimizer"]["enabled"].as_bool().unwrap_or(false);
+
+        let mut contracts = Map::new();
+        let mut sources = Map::new();
+        let mut errors = vec![];
+        let empty = Map::new();
+        let input_sources = input["sources"].as_object().unwrap_or(&empty);
+        for (id, (path, source)) in input_sources.iter().enumerate() {
+            sources.insert(path.clone(), json!({ "id": id }));
+            let content = match source["content"].as_str() {
+                Some(content) => content,
+                None => {
+                    let message = format!("Source \"{}\" has no content", path);
+                    errors.push(standard_json_error("JSONError", "error", &message, Some(path)));
+                    continue
+                }
+            };
+
+            match Codegen::compile_standard_json_source(path, content, optimize) {
+                Ok((output, warnings)) => {
+                    let name = Path::new(path).file_stem().unwrap_or_default().to_string_lossy();
+                    let mut contract = Map::new();
+                    contract.insert(name.to_string(), output);
+                    contracts.insert(path.clone(), Value::Object(contract));
+                    errors.extend(warnings.iter().map(|w| {
+                        standard_json_error("Warning", "warning", &w.to_string(), Some(path))
+                    }));
+                }
+                Err((ty, message)) => {
+                    errors.push(standard_json_error(ty, "error", &message, Some(path)))
+                }
+            }
+        }
+
+        json!({ "contracts": contracts, "errors": errors, "sources": sources }).to_string()
+    }
+
+    /// Compiles a single standard-json source into its `abi` and `evm` output, returning the
+    /// error type and message on failure
+    fn compile_standard_json_source(
+        path: &str,
+        content: &str,
+        optimize: bool,
+    ) -> Result<(Value, Vec<CodegenWarning>), (&'static str, String)> {
+        let flattened_source = FullFileSource { source: content, file: None, spans: vec![] };
+        let tokens = Lexer::new(flattened_source)
+            .collect::<Result<Vec<Token>, _>>()
+            .map_err(|e| ("LexicalError", CompilerError::LexicalError(e).to_string()))?;
+        let contract = Parser::new(tokens, None)
+            .parse()
+            .map_err(|e| ("ParserError", CompilerError::ParserError(e).to_string()))?;
+
+        let file = Arc::new(FileSource {
+            path: path.to_string(),
+            source: Some(content.to_string()),
+            ..Default::default()
+        });
+        let mut cg = Codegen::new();
+        let artifact = cg
+            .compile_contract(contract, file, vec![], optimize)
+            .map_err(|e| ("CodegenError", CompilerError::CodegenError(e).to_string()))?;
+
+        let output = json!({
+            "abi": artifact.abi.map(|abi| abi.to_solc_json()).unwrap_or_default(),
+            "evm": {
+                "bytecode": { "object": artifact.bytecode },
+                "deployedBytecode": { "object": artifact.runtime },
+            },
+        });
+        Ok((output, cg.warnings))
     }
 
     /// Helper function to find a macro or generate a CodegenError
@@ -105,6 +1265,107 @@ impl Codegen {
             })
         }
     }
+    /// Appends the shared bodies of invoked `@outline` macros after the generated code.
+    ///
+    /// A body is entered with the return address above the macro's inputs. Its prologue rotates
+    /// the return address beneath the `takes` inputs, and the epilogue rotates it back above the
+    /// `returns` outputs before jumping back to the call site. Outlined macros invoked from other
+    /// outlined bodies are appended as they are found.
+    pub(crate) fn append_outlined_macros(
+        mut res: BytecodeRes,
+        contract: &Contract,
+    ) -> Result<BytecodeRes, CodegenError> {
+        let mut offset = res.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
+
+        while let Some(jump) = res
+            .unmatched_jumps
+            .iter()
+            .find(|j| {
+                j.label.starts_with(&outlined_label("")) &&
+                    !res.label_indices.contains_key(&j.label)
+            })
+            .cloned()
+        {
+            let name = jump.label.trim_start_matches(&outlined_label("")).to_string();
+            let macro_def = Codegen::get_macro_by_name(&name, contract)?;
+            if macro_def.takes > 16 || macro_def.returns > 16 {
+                tracing::error!(target: "codegen", "OUTLINED MACRO \"{}\" EXCEEDS 16 STACK ITEMS", name);
+                return Err(CodegenError {
+                    kind: CodegenErrorKind::InvalidArguments(format!(
+                        "Outlined macro \"{}\" can take and return at most 16 stack items",
+                        name
+                    )),
+                    span: jump.span,
+                    token: None,
+                })
+            }
+            tracing::info!(target: "codegen", "APPENDING OUTLINED MACRO \"{}\" AT {}", name, offset);
+            res.label_indices.insert(jump.label, offset);
+
+            // JUMPDEST, then SWAPn..SWAP1 moves the return address beneath the inputs
+            let prologue = std::iter::once(Opcode::Jumpdest.to_string())
+                .chain((1..=macro_def.takes).rev().map(|n| format!("{:02x}", 0x8f + n)))
+                .collect::<String>();
+            res.bytes.push((offset, Bytes(prologue.clone())));
+            offset += prologue.len() / 2;
+
+            let body = Codegen::macro_to_bytecode(
+                macro_def.clone(),
+                contract,
+                &mut vec![macro_def.clone()],
+                offset,
+                &mut Vec::default(),
+            )?;
+            offset += body.bytes.iter().map(|(_, b)| b.0.len()).sum::<usize>() / 2;
+            res.bytes.extend(body.bytes);
+            res.label_indices.extend(scope_labels(body.label_indices, &outlined_label(&name)));
+            res.unmatched_jumps.extend(caller_jumps(body.unmatched_jumps, &name)?);
+            res.table_instances.extend(body.table_instances);
+
+            // SWAP1..SWAPn brings the return address back above the outputs
+            let epilogue = (1..=macro_def.returns)
+                .map(|n| format!("{:02x}", 0x8f + n))
+                .chain(std::iter::once(Opcode::Jump.to_string()))
+                .collect::<String>();
+            res.bytes.push((offset, Bytes(epilogue.clone())));
+            offset += epilogue.len() / 2;
+        }
+
+        // Fill the jumps into the appended bodies
+        let mut jump_table = JumpTable::new();
+        for jump in res.unmatched_jumps.drain(..) {
+            jump_table
+                .entry(jump.bytecode_index)
+                .or_default()
+                .push(Jump { bytecode_index: 0, ..jump });
+        }
+        let (bytes, unmatched_jumps) =
+            Codegen::fill_unmatched(res.bytes, &jump_table, &res.label_indices)?;
+        res.bytes = bytes;
+        res.unmatched_jumps = unmatched_jumps;
+
+        Ok(res)
+    }
+
+    /// Errors with [UnmatchedJumpLabel](CodegenErrorKind::UnmatchedJumpLabel) if any jump
+    /// is left without a label, as its placeholder would end up in the bytecode
+    pub(crate) fn check_unmatched_jumps(unmatched_jumps: &Jumps) -> Result<(), CodegenError> {
+        if unmatched_jumps.is_empty() {
+            return Ok(())
+        }
+        tracing::error!(
+            target: "codegen",
+            "Source contains unmatched jump labels \"{}\"",
+            unmatched_jumps.iter().map(|uj| uj.label.to_string()).collect::<Vec<String>>().join(", ")
+        );
+        Err(CodegenError {
+            kind: CodegenErrorKind::UnmatchedJumpLabel,
+            span: AstSpan(
+                unmatched_jumps.iter().flat_map(|uj| uj.span.0.clone()).collect::<Vec<Span>>(),
+            ),
+            token: None,
+        })
+    }
 
     /// Appends table bytecode to the end of the BytecodeRes output.
     /// Fills table JUMPDEST placeholders.
@@ -112,41 +1373,17 @@ impl Codegen {
         res: BytecodeRes,
         contract: &Contract,
     ) -> Result<String, CodegenError> {
-        if !res.unmatched_jumps.is_empty() {
-            tracing::error!(
-                target: "codegen",
-                "Source contains unmatched jump labels \"{}\"",
-                res.unmatched_jumps.iter().map(|uj| uj.label.to_string()).collect::<Vec<String>>().join(", ")
-            );
-            return Err(CodegenError {
-                kind: CodegenErrorKind::UnmatchedJumpLabel,
-                span: AstSpan(
-                    res.unmatched_jumps
-                        .iter()
-                        .flat_map(|uj| uj.span.0.clone())
-                        .collect::<Vec<Span>>(),
-                ),
-                token: None,
-            })
-        }
+        Codegen::check_unmatched_jumps(&res.unmatched_jumps)?;
 
         tracing::info!(target: "codegen", "GENERATING JUMPTABLE BYTECODE");
 
         let mut bytecode = res.bytes.into_iter().map(|(_, b)| b.0).collect::<String>();
         let mut table_offsets: HashMap<String, usize> = HashMap::new(); // table name -> bytecode offset
-        let mut table_offset = bytecode.len() / 2;
 
         if let Err(e) = contract.tables.iter().try_for_each(|jt| {
-            table_offsets.insert(jt.name.to_string(), table_offset);
-            let size = match bytes32_to_string(&jt.size, false).parse::<usize>() {
-                Ok(s) => s,
-                Err(_) => return Err(CodegenError {
-                    kind: CodegenErrorKind::UsizeConversion(format!("{:?}", jt.size)),
-                    span: jt.span.clone(),
-                    token: None
-                })
-            };
-            table_offset += size;
+            // Tables start where the emitted bytes end, so packed entries are laid out at their
+            // real 2 byte width regardless of the size declared for the table
+            table_offsets.insert(jt.name.to_string(), bytecode.len() / 2);
 
             tracing::info!(target: "codegen", "GENERATING BYTECODE FOR TABLE: \"{}\"", jt.name);
 
@@ -155,8 +1392,25 @@ impl Codegen {
                 .statements
                 .iter()
                 .try_for_each(|s| {
+                    // Constants are inlined as raw bytes, padded to the entry width in jump tables
+                    if let StatementType::Constant(name) = &s.ty {
+                        table_code = match jt.kind {
+                            TableKind::JumpTablePacked => {
+                                let hex = constant_value(name, contract, s.span.clone())?;
+                                format!("{}{}", table_code, pad_n_bytes(&hex, 0x02))
+                            }
+                            TableKind::JumpTable => {
+                                let hex = constant_value(name, contract, s.span.clone())?;
+                                format!("{}{}", table_code, pad_n_bytes(&hex, 0x20))
+                            }
+                            TableKind::CodeTable => {
+                                let hex = constant_table_code(name, contract, s.span.clone())?;
+                                format!("{}{}", table_code, hex)
+                            }
+                        };
+                    }
                     if let StatementType::LabelCall(label) = &s.ty {
-                        let offset = match res.label_indices.get(label) {
+                        let offset = match table_label_index(&res.label_indices, label) {
                             Some(l) => l,
                             None => {
                                 tracing::error!(
@@ -245,6 +1499,7 @@ impl Codegen {
         let mut jump_table = JumpTable::new();
         let mut label_indices = LabelIndices::new();
         let mut table_instances = Jumps::new();
+        let mut expansions = vec![];
 
         // Loop through all intermediate bytecode representations generated from the AST
         for (_ir_bytes_index, ir_byte) in ir_bytes.into_iter().enumerate() {
@@ -260,6 +1515,24 @@ impl Codegen {
                     tracing::debug!(target: "codegen", "OFFSET: {}, PUSH BYTES: {:?}", offset, push_bytes);
                     bytes.push((starting_offset, Bytes(push_bytes)));
                 }
+                IRByteType::PushedConstant(name, width) => {
+                    // Widen the constant's minimal push to the explicit width
+                    let push_bytes = constant_gen(&name, contract, ir_byte.span.clone())?;
+                    let immediate = &push_bytes[2..];
+                    if immediate.len() / 2 > width {
+                        tracing::error!(target: "codegen", "CONSTANT \"{}\" IS TOO WIDE FOR PUSH{}", name, width);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::PushWidthMismatch(width, immediate.len() / 2),
+                            span: ir_byte.span,
+                            token: None,
+                        })
+                    }
+                    let push_bytes =
+                        format!("{:02x}{}", 0x5f + width, pad_n_bytes(immediate, width));
+                    offset += push_bytes.len() / 2;
+                    tracing::debug!(target: "codegen", "OFFSET: {}, PUSH BYTES: {:?}", offset, push_bytes);
+                    bytes.push((starting_offset, Bytes(push_bytes)));
+                }
                 IRByteType::Statement(s) => {
                     let mut push_bytes = statement_gen(
                         &s,
@@ -271,6 +1544,7 @@ impl Codegen {
                         &mut jump_table,
                         &mut label_indices,
                         &mut table_instances,
+                        &mut expansions,
                         starting_offset,
                     )?;
                     bytes.append(&mut push_bytes);
@@ -287,6 +1561,7 @@ impl Codegen {
                         &mut offset,
                         mis,
                         &mut jump_table,
+                        1,
                     )?
                 }
             }
@@ -303,7 +1578,7 @@ impl Codegen {
         // Fill JUMPDEST placeholders
         let (bytes, unmatched_jumps) = Codegen::fill_unmatched(bytes, &jump_table, &label_indices)?;
 
-        Ok(BytecodeRes { bytes, label_indices, unmatched_jumps, table_instances })
+        Ok(BytecodeRes { bytes, label_indices, unmatched_jumps, table_instances, expansions })
     }
 
     /// Helper associated function to fill unmatched jump dests.
@@ -332,8 +1607,10 @@ impl Codegen {
                     for jump in jt {
                         // Check if the jump label has been defined. If not, add `jump` to the
                         // unmatched jumps and define its `bytecode_index`
-                        // at `code_index`
-                        if let Some(jump_index) = label_indices.get(jump.label.as_str()) {
+                        // at `code_index`. Labels from a caller's scope are left for the caller.
+                        if let Some(jump_index) =
+                            label_indices.get(jump.label.as_str()).filter(|_| jump.scope_depth == 0)
+                        {
                             // Format the jump index as a 2 byte hex number
                             let jump_value = format!("{:04x}", jump_index);
 
@@ -361,6 +1638,7 @@ impl Codegen {
                                 label: jump.label.clone(),
                                 bytecode_index: code_index,
                                 span: jump.span.clone(),
+                                scope_depth: jump.scope_depth,
                             });
                         }
                     }
@@ -387,6 +1665,24 @@ impl Codegen {
         main_bytecode: &str,
         constructor_bytecode: &str,
     ) -> Result<Artifact, CodegenError> {
+        let main_bytecode = match self.metadata {
+            true => format!("{}{}", main_bytecode, Codegen::version_metadata()),
+            false => main_bytecode.to_string(),
+        };
+
+        // Warn if the runtime can't be deployed
+        let contract_length = main_bytecode.len() / 2;
+        let limit = self.code_size_limit.unwrap_or(EIP170_CODE_SIZE_LIMIT);
+        if contract_length > limit {
+            self.push_warning(CodegenWarning::ExceedsCodeSizeLimit {
+                size: contract_length,
+                limit,
+            });
+        }
+
+        let immutable_refs = self.immutable_references(&main_bytecode);
+        let staging_size = 32 * self.immutables.len();
+
         let mut artifact: &mut Artifact = if let Some(art) = &mut self.artifact {
             art
         } else {
@@ -394,13 +1690,10 @@ impl Codegen {
             self.artifact.as_mut().unwrap()
         };
 
-        let contract_length = main_bytecode.len() / 2;
         let constructor_length = constructor_bytecode.len() / 2;
 
-        let encoded: Vec<Vec<u8>> =
-            args.iter().map(|tok| ethers_core::abi::encode(&[tok.clone()])).collect();
-        let hex_args: Vec<String> = encoded.iter().map(|tok| hex::encode(tok.as_slice())).collect();
-        let constructor_args = hex_args.join("");
+        // Encode the arguments as a single tuple so dynamic types get the correct head/tail offsets
+        let constructor_args = hex::encode(ethers_core::abi::encode(&args));
 
         // Constructor size optimizations
         let mut bootstrap_code_size = 9;
@@ -425,21 +1718,233 @@ impl Codegen {
             )
         };
 
+        // With immutables, the bootstrap copies the runtime past the values staged by the
+        // constructor and patches each read before returning it
+        if !immutable_refs.is_empty() {
+            bootstrap_code_size = 15 + 8 * immutable_refs.len();
+        }
+
+        // The bootstrap copies the runtime from right after itself, so both must be whole bytes
+        // and the size and offset must fit their PUSH2
+        let malformed = if main_bytecode.len() % 2 == 1 {
+            Some("runtime bytecode has an odd number of hex characters".to_string())
+        } else if constructor_bytecode.len() % 2 == 1 {
+            Some("constructor bytecode has an odd number of hex characters".to_string())
+        } else if contract_length > 0xffff {
+            Some(format!("runtime of {} bytes can't be copied by the bootstrap", contract_length))
+        } else if bootstrap_code_size + constructor_length > 0xffff {
+            Some(format!(
+                "runtime offset {} can't be copied by the bootstrap",
+                bootstrap_code_size + constructor_length
+            ))
+        } else if !immutable_refs.is_empty() && staging_size + contract_length > 0xffff {
+            Some(format!("runtime of {} bytes can't be patched with immutables", contract_length))
+        } else {
+            None
+        };
+        if let Some(reason) = malformed {
+            tracing::error!(target: "codegen", "MALFORMED CONSTRUCTOR: {}", reason);
+            return Err(CodegenError {
+                kind: CodegenErrorKind::MalformedConstructor(reason),
+                span: AstSpan(vec![Span { start: 0, end: 0, file: Some(file) }]),
+                token: None,
+            })
+        }
+
         // Generate the final bytecode
-        let bootstrap_code = format!("{}80{}3d393df3", contract_size, contract_code_offset);
+        let bootstrap_code = if immutable_refs.is_empty() {
+            format!("{}80{}3d393df3", contract_size, contract_code_offset)
+        } else {
+            let code_offset = bootstrap_code_size + constructor_length;
+            let patches = immutable_refs
+                .iter()
+                .map(|(index, offset)| {
+                    format!("61{:04x}5161{:04x}52", 32 * index, staging_size + offset + 1)
+                })
+                .collect::<String>();
+            format!(
+                "61{:04x}8061{:04x}61{:04x}39{}61{:04x}f3",
+                contract_length, code_offset, staging_size, patches, staging_size
+            )
+        };
         let constructor_code = format!("{}{}", constructor_bytecode, bootstrap_code);
+        let prefix = if self.hex_prefix { "0x" } else { "" };
         artifact.bytecode =
-            format!("{}{}{}", constructor_code, main_bytecode, constructor_args).to_lowercase();
-        artifact.runtime = main_bytecode.to_string().to_lowercase();
+            format!("{}{}{}{}", prefix, constructor_code, main_bytecode, constructor_args)
+                .to_lowercase();
+        artifact.runtime = format!("{}{}", prefix, main_bytecode).to_lowercase();
+        artifact.symbols = self.symbols.clone();
+        artifact.compiler_version = env!("CARGO_PKG_VERSION").to_string();
+        artifact.source_hash = format!(
+            "0x{}",
+            hex::encode(ethers_core::utils::keccak256(
+                FileSource::fully_flatten(Arc::clone(&file)).0
+            ))
+        );
         artifact.file = file;
+        for placeholder in artifact.unlinked_references() {
+            tracing::warn!(target: "codegen", "UNLINKED LIBRARY REFERENCE \"{}\"", placeholder);
+        }
         Ok(artifact.clone())
     }
 
+    /// Lists the cached [artifact](Codegen::artifact)'s bytecode one instruction per line, as
+    /// the offset, the instruction's hex bytes and its mnemonic, with the columns aligned.
+    ///
+    /// Unlike an annotated listing this needs no source map, so it works for any churned
+    /// artifact. Without an artifact the listing is empty.
+    pub fn bytecode_listing(&self) -> String {
+        let bytecode = self.artifact.as_ref().map(|a| a.bytecode.as_str()).unwrap_or_default();
+        let instructions = disassemble(bytecode);
+        let width = instructions.iter().map(|i| i.hex().len()).max().unwrap_or_default();
+
+        instructions
+            .iter()
+            .map(|i| {
+                let mnemonic = match i.immediate.is_empty() {
+                    true => i.mnemonic(),
+                    false => format!("{} 0x{}", i.mnemonic(), hex::encode(&i.immediate)),
+                };
+                format!("{:04x}  {:<width$}  {}\n", i.offset, i.hex(), mnemonic, width = width)
+            })
+            .collect()
+    }
+
+    /// Generates a deployable artifact for each `@facet` macro, in definition order.
+    ///
+    /// Each facet runtime is expanded on its own, as if the facet were MAIN, and wrapped in the
+    /// same bootstrap as the main runtime, without a constructor. Each artifact's symbols are
+    /// the labels of its own runtime. The cached [artifact](Codegen::artifact) and
+    /// [symbols](Codegen::symbols) are left untouched.
+    pub fn generate_facet_artifacts(
+        &mut self,
+        contract: &Contract,
+        file: Arc<FileSource>,
+    ) -> Result<Vec<(String, Artifact)>, CodegenError> {
+        let cached = (self.artifact.take(), std::mem::take(&mut self.symbols));
+        let artifacts = contract
+            .macros
+            .iter()
+            .filter(|m| m.facet)
+            .map(|m| {
+                let (runtime, _, labels) = Codegen::expand_entry(contract, &m.name)?;
+                self.artifact = None;
+                self.symbols = source_symbols(labels);
+                Ok((m.name.clone(), self.churn(Arc::clone(&file), vec![], &runtime, "")?))
+            })
+            .collect();
+        (self.artifact, self.symbols) = cached;
+        artifacts
+    }
+
+    /// Returns the compiler version as a data section appended to the runtime.
+    ///
+    /// Like solc's metadata, this is an INVALID opcode, so the data is never executed, followed by
+    /// the CBOR map `{"huff": "<version>"}` and its length as 2 bytes. It comes after all code and
+    /// tables, so no jump destinations move.
+    pub fn version_metadata() -> String {
+        let version = env!("CARGO_PKG_VERSION");
+        let mut cbor = vec![0xa1, 0x64];
+        cbor.extend_from_slice(b"huff");
+        match version.len() {
+            len @ 0..=23 => cbor.push(0x60 + len as u8),
+            len => cbor.extend_from_slice(&[0x78, len.min(0xff) as u8]),
+        }
+        cbor.extend_from_slice(&version.as_bytes()[..version.len().min(0xff)]);
+        format!("{}{}{:04x}", Opcode::Invalid, hex::encode(&cbor), cbor.len())
+    }
+
+    /// Finds the PUSH32 placeholders reading [immutables](Codegen::immutables) in the runtime,
+    /// as (immutable index, byte offset of the PUSH32) pairs
+    fn immutable_references(&self, main_bytecode: &str) -> Vec<(usize, usize)> {
+        if self.immutables.is_empty() {
+            return vec![]
+        }
+        let placeholders =
+            self.immutables.iter().map(|name| immutable_placeholder(name)).collect::<Vec<_>>();
+        let bytes = hex::decode(mask_link_placeholders(main_bytecode)).unwrap_or_default();
+
+        let mut references = vec![];
+        let mut pc = 0;
+        while pc < bytes.len() {
+            let op = bytes[pc];
+            if op == 0x7f {
+                let immediate = hex::encode(&bytes[pc + 1..(pc + 33).min(bytes.len())]);
+                if let Some(index) = placeholders.iter().position(|p| *p == immediate) {
+                    references.push((index, pc));
+                }
+            }
+            pc += match op {
+                0x60..=0x7f => 1 + (op - 0x5f) as usize,
+                _ => 1,
+            };
+        }
+        references
+    }
+
+    /// Returns the input types declared by `#define function CONSTRUCTOR(...)`
+    ///
+    /// Inputs whose type isn't a primitive EVM type, such as arrays, are `None`. Contracts that
+    /// don't declare the constructor's inputs return an empty vector.
+    pub fn constructor_input_types(contract: &Contract) -> Vec<Option<PrimitiveEVMType>> {
+        contract
+            .functions()
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case("CONSTRUCTOR"))
+            .map(|f| {
+                f.inputs
+                    .iter()
+                    .map(|arg| {
+                        arg.arg_type.clone().and_then(|t| PrimitiveEVMType::try_from(t).ok())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Encode constructor arguments as ethers_core::abi::token::Token
-    pub fn encode_constructor_args(args: Vec<String>) -> Vec<ethers_core::abi::token::Token> {
-        let tokens: Vec<ethers_core::abi::token::Token> =
-            args.iter().map(|tok| EToken::try_from(tok.clone()).unwrap().0).collect();
-        tokens
+    ///
+    /// Arguments with a declared type are parsed with [EToken::try_from_typed], the rest have
+    /// their type inferred by [EToken::try_from]. When types are declared, the number of
+    /// arguments must match them.
+    ///
+    /// Errors with the first argument that can't be encoded.
+    pub fn encode_constructor_args(
+        args: Vec<String>,
+        types: &[Option<PrimitiveEVMType>],
+    ) -> Result<Vec<ethers_core::abi::token::Token>, CodegenError> {
+        if !types.is_empty() && types.len() != args.len() {
+            tracing::error!(target: "codegen", "CONSTRUCTOR EXPECTS {} ARGUMENTS, GOT {}", types.len(), args.len());
+            return Err(CodegenError {
+                kind: CodegenErrorKind::InvalidArguments(format!(
+                    "Constructor expects {} arguments, got {}",
+                    types.len(),
+                    args.len()
+                )),
+                span: AstSpan(vec![]),
+                token: None,
+            })
+        }
+        args.into_iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                let token = match types.get(i) {
+                    Some(Some(ty)) => EToken::try_from_typed(arg.clone(), ty),
+                    _ => EToken::try_from(arg.clone()),
+                };
+                token.map(|t| t.0).map_err(|e| {
+                    tracing::error!(target: "codegen", "INVALID CONSTRUCTOR ARGUMENT \"{}\": {}", arg, e);
+                    CodegenError {
+                        kind: CodegenErrorKind::InvalidArguments(format!(
+                            "Invalid constructor argument \"{}\": {}",
+                            arg, e
+                        )),
+                        span: AstSpan(vec![]),
+                        token: None,
+                    }
+                })
+            })
+            .collect()
     }
 
     /// Export
@@ -528,3 +2033,74 @@ impl Codegen {
         Ok(abi)
     }
 }
+
+/// Finds the offset of a label referenced from a table.
+///
+/// Tables live outside of any macro, so they may reference a label scoped to a nested expansion
+/// as long as exactly one expansion defines it.
+fn table_label_index(label_indices: &LabelIndices, label: &str) -> Option<usize> {
+    if let Some(index) = label_indices.get(label) {
+        return Some(*index)
+    }
+    let mut scoped = label_indices
+        .iter()
+        .filter(|(l, _)| l.split('.').next() == Some(label))
+        .map(|(_, index)| *index);
+    match (scoped.next(), scoped.next()) {
+        (Some(index), None) => Some(index),
+        _ => None,
+    }
+}
+
+/// Keeps the labels written in the source, dropping the internal `__pc` and
+/// `__nonpayable_guard` labels
+fn source_symbols(labels: LabelIndices) -> BTreeMap<String, usize> {
+    labels
+        .into_iter()
+        .filter(|(name, _)| !name.starts_with("__pc_") && !name.starts_with("__nonpayable_"))
+        .collect()
+}
+
+/// Visits the statements of a macro body in order with the statement before each, descending
+/// into label bodies as they are reached. The first statement of a body has no previous one.
+fn walk_statements<'a>(
+    statements: &'a [Statement],
+    visit: &mut impl FnMut(&'a Statement, Option<&'a Statement>),
+) {
+    let mut previous = None;
+    for statement in statements {
+        visit(statement, previous);
+        if let StatementType::Label(label) = &statement.ty {
+            walk_statements(&label.inner, visit);
+        }
+        previous = Some(statement);
+    }
+}
+
+/// Returns the value pushed by a literal or constant statement, used by the memory lints to
+/// find the offset an opcode reads from or writes to
+fn static_offset(statement: Option<&Statement>, contract: &Contract) -> Option<U256> {
+    match &statement?.ty {
+        StatementType::Literal(l) => Some(U256::from_big_endian(l)),
+        StatementType::Constant(name) => {
+            constant_operand(name, contract, statement?.span.clone()).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Builds an entry of a standard-json output's `errors`
+fn standard_json_error(ty: &str, severity: &str, message: &str, file: Option<&str>) -> Value {
+    let formatted_message = message.trim();
+    let mut error = json!({
+        "component": "general",
+        "formattedMessage": formatted_message,
+        "message": formatted_message.lines().next().unwrap_or_default(),
+        "severity": severity,
+        "type": ty,
+    });
+    if let Some(file) = file {
+        error["sourceLocation"] = json!({ "file": file });
+    }
+    error
+}
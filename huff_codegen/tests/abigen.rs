@@ -11,6 +11,10 @@ fn constructs_valid_abi() {
         statements: vec![],
         takes: 0,
         returns: 0,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![]),
     };
     let contract = Contract {
@@ -21,6 +25,8 @@ fn constructs_valid_abi() {
         functions: vec![],
         events: vec![],
         tables: vec![],
+        memory_regions: vec![],
+        return_size: None,
     };
 
     // Generate the abi from the contract
@@ -46,6 +52,10 @@ fn missing_constructor_fails() {
         statements: vec![],
         takes: 0,
         returns: 0,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![]),
     };
     let contract = Contract {
@@ -56,6 +66,8 @@ fn missing_constructor_fails() {
         functions: vec![],
         events: vec![],
         tables: vec![],
+        memory_regions: vec![],
+        return_size: None,
     };
 
     // Generate the abi from the contract
@@ -0,0 +1,32 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+#[test]
+fn lists_one_aligned_instruction_per_line() {
+    let mut cg = Codegen::new();
+    let main_bytecode = Codegen::compile_snippet("0x01 0x0100 add dup1 0x00 mstore stop").unwrap();
+    cg.artifact = Some(Artifact { bytecode: main_bytecode, ..Default::default() });
+
+    assert_eq!(
+        cg.bytecode_listing(),
+        [
+            "0000  6001    PUSH1 0x01\n",
+            "0002  610100  PUSH2 0x0100\n",
+            "0005  01      ADD\n",
+            "0006  80      DUP1\n",
+            "0007  6000    PUSH1 0x00\n",
+            "0009  52      MSTORE\n",
+            "000a  00      STOP\n",
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn lists_unknown_bytes_and_truncated_pushes() {
+    let mut cg = Codegen::new();
+    assert_eq!(cg.bytecode_listing(), "");
+
+    cg.artifact = Some(Artifact { bytecode: "0x0c61ff".to_string(), ..Default::default() });
+    assert_eq!(cg.bytecode_listing(), "0000  0c    UNKNOWN(0x0c)\n0001  61ff  PUSH2 0xff\n");
+}
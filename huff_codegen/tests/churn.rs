@@ -1,8 +1,8 @@
 use std::sync::Arc;
 
 use ethers_core::abi::Token;
-use huff_codegen::Codegen;
-use huff_utils::prelude::FileSource;
+use huff_codegen::{Codegen, EIP170_CODE_SIZE_LIMIT};
+use huff_utils::prelude::{CodegenErrorKind, CodegenWarning, FileSource};
 
 #[test]
 fn churns_into_bytecode() {
@@ -47,3 +47,129 @@ fn churns_constructor_args() {
     assert!(churn_res.is_ok());
     assert_ne!(churn_res.unwrap().bytecode, "336000556101ac806100116000396000f360003560E01c8063a9059cbb1461004857806340c10f19146100de57806370a082311461014e57806318160ddd1461016b578063095ea7b314610177578063dd62ed3e1461018e575b600435336024358160016000526000602001526040600020548082116100d8578190038260016000526000602001526040600020558281906001600052600060200152604060002054018360016000526000602001526040600020556000527fDDF252AD1BE2C89B69C2B068FC378DAA952BA7F163C4A11628F55A4DF523B3EF60206000a3600160005260206000f35b60006000fd5b60005433146100ed5760006000fd5b600435600060243582819060016000526000602001526040600020540183600160005260006020015260406000205580600254016002556000527fDDF252AD1BE2C89B69C2B068FC378DAA952BA7F163C4A11628F55A4DF523B3EF60206000a35b600435600160005260006020015260406000205460005260206000f35b60025460005260206000f35b602435600435336000526000602001526040600020555b60243560043560005260006020015260406000205460005260206000f3".to_lowercase());
 }
+
+#[test]
+fn churns_dynamic_constructor_args() {
+    let mut cg = Codegen::new();
+
+    // (string, uint256) must be encoded as a single tuple, matching solc
+    let inputs: Vec<Token> = vec![Token::String("hello".to_string()), Token::Uint(42.into())];
+    let churn_res = cg.churn(Arc::new(FileSource::default()), inputs, "", "").unwrap();
+
+    let expected_args = [
+        "0000000000000000000000000000000000000000000000000000000000000040",
+        "000000000000000000000000000000000000000000000000000000000000002a",
+        "0000000000000000000000000000000000000000000000000000000000000005",
+        "68656c6c6f000000000000000000000000000000000000000000000000000000",
+    ]
+    .join("");
+    assert_eq!(churn_res.bytecode, format!("6000806009{}{}", "3d393df3", expected_args));
+}
+
+#[test]
+fn warns_when_runtime_exceeds_code_size_limit() {
+    let main_bytecode = "00".repeat(EIP170_CODE_SIZE_LIMIT + 1);
+
+    // Oversized runtimes still churn, but with a warning
+    let mut cg = Codegen::new();
+    assert!(cg.churn(Arc::new(FileSource::default()), vec![], &main_bytecode, "").is_ok());
+    assert_eq!(
+        cg.warnings,
+        vec![CodegenWarning::ExceedsCodeSizeLimit {
+            size: EIP170_CODE_SIZE_LIMIT + 1,
+            limit: EIP170_CODE_SIZE_LIMIT
+        }]
+    );
+
+    // Chains with a higher limit can raise it
+    let mut cg = Codegen::new().with_code_size_limit(EIP170_CODE_SIZE_LIMIT * 2);
+    assert!(cg.churn(Arc::new(FileSource::default()), vec![], &main_bytecode, "").is_ok());
+    assert!(cg.warnings.is_empty());
+}
+
+#[test]
+fn churns_provenance_fields() {
+    let file = Arc::new(FileSource {
+        path: "Test.huff".to_string(),
+        source: Some("#define macro MAIN() = takes(0) returns(0) {}".to_string()),
+        ..Default::default()
+    });
+
+    let mut cg = Codegen::new();
+    let artifact = cg.churn(Arc::clone(&file), vec![], "00", "").unwrap();
+    assert_eq!(artifact.compiler_version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(
+        artifact.source_hash,
+        format!(
+            "0x{}",
+            hex::encode(ethers_core::utils::keccak256(
+                "#define macro MAIN() = takes(0) returns(0) {}"
+            ))
+        )
+    );
+
+    // The hash is stable across compilations of the same source
+    let mut cg = Codegen::new();
+    assert_eq!(cg.churn(file, vec![], "00", "").unwrap().source_hash, artifact.source_hash);
+
+    // And both fields are exported
+    let json = serde_json::to_string(&artifact).unwrap();
+    assert!(json.contains("\"compiler_version\""));
+    assert!(json.contains("\"source_hash\""));
+}
+
+#[test]
+fn churns_with_and_without_hex_prefix() {
+    let main_bytecode = "6001600201";
+    let constructor_bytecode = "33600055";
+
+    let mut cg = Codegen::new();
+    let unprefixed = cg
+        .churn(Arc::new(FileSource::default()), vec![], main_bytecode, constructor_bytecode)
+        .unwrap();
+    assert_eq!(unprefixed.runtime, main_bytecode);
+    assert!(unprefixed.bytecode.starts_with(constructor_bytecode));
+
+    let mut cg = Codegen::new().with_hex_prefix(true);
+    let prefixed = cg
+        .churn(Arc::new(FileSource::default()), vec![], main_bytecode, constructor_bytecode)
+        .unwrap();
+    assert_eq!(prefixed.runtime, format!("0x{}", unprefixed.runtime));
+    assert_eq!(prefixed.bytecode, format!("0x{}", unprefixed.bytecode));
+}
+
+#[test]
+fn rejects_malformed_deploy_sequence() {
+    // An odd number of hex characters makes the copied size disagree with the runtime
+    let mut cg = Codegen::new();
+    let err = cg.churn(Arc::new(FileSource::default()), vec![], "600", "").unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::MalformedConstructor(_)));
+
+    let err = cg.churn(Arc::new(FileSource::default()), vec![], "6001", "336").unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::MalformedConstructor(_)));
+
+    // The bootstrap can only copy up to 0xffff bytes
+    let oversized = "00".repeat(0x10000);
+    let err = cg.churn(Arc::new(FileSource::default()), vec![], &oversized, "").unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::MalformedConstructor(_)));
+}
+
+#[test]
+fn churns_version_metadata_after_runtime() {
+    let mut cg = Codegen::new().with_metadata(true);
+    let main_bytecode = "6100055600";
+    let artifact = cg.churn(Arc::new(FileSource::default()), vec![], main_bytecode, "").unwrap();
+
+    // The runtime is unchanged, followed by INVALID and the metadata
+    let runtime = hex::decode(&artifact.runtime).unwrap();
+    assert!(artifact.runtime.starts_with(&format!("{}fe", main_bytecode)));
+    assert!(artifact.bytecode.ends_with(&artifact.runtime));
+
+    // The CBOR map {"huff": "<version>"} ends the runtime, followed by its length
+    let version = env!("CARGO_PKG_VERSION");
+    let cbor_len = u16::from_be_bytes([runtime[runtime.len() - 2], runtime[runtime.len() - 1]]);
+    let cbor = &runtime[runtime.len() - 2 - cbor_len as usize..runtime.len() - 2];
+    assert_eq!(&cbor[..6], b"\xa1\x64huff");
+    assert!(cbor.ends_with(version.as_bytes()));
+    assert_eq!(runtime.len(), main_bytecode.len() / 2 + 1 + cbor.len() + 2);
+}
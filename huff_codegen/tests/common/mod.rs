@@ -0,0 +1,63 @@
+//! Fixtures shared by the codegen integration tests
+#![allow(dead_code)]
+
+use huff_lexer::Lexer;
+use huff_parser::Parser;
+use huff_utils::prelude::*;
+use revm::{
+    primitives::{
+        AccountInfo, Address, Bytes, CreateScheme, ExecutionResult, Output, TransactTo, U256,
+    },
+    Evm, InMemoryDB,
+};
+
+/// Parses a source without deriving storage pointers
+pub fn parse_underived(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.map(|x| x.unwrap()).collect::<Vec<Token>>();
+    Parser::new(tokens, None).parse().unwrap()
+}
+
+/// Parses a source and derives its storage pointers, ready for codegen
+pub fn parse(source: &str) -> Contract {
+    let mut contract = parse_underived(source);
+    contract.derive_storage_pointers();
+    contract
+}
+
+/// Deploys the creation bytecode from a funded sender, then calls the runtime with `value` wei
+/// and empty calldata
+pub fn deploy_and_call_with_value(bytecode: &str, value: u64) -> ExecutionResult {
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        Address::ZERO,
+        AccountInfo { balance: U256::from(1_000_000), ..Default::default() },
+    );
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .modify_tx_env(|tx| {
+            tx.transact_to = TransactTo::Create(CreateScheme::Create);
+            tx.data = Bytes::from(hex::decode(bytecode).unwrap());
+        })
+        .build();
+    let address = match evm.transact_commit().unwrap() {
+        ExecutionResult::Success { output: Output::Create(_, Some(address)), .. } => address,
+        res => panic!("deployment failed: {:?}", res),
+    };
+    evm.tx_mut().transact_to = TransactTo::Call(address);
+    evm.tx_mut().data = Bytes::new();
+    evm.tx_mut().value = U256::from(value);
+    evm.tx_mut().nonce = None;
+    evm.transact().unwrap().result
+}
+
+/// Deploys the creation bytecode and returns the word the runtime returns
+pub fn deploy_and_call(bytecode: &str) -> U256 {
+    match deploy_and_call_with_value(bytecode, 0) {
+        ExecutionResult::Success { output: Output::Call(out), .. } => {
+            U256::try_from_be_slice(&out).unwrap()
+        }
+        res => panic!("execution failed: {:?}", res),
+    }
+}
@@ -0,0 +1,66 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use std::sync::Arc;
+
+mod common;
+use common::parse;
+
+#[test]
+fn injects_constants_from_json() {
+    let mut contract = parse(
+        r#"
+        #define constant FEE = 0x01
+        #define macro MAIN() = takes(0) returns(0) {
+            [OWNER] [FEE] [SUPPLY]
+        }
+    "#,
+    );
+    let json = r#"{ "OWNER": "0xdeadbeef", "FEE": 25, "SUPPLY": "1000000" }"#;
+
+    let mut cg =
+        Codegen::new().with_injected_constants(Codegen::parse_constants_json(json).unwrap());
+    cg.apply_injected_constants(&mut contract);
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(main_bytecode, "63deadbeef6019620f4240");
+
+    // Only the constant already defined in source warns
+    assert_eq!(cg.warnings.len(), 1);
+    assert!(matches!(
+        &cg.warnings[0],
+        CodegenWarning::ConstantOverridden { name, .. } if name == "FEE"
+    ));
+}
+
+#[test]
+fn rejects_invalid_constant_values() {
+    let err = Codegen::parse_constants_json(r#"{ "OWNER": "0xnothex" }"#).unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidConstantsJson(_)));
+
+    let err = Codegen::parse_constants_json(r#"["0x01"]"#).unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidConstantsJson(_)));
+}
+
+#[test]
+fn resizes_code_tables_inlining_injected_constants() {
+    let contract = parse(
+        r#"
+        #define constant X = 0x01
+        #define table T {
+            [X]
+        }
+        #define macro MAIN() = takes(0) returns(0) {
+            __tablesize(T) __tablestart(T)
+        }
+    "#,
+    );
+    let json = r#"{ "X": "0x123456" }"#;
+
+    let mut cg =
+        Codegen::new().with_injected_constants(Codegen::parse_constants_json(json).unwrap());
+    let artifact =
+        cg.compile_contract(contract, Arc::new(FileSource::default()), vec![], false).unwrap();
+
+    // The table inlines all 3 bytes of the injected value, so its size is pushed as 3
+    assert_eq!(artifact.runtime, "6003610005123456");
+}
@@ -26,7 +26,7 @@ fn encode_simple_constructor_args() {
     .map(|s| s.to_string())
     .collect();
 
-    let results = Codegen::encode_constructor_args(args);
+    let results = Codegen::encode_constructor_args(args, &[]).unwrap();
     assert_eq!(results[0], Token::String("Hello".to_string()));
     assert_eq!(results[1], Token::Uint(U256::from_dec_str("10000").unwrap()));
     assert_eq!(results[2], Token::Bool(false));
@@ -56,7 +56,7 @@ fn encode_array_constructor_args() {
     .map(|s| s.to_string())
     .collect();
 
-    let results = Codegen::encode_constructor_args(args);
+    let results = Codegen::encode_constructor_args(args, &[]).unwrap();
 
     assert_eq!(
         results[0],
@@ -86,3 +86,23 @@ fn encode_array_constructor_args() {
     assert_eq!(results[4], expected_array);
     assert_eq!(results[5], expected_array);
 }
+
+#[test]
+fn rejects_invalid_constructor_args() {
+    for arg in ["0xzz", "0x😀", "-not_a_number", "hello world", "[1, 0xgg]"] {
+        assert!(Codegen::encode_constructor_args(vec![arg.to_string()], &[]).is_err(), "{}", arg);
+    }
+}
+
+#[test]
+fn encode_constructor_args_with_declared_types() {
+    use huff_utils::types::PrimitiveEVMType;
+
+    let types = vec![Some(PrimitiveEVMType::Bool), None];
+    let results =
+        Codegen::encode_constructor_args(vec!["1".to_string(), "10".to_string()], &types).unwrap();
+    assert_eq!(results, vec![Token::Bool(true), Token::Uint(U256::from(10))]);
+
+    // The declared types fix the number of arguments
+    assert!(Codegen::encode_constructor_args(vec!["1".to_string()], &types).is_err());
+}
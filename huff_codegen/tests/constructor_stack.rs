@@ -0,0 +1,69 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+fn lint(source: &str) -> Vec<CodegenWarning> {
+    let contract = parse(source);
+    let mut cg = Codegen::new();
+    let constructor_bytecode = Codegen::generate_constructor_bytecode(&contract).unwrap();
+    cg.lint_constructor_stack(&contract, &constructor_bytecode);
+    cg.warnings
+}
+
+#[test]
+fn warns_when_constructor_leaves_items_on_the_stack() {
+    let warnings = lint(
+        r#"
+        #define macro CONSTRUCTOR() = takes(0) returns(0) {
+            0x01 0x02 0x00 sstore
+        }
+        #define macro MAIN() = takes(0) returns(0) {}
+    "#,
+    );
+    assert_eq!(warnings, vec![CodegenWarning::ConstructorStackResidue { depth: 1 }]);
+}
+
+#[test]
+fn clean_constructors_do_not_warn() {
+    let warnings = lint(
+        r#"
+        #define macro CONSTRUCTOR() = takes(0) returns(0) {
+            0x01 0x00 sstore
+            0x00 calldataload skip jumpi
+            0x02 pop
+            skip:
+        }
+        #define macro MAIN() = takes(0) returns(0) {}
+    "#,
+    );
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn constructors_that_halt_do_not_warn() {
+    let warnings = lint(
+        r#"
+        #define macro CONSTRUCTOR() = takes(0) returns(0) {
+            0x01 0x00 0x00 return
+        }
+        #define macro MAIN() = takes(0) returns(0) {}
+    "#,
+    );
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn constructor_stack_warning_can_be_disabled() {
+    let warnings = lint(
+        r#"
+        // huff-disable: constructor-stack
+        #define macro CONSTRUCTOR() = takes(0) returns(0) {
+            0x01
+        }
+        #define macro MAIN() = takes(0) returns(0) {}
+    "#,
+    );
+    assert!(warnings.is_empty());
+}
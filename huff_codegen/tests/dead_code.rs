@@ -0,0 +1,36 @@
+use huff_codegen::Codegen;
+
+#[test]
+fn strips_unreachable_block_and_relocates_jumps() {
+    let bytecode = Codegen::compile_snippet(
+        "0x00 calldataload dest jumpi 0x00 0x00 revert 0x01 0x02 add pop dest: stop",
+    )
+    .unwrap();
+    assert_eq!(bytecode, "6000356100125760006000fd6001600201505b00");
+
+    let optimized = Codegen::eliminate_dead_code(&bytecode);
+
+    assert_eq!(optimized, "60003561000c5760006000fd5b00");
+    assert!(Codegen::verify_jumpdests(&optimized).is_ok());
+}
+
+#[test]
+fn keeps_reachable_code() {
+    let bytecode =
+        Codegen::compile_snippet("0x00 calldataload dest jumpi 0x01 dest: stop").unwrap();
+    assert_eq!(Codegen::eliminate_dead_code(&bytecode), bytecode);
+}
+
+#[test]
+fn leaves_code_with_unknown_offsets_untouched() {
+    // A dynamic jump could land on any JUMPDEST, so nothing can be removed
+    let dynamic =
+        Codegen::compile_snippet("0x00 calldataload jump 0x00 0x00 revert 0x01 pop dest: stop")
+            .unwrap();
+    assert_eq!(Codegen::eliminate_dead_code(&dynamic), dynamic);
+
+    // Code reading itself depends on its own layout
+    let codecopy =
+        Codegen::compile_snippet("0x01 0x00 0x00 codecopy stop 0x01 pop dest: stop").unwrap();
+    assert_eq!(Codegen::eliminate_dead_code(&codecopy), codecopy);
+}
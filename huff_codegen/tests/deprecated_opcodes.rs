@@ -0,0 +1,41 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse_underived;
+
+#[test]
+fn warns_on_selfdestruct() {
+    let source = r#"
+        #define macro KILL() = takes(0) returns(0) {
+            caller selfdestruct
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload skip jumpi
+            KILL()
+            skip:
+                0x00 0x00 0x00 0x00 0x00 caller gas callcode
+        }
+    "#;
+    let contract = parse_underived(source);
+
+    assert!(Opcode::Selfdestruct.is_deprecated());
+    assert!(!Opcode::Delegatecall.is_deprecated());
+
+    let mut cg = Codegen::new();
+    cg.lint_deprecated_opcodes(&contract);
+
+    let deprecated = cg
+        .warnings
+        .iter()
+        .map(|w| match w {
+            CodegenWarning::DeprecatedOpcode { opcode, macro_name, .. } => {
+                (*opcode, macro_name.as_str())
+            }
+            w => panic!("unexpected warning: {:?}", w),
+        })
+        .collect::<Vec<(Opcode, &str)>>();
+    assert_eq!(deprecated, vec![(Opcode::Selfdestruct, "KILL"), (Opcode::Callcode, "MAIN")]);
+    assert!(cg.warnings[0].to_string().contains("deprecated opcode \"selfdestruct\""));
+}
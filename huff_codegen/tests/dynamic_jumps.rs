@@ -0,0 +1,49 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+#[test]
+fn warns_on_dynamic_jump() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload jump
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    cg.lint_dynamic_jumps(&contract);
+
+    assert_eq!(cg.warnings.len(), 1);
+    match &cg.warnings[0] {
+        CodegenWarning::DynamicJump { macro_name, .. } => assert_eq!(macro_name, "MAIN"),
+        w => panic!("unexpected warning: {:?}", w),
+    }
+}
+
+#[test]
+fn label_jumps_are_not_dynamic() {
+    let contract = parse(
+        r#"
+        #define macro CHECK(err) = takes(1) returns(0) {
+            <err> jumpi
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload dest jumpi
+            CHECK(dest)
+            dest:
+                end jump
+            end:
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    cg.lint_dynamic_jumps(&contract);
+
+    assert!(cg.warnings.is_empty());
+}
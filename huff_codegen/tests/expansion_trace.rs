@@ -0,0 +1,53 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse_underived;
+
+#[test]
+fn records_nested_macro_expansions() {
+    let source = r#"
+        #define macro LEAF() = takes(0) returns(1) {
+            0x01
+        }
+
+        #define macro MIDDLE() = takes(0) returns(2) {
+            LEAF() LEAF()
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            caller
+            MIDDLE()
+            LEAF()
+        }
+    "#;
+    let contract = parse_underived(source);
+
+    let mut cg = Codegen::new();
+    let bytecode = cg.generate_main_bytecode_traced(&contract).unwrap();
+    assert_eq!(bytecode, "33600160016001");
+
+    let leaf = |start: usize| MacroExpansion {
+        macro_name: "LEAF".to_string(),
+        start,
+        end: start + 2,
+        children: vec![],
+    };
+    assert_eq!(
+        cg.expansion_trace,
+        Some(MacroExpansion {
+            macro_name: "MAIN".to_string(),
+            start: 0,
+            end: 7,
+            children: vec![
+                MacroExpansion {
+                    macro_name: "MIDDLE".to_string(),
+                    start: 1,
+                    end: 5,
+                    children: vec![leaf(1), leaf(3)],
+                },
+                leaf(5),
+            ],
+        })
+    );
+}
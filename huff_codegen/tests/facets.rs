@@ -0,0 +1,69 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use revm::primitives::U256;
+use std::{collections::BTreeMap, sync::Arc};
+
+mod common;
+use common::{deploy_and_call, parse};
+
+const SOURCE: &str = r#"
+    #define macro RETURN_WORD() = takes(1) returns(0) {
+        0x00 mstore 0x20 0x00 return
+    }
+
+    #define macro OWNERSHIP() = takes(0) returns(0) @facet {
+        0x01 skip jump
+        0x02
+        skip:
+            RETURN_WORD()
+    }
+
+    #define macro LOUPE() = takes(0) returns(0) @facet {
+        0x2a RETURN_WORD()
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 RETURN_WORD()
+    }
+"#;
+
+#[test]
+fn parses_facet_decorator() {
+    let contract = parse(SOURCE);
+    let facets = contract.macros.iter().filter(|m| m.facet).map(|m| m.name.as_str());
+    assert_eq!(facets.collect::<Vec<_>>(), ["OWNERSHIP", "LOUPE"]);
+    assert!(!contract.find_macro_by_name("MAIN").unwrap().facet);
+}
+
+#[test]
+fn generates_an_artifact_per_facet() {
+    let contract = parse(SOURCE);
+    let mut cg = Codegen::new();
+    let facets = cg.generate_facet_artifacts(&contract, Arc::new(FileSource::default())).unwrap();
+
+    let names = facets.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["OWNERSHIP", "LOUPE"]);
+    for (name, artifact) in &facets {
+        assert_eq!(artifact.runtime, Codegen::generate_facet_bytecode(&contract, name).unwrap());
+        assert!(artifact.bytecode.ends_with(&artifact.runtime));
+    }
+
+    // Symbols are the facet's own labels, not MAIN's
+    assert_eq!(facets[0].1.symbols, BTreeMap::from([("skip".to_string(), 8)]));
+    assert!(facets[1].1.symbols.is_empty());
+
+    // Each facet is deployed on its own, with jumps resolved within its runtime
+    assert_eq!(deploy_and_call(&facets[0].1.bytecode), U256::from(1));
+    assert_eq!(deploy_and_call(&facets[1].1.bytecode), U256::from(0x2a));
+
+    // Facets don't replace the main artifact
+    assert!(cg.artifact.is_none());
+}
+
+#[test]
+fn facets_are_not_unused() {
+    let contract = parse(SOURCE);
+    let mut cg = Codegen::new();
+    cg.lint_unused_macros(&contract);
+    assert!(cg.warnings.is_empty());
+}
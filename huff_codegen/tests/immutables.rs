@@ -0,0 +1,60 @@
+use ethers_core::abi::Token as EToken;
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use revm::primitives::U256;
+use std::sync::Arc;
+
+mod common;
+use common::{deploy_and_call, parse};
+
+#[test]
+fn runtime_reads_immutables_set_by_the_constructor() {
+    let contract = parse(
+        r#"
+        #define constant OWNER = IMMUTABLE()
+        #define constant LIMIT = IMMUTABLE()
+
+        #define macro CONSTRUCTOR() = takes(0) returns(0) {
+            // Copy the constructor argument from the end of the code
+            0x20 0x20 codesize sub 0x00 codecopy
+            0x00 mload __set_immutable(OWNER)
+            0x07 __set_immutable(LIMIT)
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            [OWNER] [LIMIT] add [OWNER] add
+            0x00 mstore 0x20 0x00 return
+        }
+    "#,
+    );
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let constructor_bytecode = Codegen::generate_constructor_bytecode(&contract).unwrap();
+    let mut cg = Codegen::new().with_immutables(&contract);
+    let artifact = cg
+        .churn(
+            Arc::new(FileSource::default()),
+            vec![EToken::Uint(0x1234.into())],
+            &main_bytecode,
+            &constructor_bytecode,
+        )
+        .unwrap();
+
+    assert_eq!(deploy_and_call(&artifact.bytecode), U256::from(0x1234 * 2 + 7));
+}
+
+#[test]
+fn rejects_setting_a_regular_constant() {
+    let contract = parse(
+        r#"
+        #define constant OWNER = 0x01
+
+        #define macro CONSTRUCTOR() = takes(0) returns(0) {
+            caller __set_immutable(OWNER)
+        }
+    "#,
+    );
+
+    let err = Codegen::generate_constructor_bytecode(&contract).unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidArguments(_)));
+}
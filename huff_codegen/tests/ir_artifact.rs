@@ -0,0 +1,82 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+const SOURCE: &str = r#"
+    #define jumptable__packed TABLE {
+        one two
+    }
+
+    #define macro CONSTRUCTOR() = takes(0) returns(0) {
+        0x2a 0x00 sstore
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        __tablestart(TABLE) pop
+        one:
+            0x01 two jump
+        two:
+            stop
+    }
+"#;
+
+#[test]
+fn round_trips_through_serialized_ir() {
+    let contract = parse(SOURCE);
+    let cg = Codegen::new().with_ast(contract.clone());
+
+    let ir = cg.to_ir_artifact().unwrap();
+    assert!(ir.main.labels.contains_key("one"));
+    assert_eq!(ir.main.table_starts, vec![IrTableStart { table: "TABLE".to_string(), offset: 0 }]);
+
+    let json = serde_json::to_string(&ir).unwrap();
+    let deserialized: IrArtifact = serde_json::from_str(&json).unwrap();
+    assert_eq!(deserialized, ir);
+
+    let (main_bytecode, constructor_bytecode) = cg.from_ir_artifact(&deserialized).unwrap();
+    assert_eq!(main_bytecode, Codegen::generate_main_bytecode(&contract).unwrap());
+    assert_eq!(constructor_bytecode, Codegen::generate_constructor_bytecode(&contract).unwrap());
+}
+
+#[test]
+fn lays_out_rewritten_ir() {
+    let contract = parse(SOURCE);
+    let cg = Codegen::new().with_ast(contract.clone());
+    let mut ir = cg.to_ir_artifact().unwrap();
+
+    // An optimizer swaps the constructor's value for one of the same width
+    let chunk = ir.constructor.as_mut().unwrap().code.iter_mut().find(|c| c.bytes == "602a");
+    chunk.unwrap().bytes = "602b".to_string();
+
+    let (_, constructor_bytecode) = cg.from_ir_artifact(&ir).unwrap();
+    assert_eq!(constructor_bytecode, "602b600055");
+}
+
+#[test]
+fn rejects_unmatched_jumps() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            missing jump
+        }
+    "#,
+    );
+    let cg = Codegen::new().with_ast(contract.clone());
+
+    // The IR can't carry the unfilled jump, so it isn't built rather than laid out with a
+    // placeholder left in the code
+    let err = cg.to_ir_artifact().unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::UnmatchedJumpLabel);
+    assert_eq!(
+        Codegen::generate_main_bytecode(&contract).unwrap_err().kind,
+        CodegenErrorKind::UnmatchedJumpLabel
+    );
+}
+
+#[test]
+fn requires_an_ast() {
+    let err = Codegen::new().to_ir_artifact().unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::MissingAst);
+}
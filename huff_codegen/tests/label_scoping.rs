@@ -0,0 +1,117 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+#[test]
+fn inlined_labels_do_not_collide() {
+    let contract = parse(
+        r#"
+        #define macro INNER() = takes(0) returns(0) {
+            loop:
+                0x01 loop jumpi
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            loop:
+                INNER()
+                INNER()
+                loop jump
+        }
+    "#,
+    );
+
+    // Each expansion jumps to its own `loop`, and MAIN's `loop` is still at 0
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(main_bytecode, ["5b", "5b600161000157", "5b600161000857", "61000056"].concat());
+}
+
+#[test]
+fn tables_reference_labels_in_nested_expansions() {
+    let contract = parse(
+        r#"
+        #define jumptable__packed TABLE {
+            target
+        }
+
+        #define macro TARGET() = takes(0) returns(0) {
+            target:
+                stop
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            __tablestart(TABLE) TARGET()
+        }
+    "#,
+    );
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(main_bytecode, "6100055b000003");
+}
+
+#[test]
+fn jumps_to_caller_label_passed_as_argument() {
+    let contract = parse(
+        r#"
+        #define macro GOTO(dest) = takes(0) returns(0) {
+            done:
+                <dest> jump
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            GOTO(done)
+            done:
+                stop
+        }
+    "#,
+    );
+
+    // The argument resolves to MAIN's `done`, not the one defined inside GOTO
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(main_bytecode, "5b610005565b00");
+}
+
+#[test]
+fn label_arguments_pass_through_nested_expansions() {
+    let contract = parse(
+        r#"
+        #define macro INNER(to) = takes(0) returns(0) {
+            <to> jump
+        }
+
+        #define macro OUTER(to) = takes(0) returns(0) {
+            INNER(<to>)
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            OUTER(end)
+            end:
+                stop
+        }
+    "#,
+    );
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(main_bytecode, "610004565b00");
+}
+
+#[test]
+fn rejects_implicit_jump_to_caller_label() {
+    let contract = parse(
+        r#"
+        #define macro INNER() = takes(0) returns(0) {
+            end jump
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            INNER()
+            end:
+                stop
+        }
+    "#,
+    );
+
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::UnmatchedJumpLabel);
+}
@@ -0,0 +1,41 @@
+use ethers_core::types::Address;
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use std::sync::Arc;
+
+mod common;
+use common::parse;
+
+#[test]
+fn links_library_placeholders() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            __linked("Math") extcodesize
+            __linked("Strings") __linked("Math") eq
+        }
+    "#,
+    );
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let math = link_placeholder("Math");
+    let strings = link_placeholder("Strings");
+    assert_eq!(math.len(), 40);
+    assert_eq!(main_bytecode, format!("73{}3b73{}73{}14", math, strings, math));
+
+    let mut cg = Codegen::new();
+    let mut artifact =
+        cg.churn(Arc::new(FileSource::default()), vec![], &main_bytecode, "").unwrap();
+    assert_eq!(artifact.unlinked_references(), vec![math.clone(), strings.clone()]);
+
+    let address = Address::from_low_u64_be(0x1234);
+    artifact.link("Math", address);
+    let linked = format!("73{}3b73{}73{}14", hex::encode(address), strings, hex::encode(address));
+    assert_eq!(artifact.runtime, linked);
+    assert!(artifact.bytecode.contains(&linked));
+    assert_eq!(artifact.unlinked_references(), vec![strings]);
+
+    artifact.link("Strings", Address::from_low_u64_be(0x5678));
+    assert!(artifact.unlinked_references().is_empty());
+    assert!(hex::decode(&artifact.bytecode).is_ok());
+}
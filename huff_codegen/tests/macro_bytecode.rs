@@ -0,0 +1,69 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::{parse, parse_underived};
+
+#[test]
+fn macro_bytecode_matches_runtime_portion() {
+    let source: &str = r#"
+        #define constant OWNER_POINTER = FREE_STORAGE_POINTER()
+
+        #define macro SET_OWNER() = takes(0) returns(0) {
+            caller [OWNER_POINTER] sstore
+        }
+
+        #define macro INNER() = takes(0) returns(0) {
+            0x01 SET_OWNER()
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload
+            INNER()
+            stop
+        }
+    "#;
+
+    let contract = parse(source);
+
+    // Nested invocations are resolved
+    let inner = Codegen::macro_bytecode(&contract, "INNER").unwrap();
+    assert_eq!(inner, "600133600055");
+
+    // And the macro bytecode is the exact portion present in the runtime
+    let main = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(main, format!("600035{}00", inner));
+
+    // Missing macros are reported
+    let err = Codegen::macro_bytecode(&contract, "MISSING").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::MissingMacroDefinition("MISSING".to_string()));
+}
+
+#[test]
+fn macro_bytecode_rejects_jumps_outside_the_macro() {
+    let source: &str = r#"
+        #define macro JUMP_OUT() = takes(0) returns(0) {
+            done jump
+        }
+
+        #define macro LOOP() = takes(0) returns(0) {
+            start:
+                start jump
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            LOOP()
+            done:
+                stop
+        }
+    "#;
+
+    let contract = parse_underived(source);
+
+    // The label isn't defined in the macro, so the jump can't be filled
+    let err = Codegen::macro_bytecode(&contract, "JUMP_OUT").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::UnmatchedJumpLabel);
+
+    // Labels within the macro are resolved relative to its start
+    assert_eq!(Codegen::macro_bytecode(&contract, "LOOP").unwrap(), "5b61000056");
+}
@@ -0,0 +1,40 @@
+use huff_codegen::Codegen;
+
+mod common;
+use common::parse_underived;
+
+#[test]
+fn dumps_macro_ir() {
+    let source = r#"
+        #define constant OWNER = 0x01
+
+        #define macro STORE(value) = takes(0) returns(0) {
+            <value> [OWNER] sstore
+            done jump
+            done:
+                INNER()
+        }
+
+        #define macro INNER() = takes(0) returns(0) {
+            stop
+        }
+    "#;
+    let contract = parse_underived(source);
+
+    let ir = Codegen::macro_ir(&contract, "STORE").unwrap();
+    assert_eq!(
+        ir,
+        [
+            "ARG CALL: value",
+            "CONSTANT: OWNER",
+            "BYTES: 55",
+            "STATEMENT: LABEL CALL: done",
+            "BYTES: 56",
+            "STATEMENT: LABEL: done",
+            "STATEMENT: MACRO INVOCATION: INNER",
+        ]
+        .join("\n")
+    );
+
+    assert!(Codegen::macro_ir(&contract, "MISSING").is_err());
+}
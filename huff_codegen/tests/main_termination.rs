@@ -0,0 +1,75 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse_underived;
+
+fn lint(source: &str) -> Vec<CodegenWarning> {
+    let contract = parse_underived(source);
+    let mut cg = Codegen::new();
+    let main_bytecode = cg.generate_main_bytecode_traced(&contract).unwrap();
+    cg.lint_main_termination(&contract, &main_bytecode);
+    cg.warnings
+}
+
+#[test]
+fn warns_when_main_can_fall_off_the_end() {
+    let warnings = lint(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload done jumpi
+            stop
+            done:
+                0x01
+        }
+    "#,
+    );
+    assert_eq!(warnings, vec![CodegenWarning::MainFallsThrough { offset: 11 }]);
+}
+
+#[test]
+fn accepts_main_terminating_on_every_path() {
+    let warnings = lint(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload done jumpi
+            stop
+            done:
+                0x00 0x00 revert
+        }
+    "#,
+    );
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn warns_when_main_falls_into_its_tables() {
+    let warnings = lint(
+        r#"
+        #define jumptable__packed TABLE {
+            target
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            __tablestart(TABLE) pop
+            target:
+                0x01
+        }
+    "#,
+    );
+    // The table after MAIN starts with a zero byte, which decodes as STOP
+    assert_eq!(warnings, vec![CodegenWarning::MainFallsThrough { offset: 7 }]);
+}
+
+#[test]
+fn falling_off_the_end_can_be_suppressed() {
+    let warnings = lint(
+        r#"
+        // huff-disable: main-fallthrough
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01
+        }
+    "#,
+    );
+    assert!(warnings.is_empty());
+}
@@ -0,0 +1,55 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+#[test]
+fn warns_on_misaligned_mload() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 mload
+            0x1f mload
+            0x01 0x40 mstore
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    cg.lint_memory_alignment(&contract);
+
+    assert_eq!(cg.warnings.len(), 1);
+    match &cg.warnings[0] {
+        CodegenWarning::MisalignedMemoryAccess { opcode, offset, macro_name, .. } => {
+            assert_eq!(*opcode, Opcode::Mload);
+            assert_eq!(*offset, 0x1f);
+            assert_eq!(macro_name, "MAIN");
+        }
+        w => panic!("Unexpected warning: {:?}", w),
+    }
+}
+
+#[test]
+fn checks_constant_offsets_but_not_byte_stores() {
+    let contract = parse(
+        r#"
+        #define constant OFFSET = 0x24
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01 [OFFSET] mstore
+            0x01 0x1f mstore8
+            0x04 calldataload mload
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    cg.lint_memory_alignment(&contract);
+
+    assert_eq!(cg.warnings.len(), 1);
+    assert!(matches!(
+        cg.warnings[0],
+        CodegenWarning::MisalignedMemoryAccess { opcode: Opcode::Mstore, offset: 0x24, .. }
+    ));
+}
@@ -0,0 +1,64 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+#[test]
+fn warns_on_mstore_outside_scratch_space() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01 0x00 mstore
+            0x01 0x20 mstore
+            0x01 0x100 mstore
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    cg.lint_memory_regions(&contract);
+
+    assert_eq!(cg.warnings.len(), 1);
+    match &cg.warnings[0] {
+        CodegenWarning::UndeclaredMemoryAccess { opcode, offset, macro_name, .. } => {
+            assert_eq!(*opcode, Opcode::Mstore);
+            assert_eq!(*offset, 0x100);
+            assert_eq!(macro_name, "MAIN");
+        }
+        w => panic!("Unexpected warning: {:?}", w),
+    }
+}
+
+#[test]
+fn declared_regions_and_constants_are_checked() {
+    let contract = parse(
+        r#"
+        // huff-memory: 0x80..0x120
+        #define constant BUFFER = 0x100
+        #define constant FREE_MEMORY = 0x40
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01 [BUFFER] mstore
+            0x01 0x11f mstore8
+            [FREE_MEMORY] mload
+            0x01 0x101 mstore
+        }
+    "#,
+    );
+    assert_eq!(contract.memory_regions, vec![(0x80, 0x120)]);
+
+    let mut cg = Codegen::new();
+    cg.lint_memory_regions(&contract);
+
+    // The free memory pointer slot isn't declared, and a word at 0x101 overruns the region
+    let offsets = cg
+        .warnings
+        .iter()
+        .map(|w| match w {
+            CodegenWarning::UndeclaredMemoryAccess { offset, .. } => *offset,
+            w => panic!("Unexpected warning: {:?}", w),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, vec![0x40, 0x101]);
+}
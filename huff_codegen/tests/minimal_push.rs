@@ -0,0 +1,66 @@
+use ethers_core::types::U256;
+use huff_codegen::Codegen;
+use huff_utils::prelude::{AstSpan, CodegenErrorKind};
+use proptest::prelude::*;
+
+/// Decodes bytecode that should be a single PUSH into its width and immediate value
+fn decode_push(bytecode: &str) -> (usize, U256) {
+    let bytes = hex::decode(bytecode).unwrap();
+    let (op, immediate) = bytes.split_first().unwrap();
+    assert!((0x60..=0x7f).contains(op), "0x{:02x} is not a PUSH", op);
+    let width = (op - 0x5f) as usize;
+    assert_eq!(immediate.len(), width, "PUSH{} immediate is truncated or followed by code", width);
+    (width, U256::from_big_endian(immediate))
+}
+
+fn assert_round_trips(literal: [u8; 32]) {
+    let bytecode = Codegen::compile_snippet(&format!("0x{}", hex::encode(literal))).unwrap();
+    let (width, value) = decode_push(&bytecode);
+
+    let value_bytes = literal.iter().skip_while(|b| **b == 0).count();
+    assert_eq!(value, U256::from_big_endian(&literal));
+    assert_eq!(width, value_bytes.max(1));
+}
+
+#[test]
+fn minimal_push_edge_cases() {
+    assert_round_trips([0u8; 32]);
+    assert_round_trips([0xffu8; 32]);
+
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    assert_round_trips(one);
+
+    // A set high bit in each byte position must widen the PUSH to exactly that byte
+    for i in 0..32 {
+        let mut high_bit = [0u8; 32];
+        high_bit[i] = 0x80;
+        assert_round_trips(high_bit);
+    }
+}
+
+#[test]
+fn rejects_pushes_wider_than_push32() {
+    // The largest word plus one carries into a 33rd byte
+    let computed = format!("{:x}", U256::MAX.full_mul(U256::one()) + 1);
+    let err = Codegen::push_literal(&computed, AstSpan(vec![])).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::PushTooLarge(33));
+
+    let max = "ff".repeat(32);
+    assert_eq!(Codegen::push_literal(&max, AstSpan(vec![])).unwrap(), format!("7f{}", max));
+    assert_eq!(Codegen::push_literal("abc", AstSpan(vec![])).unwrap(), "610abc");
+}
+
+proptest! {
+    #[test]
+    fn minimal_push_round_trips(literal in any::<[u8; 32]>()) {
+        assert_round_trips(literal);
+    }
+
+    #[test]
+    fn minimal_push_round_trips_short_values(value in any::<u64>()) {
+        let mut literal = [0u8; 32];
+        literal[24..].copy_from_slice(&value.to_be_bytes());
+        assert_round_trips(literal);
+    }
+}
@@ -0,0 +1,38 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use revm::primitives::{ExecutionResult, Output, U256};
+use std::sync::Arc;
+
+mod common;
+use common::{deploy_and_call_with_value, parse};
+
+#[test]
+fn guard_reverts_on_nonzero_callvalue() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01 pop
+            __nonpayable_guard()
+            0x2a 0x00 mstore
+            0x20 0x00 return
+        }
+    "#,
+    );
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(&main_bytecode[..28], "600150341561000d57600080fd5b");
+
+    let mut cg = Codegen::new();
+    let artifact = cg.churn(Arc::new(FileSource::default()), vec![], &main_bytecode, "").unwrap();
+
+    match deploy_and_call_with_value(&artifact.bytecode, 0) {
+        ExecutionResult::Success { output: Output::Call(out), .. } => {
+            assert_eq!(U256::try_from_be_slice(&out).unwrap(), U256::from(0x2a))
+        }
+        res => panic!("execution failed: {:?}", res),
+    }
+    match deploy_and_call_with_value(&artifact.bytecode, 1) {
+        ExecutionResult::Revert { output, .. } => assert!(output.is_empty()),
+        res => panic!("expected a revert: {:?}", res),
+    }
+}
@@ -0,0 +1,86 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use revm::{
+    inspector_handle_register,
+    interpreter::Interpreter,
+    primitives::{Bytes, CreateScheme, ExecutionResult, Output, TransactTo},
+    Database, Evm, EvmContext, InMemoryDB, Inspector,
+};
+use std::{collections::BTreeSet, sync::Arc};
+
+mod common;
+use common::parse;
+
+/// Records the program counter of every step once enabled, i.e. after deployment
+#[derive(Default)]
+struct PcRecorder {
+    enabled: bool,
+    pcs: BTreeSet<usize>,
+}
+
+impl<DB: Database> Inspector<DB> for PcRecorder {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if self.enabled {
+            self.pcs.insert(interp.program_counter());
+        }
+    }
+}
+
+/// Deploys the artifact, then calls its runtime once per calldata and reports the coverage
+fn coverage(artifact: &Artifact, calls: &[&str]) -> OpcodeCoverage {
+    let mut evm = Evm::builder()
+        .with_db(InMemoryDB::default())
+        .with_external_context(PcRecorder::default())
+        .append_handler_register(inspector_handle_register)
+        .modify_tx_env(|tx| {
+            tx.transact_to = TransactTo::Create(CreateScheme::Create);
+            tx.data = Bytes::from(hex::decode(&artifact.bytecode).unwrap());
+        })
+        .build();
+    let address = match evm.transact_commit().unwrap() {
+        ExecutionResult::Success { output: Output::Create(_, Some(address)), .. } => address,
+        res => panic!("deployment failed: {:?}", res),
+    };
+
+    evm.context.external.enabled = true;
+    for calldata in calls {
+        evm.tx_mut().transact_to = TransactTo::Call(address);
+        evm.tx_mut().data = Bytes::from(hex::decode(calldata).unwrap());
+        evm.tx_mut().nonce = None;
+        let res = evm.transact().unwrap().result;
+        assert!(res.is_success(), "execution failed: {:?}", res);
+    }
+    opcode_coverage(&artifact.runtime, &evm.context.external.pcs)
+}
+
+#[test]
+fn reports_executed_opcodes() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload iszero empty jumpi
+            0x01 0x00 mstore 0x20 0x00 return
+            empty:
+                stop
+        }
+    "#,
+    );
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let mut cg = Codegen::new();
+    let artifact = cg.churn(Arc::new(FileSource::default()), vec![], &main_bytecode, "").unwrap();
+
+    // Empty calldata only takes the jump to `empty`
+    let partial = coverage(&artifact, &[""]);
+    assert_eq!(partial.covered(), (7, 13));
+    assert_eq!(partial.missed_opcodes(), BTreeSet::from([Opcode::Mstore, Opcode::Return]));
+    assert_eq!(
+        partial.uncovered().iter().map(|i| i.mnemonic()).collect::<Vec<_>>(),
+        ["PUSH1", "PUSH1", "MSTORE", "PUSH1", "PUSH1", "RETURN"]
+    );
+    assert!(partial.to_string().starts_with("Executed 7/13 instructions\n"));
+
+    // A non-zero word falls through, so together the calls run every instruction
+    let full = coverage(&artifact, &["", &"01".repeat(32)]);
+    assert_eq!(full.covered(), (13, 13));
+    assert!(full.missed_opcodes().is_empty());
+}
@@ -0,0 +1,67 @@
+use huff_codegen::{Codegen, OpcodePolicy};
+use huff_utils::prelude::*;
+
+mod common;
+use common::{parse, parse_underived};
+
+const SOURCE: &str = r#"
+    #define macro KILL() = takes(0) returns(0) {
+        caller selfdestruct
+    }
+
+    #define macro MAIN() = takes(0) returns(0) {
+        0x00 calldataload iszero done jumpi
+        KILL()
+        done:
+            stop
+    }
+"#;
+
+#[test]
+fn rejects_denied_opcode() {
+    let contract = parse(SOURCE);
+    let cg = Codegen::new().with_opcode_policy(OpcodePolicy::Denied(vec![Opcode::Selfdestruct]));
+
+    let err = cg.check_opcode_policy(&contract).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::ForbiddenOpcode(Opcode::Selfdestruct));
+}
+
+#[test]
+fn rejects_opcodes_missing_from_allowed_set() {
+    let contract = parse(SOURCE);
+    let allowed = vec![
+        Opcode::Caller,
+        Opcode::Selfdestruct,
+        Opcode::Calldataload,
+        Opcode::Iszero,
+        Opcode::Jumpi,
+    ];
+    let cg = Codegen::new().with_opcode_policy(OpcodePolicy::Allowed(allowed));
+
+    // Statements inside labels are checked too
+    let err = cg.check_opcode_policy(&contract).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::ForbiddenOpcode(Opcode::Stop));
+}
+
+#[test]
+fn accepts_permitted_opcodes() {
+    let contract = parse(SOURCE);
+
+    let cg = Codegen::new().with_opcode_policy(OpcodePolicy::Denied(vec![Opcode::Delegatecall]));
+    assert!(cg.check_opcode_policy(&contract).is_ok());
+
+    // Without a policy every opcode is permitted
+    assert!(Codegen::new().check_opcode_policy(&contract).is_ok());
+}
+
+#[test]
+fn compile_contract_enforces_policy() {
+    let contract = parse_underived(SOURCE);
+
+    let mut cg =
+        Codegen::new().with_opcode_policy(OpcodePolicy::Denied(vec![Opcode::Selfdestruct]));
+    let err = cg
+        .compile_contract(contract, std::sync::Arc::new(FileSource::default()), vec![], false)
+        .unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::ForbiddenOpcode(Opcode::Selfdestruct));
+}
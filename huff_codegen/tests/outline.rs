@@ -0,0 +1,79 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+#[test]
+fn outlined_macro_is_jumped_to_and_returns() {
+    let contract = parse(
+        r#"
+        #define macro ADD_ONE() = takes(1) returns(1) @outline {
+            0x01 add
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x05 ADD_ONE() stop
+        }
+    "#,
+    );
+    assert!(contract.find_macro_by_name("ADD_ONE").unwrap().outlined);
+
+    let bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+
+    // PUSH2 <return> PUSH2 <body> JUMP JUMPDEST at the call site, the body after the code
+    assert_eq!(bytecode, "600561000961000b565b005b906001019056");
+    assert!(Codegen::verify_jumpdests(&bytecode).is_ok());
+}
+
+#[test]
+fn outlining_repeated_calls_shrinks_bytecode() {
+    let source = |decorator: &str| {
+        format!(
+            r#"
+            #define macro HASH_PAIR() = takes(2) returns(1) {} {{
+                0x00 mstore 0x20 mstore 0x40 0x00 sha3
+                dup1 0x01 add swap1 0x02 mul xor
+            }}
+
+            #define macro MAIN() = takes(0) returns(0) {{
+                0x01 0x02 HASH_PAIR()
+                0x03 HASH_PAIR()
+                0x04 HASH_PAIR()
+                0x05 HASH_PAIR()
+                0x00 mstore 0x20 0x00 return
+            }}
+        "#,
+            decorator
+        )
+    };
+
+    let inlined = Codegen::generate_main_bytecode(&parse(&source(""))).unwrap();
+    let outlined = Codegen::generate_main_bytecode(&parse(&source("@outline"))).unwrap();
+
+    assert!(outlined.len() < inlined.len());
+    assert!(Codegen::verify_jumpdests(&outlined).is_ok());
+}
+
+#[test]
+fn outlined_macros_cannot_take_arguments() {
+    let contract = parse(
+        r#"
+        #define macro PUSH_ARG(value) = takes(0) returns(1) @outline {
+            <value>
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            PUSH_ARG(0x01)
+        }
+    "#,
+    );
+
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(
+        err.kind,
+        CodegenErrorKind::InvalidArguments(
+            "Outlined macro \"PUSH_ARG\" can't take arguments".to_string()
+        )
+    );
+}
@@ -0,0 +1,81 @@
+use huff_codegen::Codegen;
+use revm::{
+    primitives::{
+        AccountInfo, Address, Bytecode, Bytes, ExecutionResult, Output, TransactTo, U256,
+    },
+    Evm, InMemoryDB,
+};
+
+mod common;
+use common::parse;
+
+fn compile(source: &str) -> String {
+    Codegen::generate_main_bytecode(&parse(source)).unwrap()
+}
+
+/// Calls the runtime bytecode and returns its output as a word
+fn execute(runtime: &str) -> U256 {
+    let address = Address::with_last_byte(0x42);
+    let mut db = InMemoryDB::default();
+    db.insert_account_info(
+        address,
+        AccountInfo {
+            code: Some(Bytecode::new_raw(Bytes::from(hex::decode(runtime).unwrap()))),
+            ..Default::default()
+        },
+    );
+
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .modify_tx_env(|tx| tx.transact_to = TransactTo::Call(address))
+        .build();
+    match evm.transact().unwrap().result {
+        ExecutionResult::Success { output: Output::Call(out), .. } => {
+            U256::try_from_be_slice(&out).unwrap()
+        }
+        res => panic!("execution failed: {:?}", res),
+    }
+}
+
+#[test]
+fn outlined_macro_returns_to_each_call_site() {
+    let runtime = compile(
+        r#"
+        #define macro ADD_ONE() = takes(1) returns(1) @outline {
+            0x01 add
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x05 ADD_ONE() ADD_ONE() ADD_ONE()
+            0x00 mstore 0x20 0x00 return
+        }
+    "#,
+    );
+
+    assert_eq!(execute(&runtime), U256::from(8));
+}
+
+#[test]
+fn outlined_macro_preserves_input_order() {
+    let runtime = compile(
+        r#"
+        #define macro SUBTRACT() = takes(2) returns(1) @outline {
+            sub
+        }
+
+        #define macro SPLIT() = takes(1) returns(2) @outline {
+            dup1 0x02 swap1 div swap1
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x03 0x0a SUBTRACT()    // [7]
+            SPLIT()                 // [7, 3]
+            SUBTRACT()              // [4]
+            0x60 add                // [100]
+            0x00 mstore 0x20 0x00 return
+        }
+    "#,
+    );
+
+    assert_eq!(execute(&runtime), U256::from(100));
+}
@@ -0,0 +1,87 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+#[test]
+fn explicit_push_takes_literal_as_immediate() {
+    // `push1 0x05` is a single PUSH1 rather than a PUSH1 followed by an auto-pushed literal
+    assert_eq!(Codegen::compile_snippet("push1 0x05").unwrap(), "6005");
+
+    // The immediate is padded to the explicit width
+    assert_eq!(Codegen::compile_snippet("push2 0x05").unwrap(), "610005");
+
+    // Literals without an explicit push are still auto-pushed
+    assert_eq!(Codegen::compile_snippet("push1 0x05 0x06 add").unwrap(), "6005600601");
+}
+
+#[test]
+fn push32_forces_a_full_width_immediate() {
+    // Minimal encoding would be the 2 byte `6001`
+    let bytecode = Codegen::compile_snippet("push32 0x01").unwrap();
+    assert_eq!(bytecode.len() / 2, 33);
+    assert_eq!(bytecode, format!("7f{}01", "00".repeat(31)));
+}
+
+#[test]
+fn explicit_push_widens_constants() {
+    let contract = parse(
+        r#"
+        #define constant PLACEHOLDER = 0x01
+        #define constant SLOT = FREE_STORAGE_POINTER()
+
+        #define macro MAIN() = takes(0) returns(0) {
+            push32 [PLACEHOLDER] push4 [SLOT] [PLACEHOLDER]
+        }
+    "#,
+    );
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(main_bytecode, format!("7f{}01{}{}", "00".repeat(31), "6300000000", "6001"));
+}
+
+#[test]
+fn explicit_push_without_fitting_constant() {
+    let contract = parse(
+        r#"
+        #define constant WIDE = 0x0102
+
+        #define macro MAIN() = takes(0) returns(0) {
+            push1 [WIDE]
+        }
+    "#,
+    );
+
+    // Like a literal, a constant wider than the push can't be its immediate
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::PushWidthMismatch(1, 2));
+}
+
+#[test]
+fn explicit_push_without_fitting_literal() {
+    // A literal wider than the push can't be its immediate
+    let err = Codegen::compile_snippet("push1 0x0102").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::PushWidthMismatch(1, 2));
+
+    // A push without an immediate would take the next opcode as one
+    let err = Codegen::compile_snippet("push1 add").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::MissingPushImmediate(1));
+    let err = Codegen::compile_snippet("push2").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::MissingPushImmediate(2));
+}
+
+#[test]
+fn address_literal_is_pushed_as_push20() {
+    let address = "0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef";
+    assert_eq!(
+        Codegen::compile_snippet(address).unwrap(),
+        "73deadbeefdeadbeefdeadbeefdeadbeefdeadbeef"
+    );
+
+    // Leading zero bytes are still trimmed to the minimal width
+    assert_eq!(
+        Codegen::compile_snippet("0x0000000000000000000000000000000000000001").unwrap(),
+        "6001"
+    );
+}
@@ -0,0 +1,56 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+#[test]
+fn warns_when_return_length_differs_from_annotation() {
+    let contract = parse(
+        r#"
+        // huff-returns: 0x20
+        #define constant WORD = 0x20
+
+        #define macro RETURN_TWO_WORDS() = takes(0) returns(0) {
+            0x40 0x00 return
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload skip jumpi
+            0x20 0x00 return
+            skip:
+                [WORD] 0x00 return
+                RETURN_TWO_WORDS()
+        }
+    "#,
+    );
+    assert_eq!(contract.return_size, Some(0x20));
+
+    let mut cg = Codegen::new();
+    cg.lint_return_size(&contract);
+
+    assert_eq!(cg.warnings.len(), 1);
+    match &cg.warnings[0] {
+        CodegenWarning::ReturnSizeMismatch { expected, actual, macro_name, .. } => {
+            assert_eq!(*expected, 0x20);
+            assert_eq!(*actual, 0x40);
+            assert_eq!(macro_name, "RETURN_TWO_WORDS");
+        }
+        w => panic!("Unexpected warning: {:?}", w),
+    }
+}
+
+#[test]
+fn does_not_check_without_an_annotation() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x40 0x00 return
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    cg.lint_return_size(&contract);
+    assert!(cg.warnings.is_empty());
+}
@@ -0,0 +1,41 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse_underived;
+
+#[test]
+fn errors_on_colliding_selectors() {
+    // Both hash to 0x23b872dd
+    let contract = parse_underived(
+        r#"
+        #define function transferFrom(address,address,uint256) nonpayable returns ()
+        #define function gasprice_bit_ether(int128) nonpayable returns ()
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 0x00 return
+        }
+    "#,
+    );
+    assert_eq!(contract.functions[0].signature, contract.functions[1].signature);
+
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(
+        err.kind,
+        CodegenErrorKind::SelectorCollision(
+            "transferFrom(address,address,uint256)".to_string(),
+            "gasprice_bit_ether(int128)".to_string()
+        )
+    );
+}
+
+#[test]
+fn distinct_selectors_do_not_collide() {
+    let contract = parse_underived(
+        r#"
+        #define function transfer(address,uint256) nonpayable returns ()
+        #define function transferFrom(address,address,uint256) nonpayable returns ()
+    "#,
+    );
+    assert!(Codegen::check_selector_collisions(&contract).is_ok());
+}
@@ -0,0 +1,14 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::CodegenErrorKind;
+
+#[test]
+fn compiles_opcode_snippet() {
+    let bytecode = Codegen::compile_snippet("0x01 0x02 add").unwrap();
+    assert_eq!(bytecode, "6001600201");
+}
+
+#[test]
+fn rejects_invalid_snippet() {
+    let err = Codegen::compile_snippet("0x01 }").unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidSnippet(_)));
+}
@@ -0,0 +1,82 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+mod common;
+use common::parse_underived;
+
+#[test]
+fn compiles_standard_json_input() {
+    let input = json!({
+        "language": "Huff",
+        "sources": {
+            "src/Adder.huff": {
+                "content": "#define function add(uint256,uint256) pure returns (uint256)\n#define macro MAIN() = takes(0) returns(0) {\n    0x04 calldataload 0x24 calldataload add\n    0x00 mstore 0x20 0x00 return\n}\n"
+            }
+        },
+        "settings": {}
+    });
+
+    let output: Value =
+        serde_json::from_str(&Codegen::compile_standard_json(&input.to_string())).unwrap();
+
+    assert_eq!(output["errors"], json!([]));
+    assert_eq!(output["sources"]["src/Adder.huff"]["id"], 0);
+    let contract = &output["contracts"]["src/Adder.huff"]["Adder"];
+    assert_eq!(contract["evm"]["deployedBytecode"]["object"], "6004356024350160005260206000f3");
+    assert!(contract["evm"]["bytecode"]["object"]
+        .as_str()
+        .unwrap()
+        .ends_with("6004356024350160005260206000f3"));
+    assert_eq!(contract["abi"][0]["name"], "add");
+    assert_eq!(contract["abi"][0]["stateMutability"], "pure");
+}
+
+#[test]
+fn reports_errors_in_standard_json_output() {
+    let input = json!({
+        "sources": { "src/Broken.huff": { "content": "#define macro NOT_MAIN() = takes(0) returns(0) {}" } }
+    });
+
+    let output: Value =
+        serde_json::from_str(&Codegen::compile_standard_json(&input.to_string())).unwrap();
+
+    assert_eq!(output["contracts"], json!({}));
+    let errors = output["errors"].as_array().unwrap();
+    assert!(errors.iter().any(|e| e["severity"] == "error" &&
+        e["type"] == "CodegenError" &&
+        e["sourceLocation"]["file"] == "src/Broken.huff"));
+
+    let output: Value = serde_json::from_str(&Codegen::compile_standard_json("{")).unwrap();
+    assert_eq!(output["errors"][0]["type"], "JSONError");
+}
+
+#[test]
+fn standard_json_matches_compile_contract() {
+    let content = r#"
+        #define function get() view returns (uint256)
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01 done jump
+            0x02
+            done:
+                0x00 mstore 0x20 0x00 return
+        }
+    "#;
+    let input = json!({
+        "sources": { "src/Getter.huff": { "content": content } },
+        "settings": { "optimizer": { "enabled": true } }
+    });
+    let output: Value =
+        serde_json::from_str(&Codegen::compile_standard_json(&input.to_string())).unwrap();
+    let contract = &output["contracts"]["src/Getter.huff"]["Getter"];
+
+    // Both go through the same pipeline, optimizer included
+    let parsed = parse_underived(content);
+    let artifact = Codegen::new()
+        .compile_contract(parsed, Arc::new(FileSource::default()), vec![], true)
+        .unwrap();
+    assert_eq!(contract["evm"]["bytecode"]["object"], artifact.bytecode);
+    assert_eq!(contract["evm"]["deployedBytecode"]["object"], artifact.runtime);
+    assert_eq!(contract["abi"], artifact.abi.unwrap().to_solc_json());
+}
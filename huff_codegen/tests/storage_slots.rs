@@ -0,0 +1,114 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse_underived;
+
+#[test]
+fn errors_when_free_pointer_takes_an_explicit_slot() {
+    let contract = parse_underived(
+        r#"
+        #define constant OWNER_SLOT = 0x00
+        #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+
+        #define macro MAIN() = takes(0) returns(0) {
+            [OWNER_SLOT] sload
+            [BALANCE_SLOT] sload
+        }
+    "#,
+    );
+
+    let err = Codegen::check_storage_slots(&contract).unwrap_err();
+    assert_eq!(
+        err.kind,
+        CodegenErrorKind::StorageSlotCollision(
+            "BALANCE_SLOT".to_string(),
+            "OWNER_SLOT".to_string()
+        )
+    );
+}
+
+#[test]
+fn allows_explicit_slots_the_free_pointers_skip() {
+    let contract = parse_underived(
+        r#"
+        #define constant OWNER_SLOT = 0x05
+        #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+
+        #define macro MAIN() = takes(0) returns(0) {
+            [OWNER_SLOT] sload
+            [BALANCE_SLOT] sload
+        }
+    "#,
+    );
+    assert!(Codegen::check_storage_slots(&contract).is_ok());
+}
+
+#[test]
+fn ignores_literal_constants_not_used_as_slots() {
+    let contract = parse_underived(
+        r#"
+        #define constant ZERO = 0x00
+        #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+
+        #define macro MAIN() = takes(0) returns(0) {
+            [ZERO] mload
+            [BALANCE_SLOT] sload
+        }
+    "#,
+    );
+    assert!(Codegen::check_storage_slots(&contract).is_ok());
+}
+
+#[test]
+fn errors_when_explicit_slot_is_passed_as_an_argument() {
+    let contract = parse_underived(
+        r#"
+        #define constant OWNER_SLOT = 0x00
+        #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+
+        #define macro STORE(slot) = takes(1) returns(0) {
+            <slot> sstore
+        }
+
+        #define macro FORWARD(slot) = takes(1) returns(0) {
+            STORE(<slot>)
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            caller FORWARD(OWNER_SLOT)
+            [BALANCE_SLOT] sload
+        }
+    "#,
+    );
+
+    // The slot reaches the sstore through two macro arguments
+    let err = Codegen::check_storage_slots(&contract).unwrap_err();
+    assert_eq!(
+        err.kind,
+        CodegenErrorKind::StorageSlotCollision(
+            "BALANCE_SLOT".to_string(),
+            "OWNER_SLOT".to_string()
+        )
+    );
+}
+
+#[test]
+fn ignores_arguments_not_used_as_slots() {
+    let contract = parse_underived(
+        r#"
+        #define constant ZERO = 0x00
+        #define constant BALANCE_SLOT = FREE_STORAGE_POINTER()
+
+        #define macro LOAD(offset) = takes(0) returns(1) {
+            <offset> mload
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            LOAD(ZERO)
+            [BALANCE_SLOT] sload
+        }
+    "#,
+    );
+    assert!(Codegen::check_storage_slots(&contract).is_ok());
+}
@@ -0,0 +1,92 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use std::sync::Arc;
+
+mod common;
+use common::parse;
+
+#[test]
+fn exports_label_offsets_with_the_artifact() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01 end jumpi
+            0x00 0x00 revert
+            end:
+                stop
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    let main_bytecode = cg.generate_main_bytecode_traced(&contract).unwrap();
+    let artifact = cg.churn(Arc::new(FileSource::default()), vec![], &main_bytecode, "").unwrap();
+
+    let end = artifact.symbols["end"];
+    assert_eq!(end, 11);
+    assert_eq!(&artifact.runtime[2 * end..2 * end + 2], "5b");
+}
+
+#[test]
+fn names_nested_labels_by_scope() {
+    let contract = parse(
+        r#"
+        #define macro INNER() = takes(0) returns(0) {
+            loop:
+                0x01 loop jumpi
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            loop:
+                INNER()
+                loop jump
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    cg.generate_main_bytecode_traced(&contract).unwrap();
+    assert_eq!(cg.symbols.get("loop"), Some(&0));
+    assert_eq!(cg.symbols.len(), 2);
+    assert!(cg.symbols.values().any(|pc| *pc == 1));
+}
+
+#[test]
+fn label_names_are_stable_across_compiles() {
+    let source = r#"
+        #define macro INNER() = takes(0) returns(0) {
+            loop:
+                0x01 loop jumpi
+        }
+
+        #define macro OUTER() = takes(0) returns(0) {
+            INNER()
+            done:
+                INNER()
+        }
+
+        #define macro HELPER() = takes(0) returns(0) @outline {
+            skip:
+                INNER()
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            OUTER() HELPER() OUTER()
+            end:
+                stop
+        }
+    "#;
+
+    let symbols = || {
+        let mut cg = Codegen::new();
+        cg.generate_main_bytecode_traced(&parse(source)).unwrap();
+        cg.symbols
+    };
+
+    let first = symbols();
+    assert_eq!(first, symbols());
+    // Expansions are numbered in order, innermost index first
+    for name in ["end", "done.0", "done.2", "loop.0.0", "loop.1.0", "loop.0.2", "loop.1.2"] {
+        assert!(first.contains_key(name), "missing {} in {:?}", name, first);
+    }
+}
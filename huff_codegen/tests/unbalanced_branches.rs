@@ -0,0 +1,48 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse;
+
+#[test]
+fn warns_when_branches_rejoin_with_different_depths() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload skip jumpi
+            0x01
+            skip:
+                stop
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    cg.lint_unbalanced_branches(&main_bytecode);
+
+    assert_eq!(
+        cg.warnings,
+        vec![CodegenWarning::UnbalancedBranch { offset: 6, jump_depth: 0, fallthrough_depth: 1 }]
+    );
+}
+
+#[test]
+fn balanced_branches_do_not_warn() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload skip jumpi
+            0x01 pop
+            skip:
+                0x00 0x00 return
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    cg.lint_unbalanced_branches(&main_bytecode);
+
+    assert!(cg.warnings.is_empty());
+}
@@ -0,0 +1,29 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::CodegenErrorKind;
+
+#[test]
+fn accepts_valid_jump_targets() {
+    // PUSH2 0x0005 JUMP STOP JUMPDEST PUSH1 0x00 PUSH1 0x05 JUMPI STOP
+    assert!(Codegen::verify_jumpdests("61000556005b600060055700").is_ok());
+
+    // Compiled output with labels verifies too
+    let bytecode = Codegen::compile_snippet("lab jump lab: 0x01 lab jumpi").unwrap();
+    assert!(Codegen::verify_jumpdests(&bytecode).is_ok());
+}
+
+#[test]
+fn rejects_bad_jump_target() {
+    // PUSH2 0x0006 JUMP STOP JUMPDEST ; 0x06 is past the end of the bytecode
+    let err = Codegen::verify_jumpdests("61000656005b").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::InvalidJumpTarget(6));
+
+    // A JUMPDEST byte inside PUSH data is not a valid target
+    let err = Codegen::verify_jumpdests("600456605b").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::InvalidJumpTarget(4));
+}
+
+#[test]
+fn rejects_invalid_hex() {
+    let err = Codegen::verify_jumpdests("0xzz").unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidBytecode(_)));
+}
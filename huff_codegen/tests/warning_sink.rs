@@ -0,0 +1,53 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+mod common;
+use common::parse_underived;
+
+#[derive(Debug, Default)]
+struct CountingSink {
+    count: AtomicUsize,
+}
+
+impl WarningSink for CountingSink {
+    fn warn(&self, _warning: &CodegenWarning) {
+        self.count.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn sink_receives_warnings_as_they_are_raised() {
+    let contract = parse_underived(
+        r#"
+        #define macro UNUSED() = takes(0) returns(0) {
+            0x01 pop
+        }
+
+        // huff-disable: unused-macro
+        #define macro SILENCED() = takes(0) returns(0) {
+            0x02 pop
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload jump
+        }
+    "#,
+    );
+
+    let sink = Arc::new(CountingSink::default());
+    let mut cg = Codegen::new().with_warning_sink(sink.clone());
+
+    // SILENCED is suppressed, so only UNUSED reaches the sink
+    cg.lint_unused_macros(&contract);
+    assert_eq!(sink.count.load(Ordering::SeqCst), 1);
+
+    cg.lint_dynamic_jumps(&contract);
+    assert_eq!(sink.count.load(Ordering::SeqCst), 2);
+
+    // The warnings are still collected as well
+    assert_eq!(cg.warnings.len(), 2);
+}
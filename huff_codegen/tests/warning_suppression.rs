@@ -0,0 +1,66 @@
+use huff_codegen::Codegen;
+use huff_utils::prelude::*;
+
+mod common;
+use common::parse_underived;
+
+#[test]
+fn warns_on_unused_macro() {
+    let contract = parse_underived(
+        r#"
+        #define macro HELPER() = takes(0) returns(0) {
+            0x01 pop
+        }
+
+        #define macro UNUSED() = takes(0) returns(0) {
+            0x02 pop
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            HELPER()
+        }
+    "#,
+    );
+
+    let mut cg = Codegen::new();
+    cg.lint_unused_macros(&contract);
+    assert_eq!(cg.warnings.len(), 1);
+    assert!(matches!(
+        &cg.warnings[0],
+        CodegenWarning::UnusedMacro { macro_name, .. } if macro_name == "UNUSED"
+    ));
+}
+
+#[test]
+fn directive_suppresses_warning_for_following_definition() {
+    let contract = parse_underived(
+        r#"
+        // huff-disable: unused-macro
+        #define macro UNUSED() = takes(0) returns(0) {
+            0x02 pop
+        }
+
+        /// Not covered by the directive above
+        #define macro ALSO_UNUSED() = takes(0) returns(0) {
+            0x03 pop
+        }
+
+        // huff-disable: dynamic-jump, deprecated-opcode
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 calldataload jump
+            caller selfdestruct
+        }
+    "#,
+    );
+    assert_eq!(contract.macros[0].suppressed_warnings, vec!["unused-macro".to_string()]);
+
+    let mut cg = Codegen::new();
+    cg.lint_unused_macros(&contract);
+    cg.lint_dynamic_jumps(&contract);
+    cg.lint_deprecated_opcodes(&contract);
+    assert_eq!(cg.warnings.len(), 1);
+    assert!(matches!(
+        &cg.warnings[0],
+        CodegenWarning::UnusedMacro { macro_name, .. } if macro_name == "ALSO_UNUSED"
+    ));
+}
@@ -7,16 +7,18 @@ use huff_utils::prelude::*;
 use std::{path::PathBuf, sync::Arc};
 
 fn lex_erc20_from_source_benchmark(c: &mut Criterion) {
-    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![PathBuf::from(
-        "../huff-examples/erc20/contracts/ERC20.huff".to_string(),
-    )])
+    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(
+        vec![PathBuf::from("../huff-examples/erc20/contracts/ERC20.huff".to_string())],
+        Arc::new(FileSystemFileProvider),
+    )
     .into_iter()
     .map(|p| p.unwrap())
     .collect();
 
     // Recurse file deps + generate flattened source
     let file_source = file_sources.get(0).unwrap();
-    let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
+    let recursed_file_source =
+        Compiler::recurse_deps(Arc::clone(file_source), Arc::new(FileSystemFileProvider)).unwrap();
     let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
     let full_source = FullFileSource {
         source: &flattened.0,
@@ -34,16 +36,18 @@ fn lex_erc20_from_source_benchmark(c: &mut Criterion) {
 }
 
 fn parse_erc20_benchmark(c: &mut Criterion) {
-    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![PathBuf::from(
-        "../huff-examples/erc20/contracts/ERC20.huff".to_string(),
-    )])
+    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(
+        vec![PathBuf::from("../huff-examples/erc20/contracts/ERC20.huff".to_string())],
+        Arc::new(FileSystemFileProvider),
+    )
     .into_iter()
     .map(|p| p.unwrap())
     .collect();
 
     // Recurse file deps + generate flattened source
     let file_source = file_sources.get(0).unwrap();
-    let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
+    let recursed_file_source =
+        Compiler::recurse_deps(Arc::clone(file_source), Arc::new(FileSystemFileProvider)).unwrap();
     let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
     let full_source = FullFileSource {
         source: &flattened.0,
@@ -66,16 +70,18 @@ fn parse_erc20_benchmark(c: &mut Criterion) {
 }
 
 fn codegen_erc20_benchmark(c: &mut Criterion) {
-    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![PathBuf::from(
-        "../huff-examples/erc20/contracts/ERC20.huff".to_string(),
-    )])
+    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(
+        vec![PathBuf::from("../huff-examples/erc20/contracts/ERC20.huff".to_string())],
+        Arc::new(FileSystemFileProvider),
+    )
     .into_iter()
     .map(|p| p.unwrap())
     .collect();
 
     // Recurse file deps + generate flattened source
     let file_source = file_sources.get(0).unwrap();
-    let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
+    let recursed_file_source =
+        Compiler::recurse_deps(Arc::clone(file_source), Arc::new(FileSystemFileProvider)).unwrap();
     let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
     let full_source = FullFileSource {
         source: &flattened.0,
@@ -112,14 +118,14 @@ fn erc20_compilation_benchmark(c: &mut Criterion) {
     c.bench_function("Full ERC-20 compilation", |b| b.iter(|| {
         let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![PathBuf::from(
             "../huff-examples/erc20/contracts/ERC20.huff".to_string(),
-        )])
+        )], Arc::new(FileSystemFileProvider))
             .into_iter()
             .map(|p| p.unwrap())
             .collect();
 
         // Recurse file deps + generate flattened source
         let file_source = file_sources.get(0).unwrap();
-        let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
+        let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source), Arc::new(FileSystemFileProvider)).unwrap();
         let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
         let full_source = FullFileSource {
             source: &flattened.0,
@@ -152,14 +158,14 @@ fn erc721_compilation_benchmark(c: &mut Criterion) {
     c.bench_function("Full ERC-721 compilation", |b| b.iter(|| {
         let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![PathBuf::from(
             "../huff-examples/erc721/contracts/ERC721.huff".to_string(),
-        )])
+        )], Arc::new(FileSystemFileProvider))
             .into_iter()
             .map(|p| p.unwrap())
             .collect();
 
         // Recurse file deps + generate flattened source
         let file_source = file_sources.get(0).unwrap();
-        let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
+        let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source), Arc::new(FileSystemFileProvider)).unwrap();
         let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
         let full_source = FullFileSource {
             source: &flattened.0,
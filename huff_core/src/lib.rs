@@ -9,11 +9,12 @@ use huff_lexer::*;
 use huff_parser::*;
 use huff_utils::prelude::*;
 use rayon::prelude::*;
+#[cfg(feature = "std-fs")]
+use std::fs;
 use std::{
     ffi::OsString,
-    fs,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, OnceLock},
     time::SystemTime,
 };
 use tracing_subscriber::{filter::Directive, EnvFilter};
@@ -42,7 +43,7 @@ use uuid::Uuid;
 ///     false
 /// );
 /// ```
-#[derive(Default, Debug, Clone)]
+#[derive(Debug, Clone)]
 pub struct Compiler {
     /// The location of the files to compile
     pub sources: Arc<Vec<String>>,
@@ -54,6 +55,49 @@ pub struct Compiler {
     pub optimize: bool,
     /// Generate and log bytecode
     pub bytecode: bool,
+    /// The provider used to read sources and resolve includes
+    pub file_provider: Arc<dyn FileProvider>,
+    /// The maximum depth of nested includes
+    pub max_include_depth: usize,
+    /// Whether artifact bytecode is `0x` prefixed
+    pub hex_prefix: bool,
+    /// Whether the runtime ends with the compiler version
+    pub metadata: bool,
+    /// Restricts the opcodes compiled contracts may use
+    pub opcode_policy: Option<OpcodePolicy>,
+    /// A JSON file of constant values injected into compiled contracts
+    pub constants_json: Option<String>,
+    /// The constants parsed from [constants_json](Compiler::constants_json), read through the
+    /// file provider the first time a file is compiled and shared by the rest
+    pub parsed_constants: OnceLock<Result<Vec<(String, Literal)>, CodegenError>>,
+    /// Receives codegen warnings as they are raised
+    pub warning_sink: Option<Arc<dyn WarningSink>>,
+}
+
+/// The default maximum depth of nested includes
+pub const DEFAULT_MAX_INCLUDE_DEPTH: usize = 64;
+
+impl Default for Compiler {
+    fn default() -> Self {
+        Self {
+            sources: Arc::new(vec![]),
+            output: None,
+            construct_args: None,
+            optimize: false,
+            bytecode: false,
+            #[cfg(feature = "std-fs")]
+            file_provider: Arc::new(FileSystemFileProvider),
+            #[cfg(not(feature = "std-fs"))]
+            file_provider: Arc::new(InMemoryFileProvider::default()),
+            max_include_depth: DEFAULT_MAX_INCLUDE_DEPTH,
+            hex_prefix: false,
+            metadata: false,
+            opcode_policy: None,
+            constants_json: None,
+            parsed_constants: OnceLock::new(),
+            warning_sink: None,
+        }
+    }
 }
 
 impl<'a> Compiler {
@@ -67,7 +111,52 @@ impl<'a> Compiler {
         if cfg!(feature = "verbose") || verbose {
             Compiler::init_tracing_subscriber(Some(vec![tracing::Level::INFO.into()]));
         }
-        Self { sources, output, construct_args, optimize: false, bytecode: false }
+        Self { sources, output, construct_args, ..Default::default() }
+    }
+
+    /// Sets the provider used to read sources and resolve includes
+    pub fn with_file_provider(mut self, file_provider: Arc<dyn FileProvider>) -> Self {
+        self.file_provider = file_provider;
+        self.parsed_constants = OnceLock::new();
+        self
+    }
+
+    /// Sets the maximum depth of nested includes
+    pub fn with_max_include_depth(mut self, max_include_depth: usize) -> Self {
+        self.max_include_depth = max_include_depth;
+        self
+    }
+
+    /// Sets whether artifact bytecode is `0x` prefixed
+    pub fn with_hex_prefix(mut self, hex_prefix: bool) -> Self {
+        self.hex_prefix = hex_prefix;
+        self
+    }
+
+    /// Sets whether the runtime ends with the compiler version
+    pub fn with_metadata(mut self, metadata: bool) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Restricts the opcodes compiled contracts may use
+    pub fn with_opcode_policy(mut self, policy: OpcodePolicy) -> Self {
+        self.opcode_policy = Some(policy);
+        self
+    }
+
+    /// Injects constant values from a JSON file read through the file provider, see
+    /// [parse_constants_json](Codegen::parse_constants_json)
+    pub fn with_constants_json(mut self, path: String) -> Self {
+        self.constants_json = Some(path);
+        self.parsed_constants = OnceLock::new();
+        self
+    }
+
+    /// Installs a sink that receives codegen warnings as they are raised
+    pub fn with_warning_sink(mut self, sink: Arc<dyn WarningSink>) -> Self {
+        self.warning_sink = Some(sink);
+        self
     }
 
     /// Tracing
@@ -98,13 +187,14 @@ impl<'a> Compiler {
     /// 4. For each top-level file [Parallelized], generate the artifact using
     /// [gen_artifact](Compiler::gen_artifact).
     /// 5. Return the compiling error(s) or successfully generated artifacts.
+    #[cfg(feature = "std-fs")]
     pub fn execute(&self) -> Result<Vec<Arc<Artifact>>, Arc<CompilerError<'a>>> {
         // Grab the input files
         let file_paths: Vec<PathBuf> = Compiler::transform_paths(&self.sources)?;
 
         // Parallel file fetching
         let files: Vec<Result<Arc<FileSource>, CompilerError>> =
-            Compiler::fetch_sources(file_paths);
+            Compiler::fetch_sources(file_paths, Arc::clone(&self.file_provider));
 
         // Unwrap errors
         let mut errors =
@@ -121,8 +211,10 @@ impl<'a> Compiler {
             .collect::<Vec<Arc<FileSource>>>();
 
         // Parallel Dependency Resolution
-        let recursed_file_sources: Vec<Result<Arc<FileSource>, Arc<CompilerError<'a>>>> =
-            files.into_par_iter().map(Compiler::recurse_deps).collect();
+        let recursed_file_sources: Vec<Result<Arc<FileSource>, Arc<CompilerError<'a>>>> = files
+            .into_par_iter()
+            .map(|f| Compiler::recurse_deps(f, Arc::clone(&self.file_provider)))
+            .collect();
 
         // Collect Recurse Deps errors and try to resolve to the first one
         let mut errors = recursed_file_sources
@@ -172,6 +264,27 @@ impl<'a> Compiler {
         Ok(artifacts)
     }
 
+    /// Compiles a source string into an Artifact.
+    ///
+    /// Includes are resolved relative to the working directory through the compiler's
+    /// [FileProvider](FileProvider), so this path does not touch the filesystem unless the
+    /// provider does.
+    pub fn compile_str(&self, source: &str) -> Result<Artifact, Arc<CompilerError<'a>>> {
+        let file = Arc::new(FileSource {
+            id: Uuid::new_v4(),
+            path: String::default(),
+            source: Some(source.to_string()),
+            access: None,
+            dependencies: None,
+        });
+        let file = Compiler::recurse_deps_with_limit(
+            file,
+            Arc::clone(&self.file_provider),
+            self.max_include_depth,
+        )?;
+        self.gen_artifact(file).map_err(Arc::new)
+    }
+
     /// Artifact Generation
     ///
     /// Compiles a FileSource into an Artifact.
@@ -197,19 +310,32 @@ impl<'a> Compiler {
         tracing::info!(target: "core", "└─ TOKEN COUNT: {}", tokens.len());
 
         // Parser incantation
-        let mut parser = Parser::new(tokens, Some(file.path.clone()));
+        let mut parser = Parser::new(tokens, Some(file.path.clone()))
+            .with_file_provider(Arc::clone(&self.file_provider));
 
         // Parse into an AST
         let parse_res = parser.parse().map_err(CompilerError::ParserError);
-        let mut contract = parse_res?;
-        contract.derive_storage_pointers();
+        let contract = parse_res?;
         tracing::info!(target: "core", "PARSED CONTRACT [{}]", file.path);
 
         // Primary Bytecode Generation
         // See huffc: https://github.com/huff-language/huffc/blob/2e5287afbfdf9cc977b204a4fd1e89c27375b040/src/compiler/processor.ts
-        let mut cg = Codegen::new();
-        let main_bytecode = match Codegen::generate_main_bytecode(&contract) {
-            Ok(mb) => mb,
+        let mut cg = Codegen::new().with_hex_prefix(self.hex_prefix).with_metadata(self.metadata);
+        if let Some(sink) = &self.warning_sink {
+            cg = cg.with_warning_sink(Arc::clone(sink));
+        }
+        cg = cg.with_injected_constants(
+            self.injected_constants().map_err(CompilerError::CodegenError)?,
+        );
+        if let Some(policy) = &self.opcode_policy {
+            cg = cg.with_opcode_policy(policy.clone());
+        }
+        let inputs = self.get_constructor_args();
+        match cg.compile_contract(contract, Arc::clone(&file), inputs, self.optimize) {
+            Ok(artifact) => {
+                tracing::info!(target: "core", "GENERATED ARTIFACT [{}]", file.path);
+                Ok(artifact)
+            }
             Err(mut e) => {
                 // Add File Source to Span
                 e.span = AstSpan(
@@ -217,77 +343,44 @@ impl<'a> Compiler {
                         .0
                         .into_iter()
                         .map(|mut s| {
-                            s.file = Some(Arc::clone(&file));
+                            s.file = s.file.or_else(|| Some(Arc::clone(&file)));
                             s
                         })
                         .collect::<Vec<Span>>(),
                 );
-                tracing::error!(target: "codegen", "Roll Failed with CodegenError: {:?}", e);
-                return Err(CompilerError::CodegenError(e))
-            }
-        };
-        tracing::info!(target: "core", "MAIN BYTECODE GENERATED [{}]", main_bytecode);
-        let inputs = self.get_constructor_args();
-        let constructor_bytecode = match Codegen::generate_constructor_bytecode(&contract) {
-            Ok(mb) => mb,
-            Err(mut e) => {
-                if !inputs.is_empty() {
-                    // Add File Source to Span
-                    e.span = AstSpan(
-                        e.span
-                            .0
-                            .into_iter()
-                            .map(|mut s| {
-                                s.file = Some(Arc::clone(&file));
-                                s
-                            })
-                            .collect::<Vec<Span>>(),
-                    );
-                    tracing::error!(target: "codegen", "Constructor inputs provided, but contract missing \"CONSTRUCTOR\" macro!");
-                    return Err(CompilerError::CodegenError(e))
-                }
-                tracing::warn!(target: "codegen", "Contract has no \"CONSTRUCTOR\" macro definition!");
-                "".to_string()
-            }
-        };
-
-        // Encode Constructor Arguments
-        tracing::info!(target: "core", "CONSTRUCTOR BYTECODE GENERATED [{}]", constructor_bytecode);
-        tracing::info!(target: "core", "ENCODING {} INPUTS", inputs.len());
-        let encoded_inputs = Codegen::encode_constructor_args(inputs);
-        tracing::info!(target: "core", "ENCODED {} INPUTS", encoded_inputs.len());
-
-        // Generate Artifact with ABI
-        let churn_res = cg.churn(file, encoded_inputs, &main_bytecode, &constructor_bytecode);
-        match churn_res {
-            Ok(mut artifact) => {
-                // Then we can have the code gen output the artifact
-                let abiout = cg.abi_gen(contract, None);
-                match abiout {
-                    Ok(abi) => {
-                        tracing::info!(target: "core", "GENERATED ABI");
-                        artifact.abi = Some(abi)
-                    }
-                    Err(e) => {
-                        tracing::error!(target: "core", "ARTIFACT GENERATION FAILED: {:?}", e)
-                    }
-                }
-                Ok(artifact)
-            }
-            Err(e) => {
                 tracing::error!(target: "core", "CODEGEN ERRORED!\nError: {:?}", e);
                 Err(CompilerError::CodegenError(e))
             }
         }
     }
 
-    /// Get the file sources for a vec of PathBufs
-    pub fn fetch_sources(paths: Vec<PathBuf>) -> Vec<Result<Arc<FileSource>, CompilerError<'a>>> {
+    /// Returns the constants of the [constants_json](Compiler::constants_json) file, reading
+    /// and parsing it through the file provider only once
+    pub fn injected_constants(&self) -> Result<Vec<(String, Literal)>, CodegenError> {
+        let Some(path) = &self.constants_json else { return Ok(vec![]) };
+        self.parsed_constants
+            .get_or_init(|| {
+                tracing::info!(target: "core", "READING CONSTANTS JSON \"{}\"", path);
+                let json = self.file_provider.read_to_string(path).map_err(|e| CodegenError {
+                    kind: CodegenErrorKind::IOError(format!("{}: {:?}", path, e)),
+                    span: AstSpan(vec![]),
+                    token: None,
+                })?;
+                Codegen::parse_constants_json(&json)
+            })
+            .clone()
+    }
+
+    /// Get the file sources for a vec of PathBufs, read through the given file provider
+    pub fn fetch_sources(
+        paths: Vec<PathBuf>,
+        file_provider: Arc<dyn FileProvider>,
+    ) -> Vec<Result<Arc<FileSource>, CompilerError<'a>>> {
         paths
             .into_par_iter()
             .map(|pb| {
                 let file_loc = String::from(pb.to_string_lossy());
-                match std::fs::read_to_string(&file_loc) {
+                match file_provider.read_to_string(&file_loc) {
                     Ok(source) => Ok(Arc::new(FileSource {
                         id: Uuid::new_v4(),
                         path: file_loc,
@@ -304,14 +397,35 @@ impl<'a> Compiler {
             .collect()
     }
 
-    /// Recurses file dependencies
-    pub fn recurse_deps(fs: Arc<FileSource>) -> Result<Arc<FileSource>, Arc<CompilerError<'a>>> {
+    /// Recurses file dependencies, reading them through the given file provider
+    pub fn recurse_deps(
+        fs: Arc<FileSource>,
+        file_provider: Arc<dyn FileProvider>,
+    ) -> Result<Arc<FileSource>, Arc<CompilerError<'a>>> {
+        Compiler::recurse_deps_with_limit(fs, file_provider, DEFAULT_MAX_INCLUDE_DEPTH)
+    }
+
+    /// Recurses file dependencies, failing if includes nest deeper than `max_depth`
+    pub fn recurse_deps_with_limit(
+        fs: Arc<FileSource>,
+        file_provider: Arc<dyn FileProvider>,
+        max_depth: usize,
+    ) -> Result<Arc<FileSource>, Arc<CompilerError<'a>>> {
+        Compiler::recurse_deps_at_depth(fs, file_provider, 0, max_depth)
+    }
+
+    fn recurse_deps_at_depth(
+        fs: Arc<FileSource>,
+        file_provider: Arc<dyn FileProvider>,
+        depth: usize,
+        max_depth: usize,
+    ) -> Result<Arc<FileSource>, Arc<CompilerError<'a>>> {
         let mut new_fs = FileSource { path: fs.path.clone(), ..Default::default() };
         let file_source = if let Some(s) = &fs.source {
             s.clone()
         } else {
             // Read from path
-            let new_source = match std::fs::read_to_string(&fs.path) {
+            let new_source = match file_provider.read_to_string(&fs.path) {
                 Ok(source) => source,
                 Err(_) => {
                     tracing::error!(target: "core", "FILE READ FAILED: \"{}\"!", fs.path);
@@ -326,6 +440,13 @@ impl<'a> Compiler {
         if !imports.is_empty() {
             tracing::info!(target: "core", "IMPORT LEXICAL ANALYSIS COMPLETE ON {:?}", imports);
         }
+        if !imports.is_empty() && depth >= max_depth {
+            tracing::error!(target: "core", "INCLUDE DEPTH EXCEEDED IN \"{}\"", fs.path);
+            return Err(Arc::new(CompilerError::ParserError(ParserError {
+                kind: ParserErrorKind::IncludeDepthExceeded(max_depth),
+                spans: AstSpan(vec![Span { start: 0, end: 0, file: Some(Arc::clone(&fs)) }]),
+            })))
+        }
         let localized_imports: Vec<String> = imports
             .iter()
             .map(|import| {
@@ -335,13 +456,23 @@ impl<'a> Compiler {
                     1,
                 )
             })
+            .flat_map(|import| {
+                if !import.contains(['*', '?']) {
+                    return vec![import]
+                }
+                let matches = expand_glob(&import, &*file_provider);
+                if matches.is_empty() {
+                    tracing::warn!(target: "core", "INCLUDE GLOB \"{}\" MATCHED NO FILES", import);
+                }
+                matches
+            })
             .collect();
         if !localized_imports.is_empty() {
             tracing::info!(target: "core", "LOCALIZED IMPORTS {:?}", localized_imports);
         }
         let import_bufs: Vec<PathBuf> = Compiler::transform_paths(&localized_imports)?;
         let potentials: Result<Vec<Arc<FileSource>>, CompilerError> =
-            Compiler::fetch_sources(import_bufs).into_iter().collect();
+            Compiler::fetch_sources(import_bufs, Arc::clone(&file_provider)).into_iter().collect();
         let mut file_sources = match potentials {
             Ok(p) => p,
             Err(e) => return Err(Arc::new(e)),
@@ -351,16 +482,34 @@ impl<'a> Compiler {
         }
 
         // Now that we have all the file sources, we have to recurse and get their source
+        // Exceeding the include depth is fatal, other nested failures keep the unresolved source
         file_sources = file_sources
             .into_par_iter()
-            .map(|inner_fs| match Compiler::recurse_deps(Arc::clone(&inner_fs)) {
-                Ok(new_fs) => new_fs,
-                Err(e) => {
-                    tracing::error!(target: "core", "NESTED DEPENDENCY RESOLUTION FAILED: \"{:?}\"", e);
-                    Arc::clone(&inner_fs)
+            .map(|inner_fs| {
+                match Compiler::recurse_deps_at_depth(
+                    Arc::clone(&inner_fs),
+                    Arc::clone(&file_provider),
+                    depth + 1,
+                    max_depth,
+                ) {
+                    Ok(new_fs) => Ok(new_fs),
+                    Err(e) if matches!(
+                        &*e,
+                        CompilerError::ParserError(ParserError {
+                            kind: ParserErrorKind::IncludeDepthExceeded(_),
+                            ..
+                        })
+                    ) =>
+                    {
+                        Err(e)
+                    }
+                    Err(e) => {
+                        tracing::error!(target: "core", "NESTED DEPENDENCY RESOLUTION FAILED: \"{:?}\"", e);
+                        Ok(Arc::clone(&inner_fs))
+                    }
                 }
             })
-            .collect();
+            .collect::<Result<Vec<Arc<FileSource>>, Arc<CompilerError<'a>>>>()?;
 
         // Finally set the parent deps
         new_fs.dependencies = Some(file_sources);
@@ -372,6 +521,7 @@ impl<'a> Compiler {
     ///
     /// 1. Cleans any previous artifacts in the output directory.
     /// 2. Exports artifacts in parallel as serialized json `Artifact` objects.
+    #[cfg(feature = "std-fs")]
     pub fn export_artifacts(artifacts: &Vec<Arc<Artifact>>, output: &OutputLocation) {
         // Exit if empty output location
         if output.0.is_empty() {
@@ -407,6 +557,39 @@ impl<'a> Compiler {
         });
     }
 
+    /// Combines the ABIs of several contracts into one json object.
+    ///
+    /// Follows the `solc --combined-json abi` layout used by forge and hardhat, keying each
+    /// solc-style ABI by `<path>:<ContractName>` where the contract name is the file stem.
+    pub fn combined_abi(paths_to_abis: &[(String, Abi)]) -> serde_json::Value {
+        let contracts = paths_to_abis
+            .iter()
+            .map(|(path, abi)| {
+                let name = Path::new(path).file_stem().unwrap_or_default().to_string_lossy();
+                (format!("{}:{}", path, name), serde_json::json!({ "abi": abi.to_solc_json() }))
+            })
+            .collect::<serde_json::Map<String, serde_json::Value>>();
+        serde_json::json!({ "contracts": contracts, "version": env!("CARGO_PKG_VERSION") })
+    }
+
+    /// Export Combined ABI
+    ///
+    /// Writes the [combined ABI](Compiler::combined_abi) of several contracts to a single json
+    /// file at `out`.
+    #[cfg(feature = "std-fs")]
+    pub fn export_combined_abi(
+        paths_to_abis: &[(String, Abi)],
+        out: &str,
+    ) -> Result<(), std::io::Error> {
+        let serialized = serde_json::to_string_pretty(&Compiler::combined_abi(paths_to_abis))?;
+        if let Some(p) = Path::new(out).parent() {
+            fs::create_dir_all(p)?
+        }
+        fs::write(out, serialized)?;
+        tracing::info!(target: "core", "EXPORTED COMBINED ABI TO \"{}\"", out);
+        Ok(())
+    }
+
     /// Transforms File Strings into PathBufs
     pub fn transform_paths(sources: &Vec<String>) -> Result<Vec<PathBuf>, CompilerError<'a>> {
         let mut paths = vec![];
@@ -415,6 +598,11 @@ impl<'a> Compiler {
             let ext = Path::new(&f).extension().unwrap_or_default();
             if ext.eq("huff") {
                 paths.push(Path::new(&f).to_path_buf())
+            } else if cfg!(not(feature = "std-fs")) {
+                // Directories can only be unpacked from disk
+                return Err(CompilerError::FileUnpackError(UnpackError::UnsupportedExtension(
+                    f.clone(),
+                )))
             } else {
                 // Otherwise, override the source files and use all files in the provided dir
                 match unpack_files(f) {
@@ -45,6 +45,42 @@ fn test_codesize_builtin() {
     assert_eq!(cbytes, String::from("6004"));
 }
 
+#[test]
+fn test_codesize_builtin_uses_minimal_push() {
+    let large_body = "0x01 pop ".repeat(100);
+    let source = format!(
+        r#"
+        #define macro SMALL() = takes(0) returns(0) {{
+            0x01 0x02 add
+        }}
+
+        #define macro LARGE() = takes(0) returns(0) {{
+            {}
+        }}
+
+        #define macro MAIN() = takes(0) returns (0) {{
+            __codesize(SMALL)
+            __codesize(LARGE)
+        }}
+    "#,
+        large_body
+    );
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // Parse the AST
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+
+    // SMALL is 5 bytes and fits a PUSH1, LARGE is 300 bytes and needs a PUSH2
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(mbytes, "600561012c");
+}
+
 #[test]
 fn test_tablesize_builtin() {
     let source: &str = r#"
@@ -112,6 +148,43 @@ fn test_tablesize_builtin() {
     assert_eq!(mbytes, String::from("60085b60006000f35b60006000f35b60006000f35b60006000f300020008000e001400000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000e0000000000000000000000000000000000000000000000000000000000000014"));
 }
 
+#[test]
+fn test_tablesize_is_byte_length() {
+    let source: &str = r#"
+        #define jumptable__packed PACKED_JUMPTABLE {
+            lab_0 lab_1 lab_2 lab_3
+        }
+
+        #define jumptable STANDARD_JUMPTABLE {
+            lab_0 lab_1 lab_2 lab_3
+        }
+
+        #define macro MAIN() = takes(0) returns (0) {
+            __tablesize(STANDARD_JUMPTABLE)
+            __tablesize(PACKED_JUMPTABLE)
+
+            lab_0: 0x00 0x00 return
+            lab_1: 0x00 0x00 return
+            lab_2: 0x00 0x00 return
+            lab_3: 0x00 0x00 return
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // Parse the AST
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+
+    // 4 entries * 0x20 bytes = 0x80 and 4 entries * 0x02 bytes = 0x08
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert!(mbytes.starts_with("60806008"));
+}
+
 #[test]
 fn test_tablestart_builtin() {
     let source: &str = r#"
@@ -177,6 +250,66 @@ fn test_tablestart_builtin() {
     assert_eq!(cbytes, String::from("61001e6100265b60006000f35b60006000f35b60006000f35b60006000f30006000c001200180000000000000000000000000000000000000000000000000000000000000006000000000000000000000000000000000000000000000000000000000000000c00000000000000000000000000000000000000000000000000000000000000120000000000000000000000000000000000000000000000000000000000000018"));
 }
 
+#[test]
+fn test_tablestart_packed_points_at_table_bytes() {
+    let source: &str = r#"
+        #define constant MAGIC = 0xdeadbeef
+
+        #define table CODE_TABLE {
+            [MAGIC]
+        }
+
+        #define jumptable STANDARD_JUMPTABLE {
+            lab_0 lab_1
+        }
+
+        #define jumptable__packed PACKED_JUMPTABLE {
+            lab_0 lab_1 lab_1 lab_0
+        }
+
+        #define macro MAIN() = takes(0) returns (0) {
+            __tablesize(PACKED_JUMPTABLE) __tablestart(PACKED_JUMPTABLE) 0x00 codecopy
+
+            lab_0:
+                0x00 0x00 return
+            lab_1:
+                0x20 0x00 return
+        }
+    "#;
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+    let bytes = ethers_core::utils::hex::decode(&main_bytecode).unwrap();
+
+    // Disassemble the code up to the final RETURN, collecting JUMPDESTs and the __tablestart PUSH2
+    let (mut pc, mut returns, mut jumpdests, mut table_start) = (0, 0, vec![], None);
+    while returns < 2 {
+        match bytes[pc] {
+            0x5b => jumpdests.push(pc),
+            0xf3 => returns += 1,
+            0x61 if table_start.is_none() => {
+                table_start = Some(u16::from_be_bytes([bytes[pc + 1], bytes[pc + 2]]) as usize)
+            }
+            _ => {}
+        }
+        pc += 1 + if (0x60..=0x7f).contains(&bytes[pc]) { (bytes[pc] - 0x5f) as usize } else { 0 };
+    }
+
+    // The code table (4 bytes) and standard jump table (2 * 32 bytes) precede the packed table
+    let table_start = table_start.unwrap();
+    assert_eq!(table_start, pc + 4 + 2 * 0x20);
+    let entries = bytes[table_start..]
+        .chunks(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]) as usize)
+        .collect::<Vec<usize>>();
+    assert_eq!(entries, vec![jumpdests[0], jumpdests[1], jumpdests[1], jumpdests[0]]);
+}
+
 #[test]
 fn test_jump_table_exhaustive_usage() {
     let source: &str = r#"
@@ -237,7 +370,7 @@ fn test_jump_table_exhaustive_usage() {
 
     // Have the Codegen create the constructor bytecode
     let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
-    assert_eq!(mbytes, String::from("61012861004160003960003560e01c8063a9059cbb1461001a575b60208703516202ffe016806020015b60206020015b60206020015b60206020015b60206020010000000000000000000000000000000000000000000000000000000000000029000000000000000000000000000000000000000000000000000000000000002f0000000000000000000000000000000000000000000000000000000000000035000000000000000000000000000000000000000000000000000000000000003b"));
+    assert_eq!(mbytes, String::from("608061004060003960003560e01c8063a9059cbb14610019575b60208703516202ffe016806020015b60206020015b60206020015b60206020015b60206020010000000000000000000000000000000000000000000000000000000000000028000000000000000000000000000000000000000000000000000000000000002e0000000000000000000000000000000000000000000000000000000000000034000000000000000000000000000000000000000000000000000000000000003a"));
 }
 
 #[test]
@@ -364,5 +497,347 @@ fn test_label_clashing() {
 
     // Have the Codegen create the main macro bytecode
     let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
-    assert_eq!(mbytes, String::from("600861004960003961012861005160003960003560e01c8063a9059cbb14610022575b60208703516202ffe016806020015b60206020015b60206020015b60206020015b602060200100310037003d004300000000000000000000000000000000000000000000000000000000000000310000000000000000000000000000000000000000000000000000000000000037000000000000000000000000000000000000000000000000000000000000003d0000000000000000000000000000000000000000000000000000000000000043"));
+    assert_eq!(mbytes, String::from("6008610048600039608061005060003960003560e01c8063a9059cbb14610021575b60208703516202ffe016806020015b60206020015b60206020015b60206020015b602060200100300036003c004200000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000000000000000000000000000000000000036000000000000000000000000000000000000000000000000000000000000003c0000000000000000000000000000000000000000000000000000000000000042"));
+}
+
+#[test]
+fn test_calldata_offset_builtin() {
+    let source: &str = r#"
+        #define function foo(uint256,address) nonpayable returns ()
+        #define function bar(uint256[2],string,address) nonpayable returns ()
+
+        #define macro MAIN() = takes(0) returns (0) {
+            __calldata_offset(foo, 1) calldataload
+            __calldata_offset(bar, 2) calldataload
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // Parse the AST
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+
+    // foo's second head is at 4 + 0x20, bar's third head is at 4 + 2 * 0x20 + 0x20
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(mbytes, "602435606435");
+
+    // Out of range parameters are rejected
+    contract.macros[0].statements.truncate(1);
+    if let StatementType::BuiltinFunctionCall(bf) = &mut contract.macros[0].statements[0].ty {
+        bf.args[1].name = Some("2".to_string());
+    }
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidArguments(_)));
+}
+
+#[test]
+fn test_proxy_slot_builtin() {
+    let source: &str = r#"
+        #define macro MAIN() = takes(0) returns (0) {
+            __proxy_slot("implementation") sload
+            __proxy_slot("admin") sload
+            __proxy_slot("beacon") sload
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // Parse the AST
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+
+    // The canonical EIP-1967 slots
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(
+        mbytes,
+        [
+            "7f360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc54",
+            "7fb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d610354",
+            "7fa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d5054",
+        ]
+        .concat()
+    );
+
+    // Unknown slot names are rejected
+    if let StatementType::BuiltinFunctionCall(bf) = &mut contract.macros[0].statements[0].ty {
+        bf.args[0].name = Some("owner".to_string());
+    }
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidArguments(_)));
+}
+
+#[test]
+fn test_mapping_slot_builtin() {
+    let source: &str = r#"
+        #define constant BALANCES_SLOT = FREE_STORAGE_POINTER()
+        #define constant ALLOWANCES_SLOT = FREE_STORAGE_POINTER()
+        #define constant HOLDER = 0xbeef
+
+        #define macro MAIN() = takes(0) returns (0) {
+            __mapping_slot(BALANCES_SLOT, 0x00) sload
+            __mapping_slot(ALLOWANCES_SLOT, HOLDER) sload
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // Parse the AST
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+
+    // The slot of key 0 in a mapping at slot 0 is keccak256 of 64 zero bytes, and the second
+    // mapping takes the next free slot
+    let mut preimage = [0u8; 64];
+    preimage[30..32].copy_from_slice(&[0xbe, 0xef]);
+    preimage[63] = 1;
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(
+        mbytes,
+        [
+            "7fad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb554",
+            &format!(
+                "7f{}54",
+                ethers_core::utils::hex::encode(ethers_core::utils::keccak256(preimage))
+            ),
+        ]
+        .concat()
+    );
+}
+
+#[test]
+fn test_mapping_slot_builtin_rejects_runtime_keys() {
+    let source: &str = r#"
+        #define constant BALANCES_SLOT = FREE_STORAGE_POINTER()
+
+        #define macro BALANCE_OF(key) = takes(0) returns (1) {
+            __mapping_slot(BALANCES_SLOT, <key>) sload
+        }
+
+        #define macro MAIN() = takes(0) returns (0) {
+            BALANCE_OF(0x01)
+        }
+    "#;
+
+    // Parse tokens
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+
+    // Parse the AST
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidArguments(_)));
+}
+
+#[test]
+fn test_assert_builtin() {
+    let compile = |condition: &str| {
+        let source = format!(
+            r#"
+            #define constant SLOT = 0x10
+            #define constant MAX = 0x20
+
+            #define macro MAIN() = takes(0) returns (0) {{
+                __assert({}) [SLOT] sload
+            }}
+            "#,
+            condition
+        );
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let mut contract = parser.parse().unwrap();
+        contract.derive_storage_pointers();
+        Codegen::generate_main_bytecode(&contract)
+    };
+
+    // Passing assertions emit no code
+    for condition in ["SLOT < 0x20", "SLOT < MAX", "MAX >= 32", "SLOT == 16", "SLOT <= SLOT", "MAX"]
+    {
+        assert_eq!(compile(condition).unwrap(), "601054", "{}", condition);
+    }
+
+    // Failing assertions stop compilation with the condition
+    let err = compile("SLOT > MAX").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::AssertionFailed("SLOT > MAX".to_string()));
+    let err = compile("0x00").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::AssertionFailed("0x00".to_string()));
+
+    // Unknown constants are reported as such
+    let err = compile("MISSING < 0x20").unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::MissingConstantDefinition("MISSING".to_string()));
+}
+
+#[test]
+fn test_constructor_rejects_runtime_tables() {
+    let parse = |constructor: &str| {
+        let source = format!(
+            r#"
+            #define jumptable__packed TABLE {{
+                target
+            }}
+
+            #define macro CONSTRUCTOR() = takes(0) returns(0) {{
+                {}
+            }}
+
+            #define macro MAIN() = takes(0) returns(0) {{
+                __tablestart(TABLE)
+                target:
+                    stop
+            }}
+            "#,
+            constructor
+        );
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let mut contract = parser.parse().unwrap();
+        contract.derive_storage_pointers();
+        contract
+    };
+
+    // Runtime tables aren't laid out after a constructor that doesn't use them
+    let contract = parse("0x01 0x00 sstore");
+    assert_eq!(Codegen::generate_constructor_bytecode(&contract).unwrap(), "6001600055");
+    assert_eq!(Codegen::generate_main_bytecode(&contract).unwrap(), "6100055b000003");
+
+    // A constructor using the table would read offsets into the runtime
+    let contract = parse("__tablestart(TABLE) pop");
+    let err = Codegen::generate_constructor_bytecode(&contract).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::TableScopeMismatch("TABLE".to_string()));
+}
+
+#[test]
+fn test_pc_builtin() {
+    let source: &str = r#"
+        #define macro HERE() = takes(0) returns(1) {
+            __pc()
+        }
+
+        #define macro OUTLINED() = takes(0) returns(1) @outline {
+            __pc()
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01 __pc() HERE() pop
+            OUTLINED() HERE()
+        }
+    "#;
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+    let main_bytecode = Codegen::generate_main_bytecode(&contract).unwrap();
+
+    // Walk the instructions, checking every PUSH2 of __pc pushes its own offset
+    let bytes = ethers_core::utils::hex::decode(&main_bytecode).unwrap();
+    let mut pcs = vec![];
+    let mut pc = 0;
+    while pc < bytes.len() {
+        match bytes[pc] {
+            op @ 0x60..=0x7f => {
+                if op == 0x61 && bytes.get(pc + 3) != Some(&0x56) {
+                    let value = u16::from_be_bytes([bytes[pc + 1], bytes[pc + 2]]) as usize;
+                    if value == pc {
+                        pcs.push(pc);
+                    }
+                }
+                pc += 1 + (op - 0x5f) as usize;
+            }
+            _ => pc += 1,
+        }
+    }
+
+    // The direct use, both inlined expansions of HERE and the outlined body
+    assert_eq!(pcs.len(), 4, "{}", main_bytecode);
+    assert_eq!(&main_bytecode[..16], "6001610002610005");
+}
+
+#[test]
+fn test_minimal_proxy_builtin() {
+    let parse = |main: &str| {
+        let source = format!(
+            r#"
+            #define constant IMPLEMENTATION = 0xbebebebebebebebebebebebebebebebebebebebe
+            #define macro MAIN() = takes(0) returns (0) {{
+                {}
+            }}
+        "#,
+            main
+        );
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let mut contract = parser.parse().unwrap();
+        contract.derive_storage_pointers();
+        contract
+    };
+
+    // The canonical 45 byte EIP-1167 clone
+    let contract = parse("__minimal_proxy(IMPLEMENTATION)");
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(
+        mbytes,
+        "363d3d373d3d3d363d73bebebebebebebebebebebebebebebebebebebebe5af43d82803e903d91602b57fd5bf3"
+    );
+    assert_eq!(mbytes.len() / 2, 45);
+
+    // Short addresses are left padded
+    let contract = parse("__minimal_proxy(0x01)");
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(&mbytes[20..60], format!("{:0>40}", "1"));
+
+    // Library names are emitted as placeholders to link
+    let contract = parse("__minimal_proxy(\"Implementation\")");
+    let mbytes = Codegen::generate_main_bytecode(&contract).unwrap();
+    assert_eq!(&mbytes[20..60], link_placeholder("Implementation"));
+
+    // Anything wider than an address is rejected
+    let contract = parse(&format!("__minimal_proxy(0x{})", "ff".repeat(21)));
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidArguments(msg) if msg.contains("address")));
+
+    // The clone's jump target is absolute, so code before it would break the clone
+    let contract = parse("0x00 pop __minimal_proxy(IMPLEMENTATION)");
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::MisplacedBuiltin("__minimal_proxy".to_string(), 3));
+}
+
+#[test]
+fn test_builtin_string_arguments_concatenate() {
+    let compile = |argument: &str| {
+        let source =
+            format!("#define macro MAIN() = takes(0) returns (0) {{ __linked({}) }}", argument);
+        let flattened_source = FullFileSource { source: &source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let contract = parser.parse().unwrap();
+        Codegen::generate_main_bytecode(&contract).unwrap()
+    };
+
+    assert_eq!(compile(r#""Safe" "Math""#), compile(r#""SafeMath""#));
 }
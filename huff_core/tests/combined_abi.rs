@@ -0,0 +1,44 @@
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(feature = "std-fs")]
+#[test]
+fn exports_combined_abi_for_two_contracts() {
+    let sources = HashMap::from([
+        (
+            "src/Token.huff".to_string(),
+            "#define function balanceOf(address) view returns (uint256)\n#define macro MAIN() = takes(0) returns(0) {\n    0x00 0x00 return\n}".to_string(),
+        ),
+        (
+            "src/Vault.huff".to_string(),
+            "#define event Deposit(address indexed, uint256)\n#define macro MAIN() = takes(0) returns(0) {\n    0x00 0x00 return\n}".to_string(),
+        ),
+    ]);
+    let compiler = Compiler::new(
+        Arc::new(vec!["src/Token.huff".to_string(), "src/Vault.huff".to_string()]),
+        None,
+        None,
+        false,
+    )
+    .with_file_provider(Arc::new(InMemoryFileProvider::new(sources)));
+
+    let artifacts = compiler.execute().unwrap();
+    let mut paths_to_abis = artifacts
+        .iter()
+        .map(|a| (a.file.path.clone(), a.abi.clone().unwrap()))
+        .collect::<Vec<(String, Abi)>>();
+    paths_to_abis.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let out = std::env::temp_dir().join("huff_combined_abi_test/combined.json");
+    Compiler::export_combined_abi(&paths_to_abis, out.to_str().unwrap()).unwrap();
+    let combined: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+
+    let contracts = combined["contracts"].as_object().unwrap();
+    assert_eq!(contracts.len(), 2);
+    assert_eq!(contracts["src/Token.huff:Token"]["abi"][0]["name"], "balanceOf");
+    assert_eq!(contracts["src/Token.huff:Token"]["abi"][0]["type"], "function");
+    assert_eq!(contracts["src/Vault.huff:Vault"]["abi"][0]["name"], "Deposit");
+    assert_eq!(contracts["src/Vault.huff:Vault"]["abi"][0]["type"], "event");
+}
@@ -0,0 +1,18 @@
+//! Fixtures shared by the core integration tests
+#![allow(dead_code)]
+
+use huff_codegen::*;
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+/// Parses a source and generates its MAIN bytecode
+pub fn compile_main(source: &str) -> String {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let mut contract = parser.parse().unwrap();
+    contract.derive_storage_pointers();
+    Codegen::generate_main_bytecode(&contract).unwrap()
+}
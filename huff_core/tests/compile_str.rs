@@ -0,0 +1,31 @@
+use std::{collections::HashMap, sync::Arc};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_compile_str_resolves_includes_through_provider() {
+    let provider = InMemoryFileProvider::new(HashMap::from([(
+        "./lib.huff".to_string(),
+        "#define macro LIB() = takes(0) returns(0) { 0x01 }\n".to_string(),
+    )]));
+    let compiler = Compiler::default().with_file_provider(Arc::new(provider));
+
+    let source = "#include \"./lib.huff\"\n#define macro MAIN() = takes(0) returns(0) { LIB() }\n";
+    let artifact = compiler.compile_str(source).unwrap();
+
+    assert_eq!(artifact.runtime, "6001");
+}
+
+/// Without `std-fs` the default provider is empty, so nothing is read from disk
+#[cfg(not(feature = "std-fs"))]
+#[test]
+fn test_compile_str_without_std_fs() {
+    let compiler = Compiler::default();
+
+    let artifact =
+        compiler.compile_str("#define macro MAIN() = takes(0) returns(0) { 0x01 }").unwrap();
+    assert_eq!(artifact.runtime, "6001");
+
+    assert!(compiler.compile_str("#include \"./missing.huff\"").is_err());
+}
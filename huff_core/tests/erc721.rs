@@ -8,16 +8,18 @@ use huff_utils::prelude::*;
 
 #[test]
 fn test_erc721_compile() {
-    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![PathBuf::from(
-        "../huff-examples/erc721/contracts/ERC721.huff".to_string(),
-    )])
+    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(
+        vec![PathBuf::from("../huff-examples/erc721/contracts/ERC721.huff".to_string())],
+        Arc::new(FileSystemFileProvider),
+    )
     .iter()
     .map(|p| p.clone().unwrap())
     .collect();
 
     // Recurse file deps + generate flattened source
     let file_source = file_sources.get(0).unwrap();
-    let recursed_file_source = Compiler::recurse_deps(Arc::clone(file_source)).unwrap();
+    let recursed_file_source =
+        Compiler::recurse_deps(Arc::clone(file_source), Arc::new(FileSystemFileProvider)).unwrap();
     let flattened = FileSource::fully_flatten(Arc::clone(&recursed_file_source));
     let full_source = FullFileSource {
         source: &flattened.0,
@@ -0,0 +1,124 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+#[test]
+fn test_recurse_deps_from_in_memory_provider() {
+    let provider = InMemoryFileProvider::new(HashMap::from([
+        (
+            "./main.huff".to_string(),
+            "#include \"./lib.huff\"\n#define macro MAIN() = takes(0) returns(0) { LIB() }\n"
+                .to_string(),
+        ),
+        (
+            "./lib.huff".to_string(),
+            "#define macro LIB() = takes(0) returns(0) { 0x01 }\n".to_string(),
+        ),
+    ]));
+    let provider: Arc<dyn FileProvider> = Arc::new(provider);
+
+    let file_sources: Vec<Arc<FileSource>> =
+        Compiler::fetch_sources(vec![PathBuf::from("./main.huff")], Arc::clone(&provider))
+            .into_iter()
+            .map(|p| p.unwrap())
+            .collect();
+    let main = Compiler::recurse_deps(Arc::clone(&file_sources[0]), provider).unwrap();
+
+    let deps = main.dependencies.as_ref().unwrap();
+    assert_eq!(deps.len(), 1);
+    assert_eq!(deps[0].path, "./lib.huff");
+    assert!(deps[0].source.as_ref().unwrap().contains("LIB()"));
+}
+
+#[cfg(feature = "std-fs")]
+#[test]
+fn test_compile_includes_from_in_memory_provider() {
+    let provider = InMemoryFileProvider::new(HashMap::from([
+        (
+            "./main.huff".to_string(),
+            "#include \"./lib.huff\"\n#define macro MAIN() = takes(0) returns(0) { LIB() }\n"
+                .to_string(),
+        ),
+        (
+            "./lib.huff".to_string(),
+            "#define macro LIB() = takes(0) returns(0) { 0x01 }\n".to_string(),
+        ),
+    ]));
+
+    let compiler = Compiler::new(Arc::new(vec!["./main.huff".to_string()]), None, None, false)
+        .with_file_provider(Arc::new(provider));
+    let artifacts = compiler.execute().unwrap();
+
+    assert_eq!(artifacts.len(), 1);
+    assert!(artifacts[0].bytecode.ends_with("6001"));
+}
+
+#[test]
+fn test_missing_in_memory_file() {
+    let provider: Arc<dyn FileProvider> = Arc::new(InMemoryFileProvider::default());
+    let res = Compiler::fetch_sources(vec![PathBuf::from("./missing.huff")], provider);
+    assert_eq!(
+        res[0].clone().unwrap_err(),
+        CompilerError::FileUnpackError(UnpackError::MissingFile("./missing.huff".to_string()))
+    );
+}
+
+/// Counts the reads passed through to an in-memory provider
+#[derive(Debug)]
+struct CountingFileProvider {
+    inner: InMemoryFileProvider,
+    reads: AtomicUsize,
+}
+
+impl FileProvider for CountingFileProvider {
+    fn read_to_string(&self, path: &str) -> Result<String, UnpackError> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.read_to_string(path)
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.inner.exists(path)
+    }
+}
+
+#[test]
+fn test_constants_json_is_read_once_through_provider() {
+    let provider = Arc::new(CountingFileProvider {
+        inner: InMemoryFileProvider::new(HashMap::from([(
+            "./constants.json".to_string(),
+            r#"{ "OWNER": "0xdeadbeef" }"#.to_string(),
+        )])),
+        reads: AtomicUsize::new(0),
+    });
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, false)
+        .with_file_provider(Arc::clone(&provider) as Arc<dyn FileProvider>)
+        .with_constants_json("./constants.json".to_string());
+
+    let source = "#define macro MAIN() = takes(0) returns(0) { [OWNER] }";
+    for _ in 0..2 {
+        let artifact = compiler.compile_str(source).unwrap();
+        assert_eq!(artifact.runtime, "63deadbeef");
+    }
+    assert_eq!(provider.reads.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_missing_constants_json() {
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, false)
+        .with_file_provider(Arc::new(InMemoryFileProvider::default()))
+        .with_constants_json("./constants.json".to_string());
+
+    let err = compiler.compile_str("#define macro MAIN() = takes(0) returns(0) {}").unwrap_err();
+    assert!(matches!(
+        &*err,
+        CompilerError::CodegenError(CodegenError { kind: CodegenErrorKind::IOError(_), .. })
+    ));
+}
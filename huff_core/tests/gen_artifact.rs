@@ -94,3 +94,99 @@ fn test_missing_constructor_with_inputs() {
         }
     }
 }
+
+#[test]
+fn test_failing_constructor_is_not_treated_as_missing() {
+    let source = r#"
+    #define macro CONSTRUCTOR() = takes(0) returns (0) {
+        UNDEFINED()
+    }
+
+    #define macro MAIN() = takes(0) returns (0) {
+        0x00 calldataload
+    }
+    "#;
+
+    let full_source = FileSource {
+        source: Some(source.to_string()),
+        id: uuid::Uuid::new_v4(),
+        path: "".to_string(),
+        access: None,
+        dependencies: None,
+    };
+
+    // Only an absent constructor compiles to empty constructor bytecode
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, false);
+    match compiler.gen_artifact(Arc::new(full_source)) {
+        Ok(_) => panic!("moose"),
+        Err(CompilerError::CodegenError(e)) => {
+            assert_eq!(e.kind, CodegenErrorKind::InvalidMacroInvocation("UNDEFINED".to_string()))
+        }
+        Err(e) => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_codesize_excludes_appended_metadata() {
+    let source = r#"
+    #define macro CONSTRUCTOR() = takes(0) returns (0) {
+        __codesize(MAIN) pop
+    }
+
+    #define macro MAIN() = takes(0) returns (0) {
+        0x01 0x00 mstore
+        0x20 0x00 return
+    }
+    "#;
+
+    let full_source = FileSource {
+        source: Some(source.to_string()),
+        id: uuid::Uuid::new_v4(),
+        path: "".to_string(),
+        access: None,
+        dependencies: None,
+    };
+    let compiler = Compiler::new(Arc::new(vec![]), None, None, false).with_metadata(true);
+    let artifact = compiler.gen_artifact(Arc::new(full_source)).unwrap();
+
+    // MAIN is 10 bytes of code, followed by the metadata in the runtime
+    let code = "600160005260206000f3";
+    assert_eq!(artifact.runtime, format!("{}{}", code, huff_codegen::Codegen::version_metadata()));
+    assert!(artifact.bytecode.starts_with("600a50"));
+}
+
+#[test]
+fn test_constructor_args_are_checked_against_declared_types() {
+    let source = r#"
+    #define function CONSTRUCTOR(uint8) nonpayable returns ()
+
+    #define macro CONSTRUCTOR() = takes(0) returns (0) {}
+
+    #define macro MAIN() = takes(0) returns (0) {
+        0x00 calldataload
+    }
+    "#;
+
+    let full_source = Arc::new(FileSource {
+        source: Some(source.to_string()),
+        id: uuid::Uuid::new_v4(),
+        path: "".to_string(),
+        access: None,
+        dependencies: None,
+    });
+
+    let compiler = Compiler::new(Arc::new(vec![]), None, Some(vec!["255".to_string()]), false);
+    let artifact = compiler.gen_artifact(Arc::clone(&full_source)).unwrap();
+    assert!(artifact.bytecode.ends_with(&format!("{:0>64}", "ff")));
+
+    // 256 doesn't fit in the declared uint8
+    let compiler = Compiler::new(Arc::new(vec![]), None, Some(vec!["256".to_string()]), false);
+    match compiler.gen_artifact(full_source) {
+        Err(CompilerError::CodegenError(e)) => assert!(
+            matches!(e.kind, CodegenErrorKind::InvalidArguments(ref msg) if msg.contains("uint8")),
+            "{:?}",
+            e
+        ),
+        other => panic!("expected an invalid argument error, got {:?}", other),
+    }
+}
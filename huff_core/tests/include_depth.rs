@@ -0,0 +1,62 @@
+use std::{collections::HashMap, sync::Arc};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+/// A chain of `length` files, each including the next
+fn include_chain(length: usize) -> InMemoryFileProvider {
+    InMemoryFileProvider::new(
+        (0..length)
+            .map(|i| {
+                let include = match i + 1 < length {
+                    true => format!("#include \"./{}.huff\"\n", i + 1),
+                    false => String::default(),
+                };
+                (format!("./{}.huff", i), include)
+            })
+            .collect::<HashMap<String, String>>(),
+    )
+}
+
+fn source(provider: &InMemoryFileProvider, path: &str) -> Arc<FileSource> {
+    Arc::new(FileSource {
+        path: path.to_string(),
+        source: Some(provider.read_to_string(path).unwrap()),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_include_chain_exceeding_max_depth() {
+    let provider = include_chain(6);
+    let root = source(&provider, "./0.huff");
+
+    let res = Compiler::recurse_deps_with_limit(Arc::clone(&root), Arc::new(provider.clone()), 4);
+    match res.unwrap_err().as_ref() {
+        CompilerError::ParserError(e) => {
+            assert_eq!(e.kind, ParserErrorKind::IncludeDepthExceeded(4))
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    // The same chain resolves within a larger limit
+    assert!(Compiler::recurse_deps_with_limit(root, Arc::new(provider), 5).is_ok());
+}
+
+#[test]
+fn test_include_cycle_hits_default_max_depth() {
+    let provider = InMemoryFileProvider::new(HashMap::from([
+        ("./a.huff".to_string(), "#include \"./b.huff\"\n".to_string()),
+        ("./b.huff".to_string(), "#include \"./a.huff\"\n".to_string()),
+    ]));
+    let root = source(&provider, "./a.huff");
+
+    let res = Compiler::recurse_deps(root, Arc::new(provider));
+    assert!(matches!(
+        res.unwrap_err().as_ref(),
+        CompilerError::ParserError(ParserError {
+            kind: ParserErrorKind::IncludeDepthExceeded(huff_core::DEFAULT_MAX_INCLUDE_DEPTH),
+            ..
+        })
+    ));
+}
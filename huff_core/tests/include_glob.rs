@@ -0,0 +1,90 @@
+use std::{
+    collections::HashMap,
+    io,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use huff_core::Compiler;
+use huff_utils::prelude::*;
+
+fn resolve(sources: &[(&str, &str)]) -> Arc<FileSource> {
+    let provider = InMemoryFileProvider::new(
+        sources.iter().map(|(p, s)| (p.to_string(), s.to_string())).collect::<HashMap<_, _>>(),
+    );
+    let provider: Arc<dyn FileProvider> = Arc::new(provider);
+
+    let main = Compiler::fetch_sources(vec![PathBuf::from("./main.huff")], Arc::clone(&provider))
+        .remove(0)
+        .unwrap();
+    Compiler::recurse_deps(main, provider).unwrap()
+}
+
+#[test]
+fn includes_every_matching_file_in_sorted_order() {
+    let main = resolve(&[
+        (
+            "./main.huff",
+            "#include \"./macros/*.huff\"\n#define macro MAIN() = takes(0) returns(0) { A() B() }\n",
+        ),
+        ("./macros/b.huff", "#define macro B() = takes(0) returns(0) { 0x02 }\n"),
+        ("./macros/a.huff", "#define macro A() = takes(0) returns(0) { 0x01 }\n"),
+        ("./macros/notes.txt", "not huff"),
+        ("./macros/nested/c.huff", "#define macro C() = takes(0) returns(0) { 0x03 }\n"),
+    ]);
+
+    let deps = main.dependencies.as_ref().unwrap();
+    let paths = deps.iter().map(|d| d.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(paths, ["./macros/a.huff", "./macros/b.huff"]);
+    assert!(deps[0].source.as_ref().unwrap().contains("A()"));
+}
+
+/// Collects formatted log output
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn glob_matching_nothing_only_warns() {
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::WARN)
+        .with_ansi(false)
+        .with_writer(move || writer.clone())
+        .finish();
+
+    let main = tracing::subscriber::with_default(subscriber, || {
+        resolve(&[(
+            "./main.huff",
+            "#include \"./empty/*.huff\"\n#define macro MAIN() = takes(0) returns(0) {}\n",
+        )])
+    });
+    assert!(main.dependencies.as_ref().unwrap().is_empty());
+
+    let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+    assert!(logs.contains("WARN"), "{}", logs);
+    assert!(logs.contains("INCLUDE GLOB \"./empty/*.huff\" MATCHED NO FILES"), "{}", logs);
+}
+
+#[test]
+fn expands_wildcards_in_file_names_only() {
+    let provider = InMemoryFileProvider::new(HashMap::from([
+        ("./lib/erc20.huff".to_string(), String::new()),
+        ("./lib/erc721.huff".to_string(), String::new()),
+        ("./lib/utils.huff".to_string(), String::new()),
+    ]));
+    assert_eq!(expand_glob("./lib/erc???.huff", &provider), ["./lib/erc721.huff"]);
+    assert_eq!(expand_glob("./lib/erc*", &provider), ["./lib/erc20.huff", "./lib/erc721.huff"]);
+    assert!(expand_glob("./*/utils.huff", &provider).is_empty());
+}
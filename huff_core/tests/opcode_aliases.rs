@@ -0,0 +1,44 @@
+mod common;
+use common::compile_main;
+
+#[test]
+fn test_sha3_keccak256_alias() {
+    let sha3 = compile_main(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x20 0x00 sha3
+        }
+    "#,
+    );
+    let keccak256 = compile_main(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x20 0x00 keccak256
+        }
+    "#,
+    );
+
+    assert_eq!(sha3, String::from("6020600020"));
+    assert_eq!(sha3, keccak256);
+}
+
+#[test]
+fn test_difficulty_prevrandao_alias() {
+    let difficulty = compile_main(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            difficulty
+        }
+    "#,
+    );
+    let prevrandao = compile_main(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            prevrandao
+        }
+    "#,
+    );
+
+    assert_eq!(difficulty, String::from("44"));
+    assert_eq!(difficulty, prevrandao);
+}
@@ -1,19 +1,21 @@
 use std::{path::PathBuf, sync::Arc};
 
 use huff_core::Compiler;
-use huff_utils::files::FileSource;
+use huff_utils::files::{FileSource, FileSystemFileProvider};
 
 #[test]
 fn test_recursing_fs_dependencies() {
-    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![PathBuf::from(
-        "../huff-examples/erc20/contracts/ERC20.huff".to_string(),
-    )])
+    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(
+        vec![PathBuf::from("../huff-examples/erc20/contracts/ERC20.huff".to_string())],
+        Arc::new(FileSystemFileProvider),
+    )
     .iter()
     .map(|p| p.clone().unwrap())
     .collect();
     assert_eq!(file_sources.len(), 1);
     let erc20_file_source = file_sources[0].clone();
-    let res = Compiler::recurse_deps(Arc::clone(&erc20_file_source));
+    let res =
+        Compiler::recurse_deps(Arc::clone(&erc20_file_source), Arc::new(FileSystemFileProvider));
     let full_erc20_file_source = res.unwrap();
     let dependencies = full_erc20_file_source.dependencies.as_ref().unwrap();
     assert_eq!(dependencies.len(), 4);
@@ -25,15 +27,17 @@ fn test_recursing_fs_dependencies() {
 
 #[test]
 fn test_recursing_external_dependencies() {
-    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![PathBuf::from(
-        "../huff-examples/erc20/contracts/ERC20.huff".to_string(),
-    )])
+    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(
+        vec![PathBuf::from("../huff-examples/erc20/contracts/ERC20.huff".to_string())],
+        Arc::new(FileSystemFileProvider),
+    )
     .iter()
     .map(|p| p.clone().unwrap())
     .collect();
     assert_eq!(file_sources.len(), 1);
     let erc20_file_source = file_sources[0].clone();
-    let res = Compiler::recurse_deps(Arc::clone(&erc20_file_source));
+    let res =
+        Compiler::recurse_deps(Arc::clone(&erc20_file_source), Arc::new(FileSystemFileProvider));
     let full_erc20_file_source = res.unwrap();
     let dependencies = full_erc20_file_source.dependencies.as_ref().unwrap();
     assert_eq!(dependencies.len(), 4);
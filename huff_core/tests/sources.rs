@@ -5,11 +5,14 @@ use huff_utils::prelude::*;
 
 #[test]
 fn test_fetch_sources() {
-    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(vec![
-        PathBuf::from("../huff-examples/erc20/contracts/ERC20.huff".to_string()),
-        PathBuf::from("../huff-examples/erc20/contracts/utils/Address.huff".to_string()),
-        PathBuf::from("../huff-examples/erc20/contracts/utils/HashMap.huff".to_string()),
-    ])
+    let file_sources: Vec<Arc<FileSource>> = Compiler::fetch_sources(
+        vec![
+            PathBuf::from("../huff-examples/erc20/contracts/ERC20.huff".to_string()),
+            PathBuf::from("../huff-examples/erc20/contracts/utils/Address.huff".to_string()),
+            PathBuf::from("../huff-examples/erc20/contracts/utils/HashMap.huff".to_string()),
+        ],
+        Arc::new(FileSystemFileProvider),
+    )
     .iter()
     .map(|p| p.clone().unwrap())
     .collect();
@@ -35,7 +38,7 @@ fn test_fetch_invalid_sources() {
         PathBuf::from("../huff-examples/erc20/contracts/utils/".to_string()),
     ];
     let file_sources: Vec<Result<Arc<FileSource>, CompilerError>> =
-        Compiler::fetch_sources(paths.clone());
+        Compiler::fetch_sources(paths.clone(), Arc::new(FileSystemFileProvider));
     for (i, e) in file_sources.iter().enumerate() {
         let file_loc = String::from(paths[i].to_string_lossy());
         assert_eq!(
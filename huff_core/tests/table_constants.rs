@@ -0,0 +1,106 @@
+use huff_codegen::*;
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+use std::sync::Arc;
+
+mod common;
+use common::compile_main;
+
+#[test]
+fn constant_is_pushed_in_code_and_inlined_in_code_table() {
+    let source: &str = r#"
+        #define constant MAGIC = 0xdeadbeef
+
+        #define table MAGIC_TABLE {
+            [MAGIC] [MAGIC]
+        }
+
+        #define macro MAIN() = takes(0) returns (0) {
+            [MAGIC] __tablesize(MAGIC_TABLE) __tablestart(MAGIC_TABLE)
+        }
+    "#;
+
+    // In code, the constant is a PUSH4. In the table, the raw bytes are inlined.
+    assert_eq!(compile_main(source), "63deadbeef600861000adeadbeefdeadbeef");
+}
+
+#[test]
+fn constant_is_padded_to_jump_table_entry_width() {
+    let source: &str = r#"
+        #define constant ENTRY = 0x01
+
+        #define jumptable__packed PACKED {
+            [ENTRY] lab_0
+        }
+
+        #define macro MAIN() = takes(0) returns (0) {
+            __tablesize(PACKED)
+            lab_0:
+                stop
+        }
+    "#;
+
+    assert_eq!(compile_main(source), "60045b0000010002");
+}
+
+#[test]
+fn constant_array_is_inlined_into_code_table() {
+    let source: &str = r#"
+        #define constant VALUES = [0x01, 0x02, 0x03]
+
+        #define table VALUES_TABLE {
+            [VALUES]
+        }
+
+        #define macro MAIN() = takes(0) returns (0) {
+            __tablesize(VALUES_TABLE) __tablestart(VALUES_TABLE)
+        }
+    "#;
+
+    // The table is sized for all three elements, which follow the code back to back
+    assert_eq!(compile_main(source), "6003610005010203");
+}
+
+#[test]
+fn constant_array_cannot_be_pushed() {
+    let source: &str = r#"
+        #define constant VALUES = [0x01, 0x02, 0x03]
+
+        #define macro MAIN() = takes(0) returns (0) {
+            [VALUES]
+        }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let tokens = Lexer::new(flattened_source).map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let contract = Parser::new(tokens, None).parse().unwrap();
+
+    let err = Codegen::generate_main_bytecode(&contract).unwrap_err();
+    assert!(matches!(err.kind, CodegenErrorKind::InvalidArguments(msg) if msg.contains("VALUES")));
+}
+
+#[test]
+fn free_storage_pointer_is_sized_in_code_table() {
+    let source: &str = r#"
+        #define constant FIRST_SLOT = FREE_STORAGE_POINTER()
+        #define constant SECOND_SLOT = FREE_STORAGE_POINTER()
+
+        #define table SLOTS {
+            [SECOND_SLOT]
+        }
+
+        #define macro MAIN() = takes(0) returns (0) {
+            [FIRST_SLOT] sload [SECOND_SLOT] sload
+            __tablesize(SLOTS) __tablestart(SLOTS)
+        }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let tokens = Lexer::new(flattened_source).map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let contract = Parser::new(tokens, None).parse().unwrap();
+
+    // The derived slot is only known once storage pointers are derived, and is inlined as 1 byte
+    let artifact = Codegen::new()
+        .compile_contract(contract, Arc::new(FileSource::default()), vec![], false)
+        .unwrap();
+    assert_eq!(artifact.runtime, "600054600154600161000b01");
+}
@@ -178,46 +178,61 @@ impl<'a> Lexer<'a> {
     pub fn dyn_peek(&mut self, f: impl Fn(&char) -> bool + Copy) -> String {
         let mut chars: Vec<char> = Vec::new();
         let mut current_pos = self.current_span().start;
-        while self.nth_peek(current_pos).map(|x| f(&x)).unwrap_or(false) {
-            chars.push(self.nth_peek(current_pos).unwrap());
-            current_pos += 1;
+        while let Some(c) = self.nth_peek(current_pos).filter(f) {
+            chars.push(c);
+            current_pos += c.len_utf8();
         }
         chars.iter().collect()
     }
 
-    /// Try to peek at the nth character from the source
-    pub fn nth_peek(&mut self, n: usize) -> Option<char> {
-        self.reference_chars.clone().nth(n)
+    /// Try to peek at the character starting at byte offset n in the source
+    pub fn nth_peek(&self, n: usize) -> Option<char> {
+        self.source.source.get(n..).and_then(|s| s.chars().next())
     }
 
     /// Try to peek at next n characters from the source
     pub fn peek_n_chars(&mut self, n: usize) -> String {
         let cur_span: Ref<Span> = self.current_span();
         // Break with an empty string if the bounds are exceeded
-        if cur_span.end + n > self.source.source.len() {
-            return String::default()
-        }
-        self.source.source[cur_span.start..cur_span.end + n].to_string()
+        self.source.source.get(cur_span.start..cur_span.end + n).unwrap_or_default().to_string()
     }
 
     /// Peek n chars from a given start point in the source
     pub fn peek_n_chars_from(&mut self, n: usize, from: usize) -> String {
-        self.source.source[Span::new(from..(from + n), None).range().unwrap()].to_string()
+        self.source.source.get(from..from + n).unwrap_or_default().to_string()
     }
 
     /// Gets the current slice of the source code covered by span
     pub fn slice(&self) -> String {
-        self.source.source[self.current_span().range().unwrap()].to_string()
+        self.current_span()
+            .range()
+            .and_then(|r| self.source.source.get(r))
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Borrows the current slice of the source code for the lifetime of the source
+    fn raw_slice(&self) -> &'a str {
+        let source = self.source.source;
+        self.current_span().range().and_then(|r| source.get(r)).unwrap_or_default()
     }
 
     /// Consumes the characters
     pub fn consume(&mut self) -> Option<char> {
         self.chars.next().map(|x| {
-            self.current_span_mut().end += 1;
+            self.current_span_mut().end += x.len_utf8();
             x
         })
     }
 
+    /// Returns the number of characters up to and including the opening quote of a string
+    /// literal that directly follows the current span, separated from it only by whitespace
+    fn adjacent_string(&self, quote: char) -> Option<usize> {
+        let rest = self.source.source.get(self.current_span().end..)?;
+        let whitespace = rest.chars().take_while(char::is_ascii_whitespace).count();
+        (rest.chars().nth(whitespace) == Some(quote)).then_some(whitespace + 1)
+    }
+
     /// Consumes n characters
     pub fn nconsume(&mut self, count: usize) {
         for _ in 0..count {
@@ -233,8 +248,7 @@ impl<'a> Lexer<'a> {
             if word == peeked {
                 break
             }
-            self.consume();
-            current_pos += 1;
+            current_pos += self.consume().map(char::len_utf8).unwrap_or_default();
         }
     }
 
@@ -253,6 +267,33 @@ impl<'a> Lexer<'a> {
         exclusive_span.start = exclusive_span.end;
     }
 
+    /// Skips past the remainder of a malformed token
+    ///
+    /// Consumes characters up to the next whitespace or delimiter so lexing can resume at a
+    /// plausible token boundary.
+    pub fn recover(&mut self) {
+        self.dyn_consume(|c| !c.is_ascii_whitespace() && !"(){}[],".contains(*c));
+    }
+
+    /// Lexes the entire source, collecting every lexical error instead of stopping at the first
+    ///
+    /// After each error the lexer [recovers](Lexer::recover) to the next token boundary and
+    /// continues, so a single pass reports all errors in the source.
+    pub fn lex_with_recovery(mut self) -> (Vec<Token>, Vec<LexicalError<'a>>) {
+        let mut tokens = vec![];
+        let mut errors = vec![];
+        while let Some(res) = self.next() {
+            match res {
+                Ok(token) => tokens.push(token),
+                Err(e) => {
+                    errors.push(e);
+                    self.recover();
+                }
+            }
+        }
+        (tokens, errors)
+    }
+
     /// Check if a given keyword follows the keyword rules in the `source`. If not, it is a
     /// `TokenKind::Ident`.
     ///
@@ -291,6 +332,9 @@ impl<'a> Lexer<'a> {
                 }
                 false
             }
+            Some(TokenKind::Anonymous) => {
+                self.context == Context::Abi && self.checked_lookback(TokenKind::CloseParen)
+            }
             Some(TokenKind::Takes) => self.checked_lookback(TokenKind::Assign),
             Some(TokenKind::Returns) => {
                 let cur_span_end = self.current_span().end;
@@ -318,8 +362,9 @@ impl<'a> Iterator for Lexer<'a> {
                         match ch2 {
                             '/' => {
                                 self.consume();
-                                // Consume until newline
-                                self.dyn_consume(|c| *c != '\n');
+                                // Consume until newline, treating `\r\n` as a newline so CRLF
+                                // sources lex to the same tokens as LF sources
+                                self.dyn_consume(|c| *c != '\n' && *c != '\r');
                                 TokenKind::Comment(self.slice())
                             }
                             '*' => {
@@ -362,6 +407,25 @@ impl<'a> Iterator for Lexer<'a> {
                         )))
                     }
                 }
+                // @ decorators
+                '@' => {
+                    let decorator =
+                        [TokenKind::Outline, TokenKind::Facet].into_iter().find(|kind| {
+                            let key = kind.to_string();
+                            key == self.peek_n_chars(key.len() - 1)
+                        });
+
+                    if let Some(kind) = decorator {
+                        self.nconsume(kind.to_string().len() - 1);
+                        kind
+                    } else {
+                        tracing::error!(target: "lexer", "INVALID '@' CHARACTER USAGE");
+                        return Some(Err(LexicalError::new(
+                            LexicalErrorKind::InvalidCharacter('@'),
+                            self.current_span().clone(),
+                        )))
+                    }
+                }
                 // Alphabetical characters
                 ch if ch.is_alphabetic() || ch.eq(&'_') => {
                     let mut found_kind: Option<TokenKind> = None;
@@ -376,6 +440,7 @@ impl<'a> Iterator for Lexer<'a> {
                         TokenKind::NonPayable,
                         TokenKind::Payable,
                         TokenKind::Indexed,
+                        TokenKind::Anonymous,
                         TokenKind::View,
                         TokenKind::Pure,
                         // First check for packed jump table
@@ -433,6 +498,15 @@ impl<'a> Iterator for Lexer<'a> {
                         found_kind = Some(TokenKind::FreeStoragePointer);
                     }
 
+                    // Check for the immutable keyword, parenthesis included so identifiers
+                    // prefixed with `IMMUTABLE` are left alone
+                    let immutable = "IMMUTABLE()";
+                    let token_length = immutable.len() - 1;
+                    if immutable == self.peek_n_chars(token_length) {
+                        self.nconsume(token_length);
+                        found_kind = Some(TokenKind::Immutable);
+                    }
+
                     let potential_label: String =
                         self.dyn_peek(|c| c.is_alphanumeric() || c == &'_' || c == &':');
                     if let true = potential_label.ends_with(':') {
@@ -494,22 +568,19 @@ impl<'a> Iterator for Lexer<'a> {
                                 for size in sizes.iter() {
                                     match size.is_empty() {
                                         true => size_vec.push(0),
-                                        false => {
-                                            let arr_size: usize = size
-                                                .parse::<usize>()
-                                                .map_err(|_| {
-                                                    let err = LexicalError {
-                                                        kind: LexicalErrorKind::InvalidArraySize(
-                                                            &words[1],
-                                                        ),
-                                                        span: self.current_span().clone(),
-                                                    };
-                                                    tracing::error!(target: "lexer", "{}", format!("{:?}", err));
-                                                    err
-                                                })
-                                                .unwrap();
-                                            size_vec.push(arr_size);
-                                        }
+                                        false => match size.parse::<usize>() {
+                                            Ok(arr_size) => size_vec.push(arr_size),
+                                            Err(_) => {
+                                                let err = LexicalError {
+                                                    kind: LexicalErrorKind::InvalidArraySize(
+                                                        self.raw_slice(),
+                                                    ),
+                                                    span: self.current_span().clone(),
+                                                };
+                                                tracing::error!(target: "lexer", "{}", format!("{:?}", err));
+                                                return Some(Err(err))
+                                            }
+                                        },
                                     }
                                 }
                                 let primitive = PrimitiveEVMType::try_from(words[0].clone());
@@ -543,7 +614,18 @@ impl<'a> Iterator for Lexer<'a> {
                         if self.context == Context::MacroBody &&
                             matches!(
                                 slice.as_ref(),
-                                "__codesize" | "__tablesize" | "__tablestart"
+                                "__codesize" |
+                                    "__tablesize" |
+                                    "__tablestart" |
+                                    "__calldata_offset" |
+                                    "__proxy_slot" |
+                                    "__assert" |
+                                    "__set_immutable" |
+                                    "__linked" |
+                                    "__pc" |
+                                    "__mapping_slot" |
+                                    "__minimal_proxy" |
+                                    "__nonpayable_guard"
                             )
                         {
                             TokenKind::BuiltinFunction(slice)
@@ -553,15 +635,19 @@ impl<'a> Iterator for Lexer<'a> {
                     }
                 }
                 // If it's the start of a hex literal
-                ch if ch == '0' && self.peek().unwrap() == 'x' => {
+                ch if ch == '0' && self.peek() == Some('x') => {
                     self.consume(); // Consume the 'x' after '0' (separated from the `dyn_consume` so we don't have
                                     // to match `x` in the actual hex)
-                    self.dyn_consume(|c| {
-                        c.is_numeric() ||
-                            // Match a-f & A-F
-                            matches!(c, '\u{0041}'..='\u{0046}' | '\u{0061}'..='\u{0066}')
-                    });
+                    self.dyn_consume(char::is_ascii_hexdigit);
                     self.current_span_mut().start += 2; // Ignore the "0x"
+                                                        // Literals are word sized
+                    if self.current_span().end - self.current_span().start > 64 {
+                        tracing::error!(target: "lexer", "LITERAL EXCEEDS 32 BYTES");
+                        return Some(Err(LexicalError::new(
+                            LexicalErrorKind::LiteralTooLarge(self.raw_slice()),
+                            self.current_span().clone(),
+                        )))
+                    }
                     TokenKind::Literal(str_to_bytes32(self.slice().as_ref()))
                 }
                 '=' => TokenKind::Assign,
@@ -606,7 +692,21 @@ impl<'a> Iterator for Lexer<'a> {
                 ',' => TokenKind::Comma,
                 '0'..='9' => {
                     self.dyn_consume(char::is_ascii_digit);
-                    TokenKind::Num(self.slice().parse().unwrap())
+                    // Constants are word sized, so their digits are left for the parser
+                    if self.context == Context::Constant {
+                        TokenKind::Decimal(self.slice())
+                    } else {
+                        match self.slice().parse() {
+                            Ok(n) => TokenKind::Num(n),
+                            Err(_) => {
+                                tracing::error!(target: "lexer", "NUMBER EXCEEDS USIZE");
+                                return Some(Err(LexicalError::new(
+                                    LexicalErrorKind::LiteralTooLarge(self.raw_slice()),
+                                    self.current_span().clone(),
+                                )))
+                            }
+                        }
+                    }
                 }
                 // Lexes Spaces and Newlines as Whitespace
                 ch if ch.is_ascii_whitespace() => {
@@ -614,51 +714,88 @@ impl<'a> Iterator for Lexer<'a> {
                     TokenKind::Whitespace
                 }
                 // String literals
-                '"' => loop {
-                    match self.peek() {
-                        Some('"') => {
-                            self.consume();
-                            let str = self.slice();
-                            break TokenKind::Str((&str[1..str.len() - 1]).to_string())
-                        }
-                        Some('\\') if matches!(self.nth_peek(1), Some('\\') | Some('"')) => {
-                            self.consume();
-                        }
-                        Some(_) => {}
-                        None => {
-                            self.eof = true;
-                            tracing::error!(target: "lexer", "UNEXPECTED EOF SPAN");
-                            return Some(Err(LexicalError::new(
-                                LexicalErrorKind::UnexpectedEof,
-                                self.current_span().clone(),
-                            )))
+                // Adjacent string literals, separated only by whitespace, are concatenated
+                '"' => {
+                    let mut content = String::new();
+                    let mut segment_start = self.current_span().end;
+                    loop {
+                        match self.peek() {
+                            Some('"') => {
+                                content.push_str(
+                                    &self.source.source[segment_start..self.current_span().end],
+                                );
+                                self.consume();
+                                match self.adjacent_string('"') {
+                                    Some(count) => {
+                                        self.nconsume(count);
+                                        segment_start = self.current_span().end;
+                                        continue
+                                    }
+                                    None => break TokenKind::Str(content),
+                                }
+                            }
+                            Some('\\')
+                                if matches!(
+                                    self.nth_peek(self.current_span().end + 1),
+                                    Some('\\') | Some('"')
+                                ) =>
+                            {
+                                self.consume();
+                            }
+                            Some(_) => {}
+                            None => {
+                                self.eof = true;
+                                tracing::error!(target: "lexer", "UNEXPECTED EOF SPAN");
+                                return Some(Err(LexicalError::new(
+                                    LexicalErrorKind::UnexpectedEof,
+                                    self.current_span().clone(),
+                                )))
+                            }
                         }
+                        self.consume();
                     }
-                    self.consume();
-                },
+                }
                 // Allow string literals to be wrapped by single quotes
-                '\'' => loop {
-                    match self.peek() {
-                        Some('\'') => {
-                            self.consume();
-                            let str = self.slice();
-                            break TokenKind::Str((&str[1..str.len() - 1]).to_string())
-                        }
-                        Some('\\') if matches!(self.nth_peek(1), Some('\\') | Some('\'')) => {
-                            self.consume();
-                        }
-                        Some(_) => {}
-                        None => {
-                            self.eof = true;
-                            tracing::error!(target: "lexer", "UNEXPECTED EOF SPAN");
-                            return Some(Err(LexicalError::new(
-                                LexicalErrorKind::UnexpectedEof,
-                                self.current_span().clone(),
-                            )))
+                '\'' => {
+                    let mut content = String::new();
+                    let mut segment_start = self.current_span().end;
+                    loop {
+                        match self.peek() {
+                            Some('\'') => {
+                                content.push_str(
+                                    &self.source.source[segment_start..self.current_span().end],
+                                );
+                                self.consume();
+                                match self.adjacent_string('\'') {
+                                    Some(count) => {
+                                        self.nconsume(count);
+                                        segment_start = self.current_span().end;
+                                        continue
+                                    }
+                                    None => break TokenKind::Str(content),
+                                }
+                            }
+                            Some('\\')
+                                if matches!(
+                                    self.nth_peek(self.current_span().end + 1),
+                                    Some('\\') | Some('\'')
+                                ) =>
+                            {
+                                self.consume();
+                            }
+                            Some(_) => {}
+                            None => {
+                                self.eof = true;
+                                tracing::error!(target: "lexer", "UNEXPECTED EOF SPAN");
+                                return Some(Err(LexicalError::new(
+                                    LexicalErrorKind::UnexpectedEof,
+                                    self.current_span().clone(),
+                                )))
+                            }
                         }
+                        self.consume();
                     }
-                    self.consume();
-                },
+                }
                 // At this point, the source code has an invalid or unsupported token
                 ch => {
                     tracing::error!(target: "lexer", "UNSUPPORTED TOKEN '{}'", ch);
@@ -4,7 +4,15 @@ use std::ops::Deref;
 
 #[test]
 fn parses_builtin_function_in_macro_body() {
-    let builtin_funcs = ["__codesize", "__tablesize", "__tablestart"];
+    let builtin_funcs = [
+        "__codesize",
+        "__tablesize",
+        "__tablestart",
+        "__proxy_slot",
+        "__assert",
+        "__linked",
+        "__pc",
+    ];
 
     for builtin in builtin_funcs {
         let source = &format!(
@@ -69,7 +77,15 @@ fn parses_builtin_function_in_macro_body() {
 #[test]
 #[should_panic]
 fn fails_to_parse_builtin_outside_macro_body() {
-    let builtin_funcs = ["__codesize", "__tablesize", "__tablestart"];
+    let builtin_funcs = [
+        "__codesize",
+        "__tablesize",
+        "__tablestart",
+        "__proxy_slot",
+        "__assert",
+        "__linked",
+        "__pc",
+    ];
 
     for builtin in builtin_funcs {
         let source = &format!("{}(MAIN)", builtin);
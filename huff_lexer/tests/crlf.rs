@@ -0,0 +1,32 @@
+use huff_lexer::*;
+use huff_utils::prelude::*;
+
+#[test]
+fn crlf_lexes_like_lf() {
+    let lf = "// comment\n#define macro MAIN() = takes(0) returns(0) {\n    0x01 // one\n    /* block */ add\n}\n";
+    let crlf = lf.replace('\n', "\r\n");
+
+    let lex = |source: &str| {
+        Lexer::new(FullFileSource { source, file: None, spans: vec![] })
+            .map(|x| x.unwrap())
+            .filter(|x| !matches!(x.kind, TokenKind::Whitespace))
+            .collect::<Vec<Token>>()
+    };
+    let lf_tokens = lex(lf);
+    let crlf_tokens = lex(&crlf);
+
+    // Same tokens, including comments without a trailing carriage return
+    assert_eq!(
+        lf_tokens.iter().map(|t| t.kind.clone()).collect::<Vec<TokenKind>>(),
+        crlf_tokens.iter().map(|t| t.kind.clone()).collect::<Vec<TokenKind>>()
+    );
+    assert_eq!(crlf_tokens[0].kind, TokenKind::Comment("// comment".to_string()));
+
+    // Spans are byte offsets into the original source and cover the same text
+    for (lf_token, crlf_token) in lf_tokens.iter().zip(crlf_tokens.iter()) {
+        assert_eq!(
+            &lf[lf_token.span.start..lf_token.span.end],
+            &crlf[crlf_token.span.start..crlf_token.span.end]
+        );
+    }
+}
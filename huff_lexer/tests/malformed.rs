@@ -0,0 +1,57 @@
+use huff_lexer::*;
+use huff_utils::prelude::*;
+
+fn lex(source: &str) -> Vec<Result<Token, LexicalError<'_>>> {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    Lexer::new(flattened_source).collect()
+}
+
+#[test]
+fn lexes_multibyte_identifiers_by_byte_span() {
+    let tokens = lex("#define macro Ñandú() = takes(0) returns(0) {}");
+    let ident = tokens.iter().flatten().find(|t| matches!(t.kind, TokenKind::Ident(_))).unwrap();
+    assert_eq!(ident.kind, TokenKind::Ident("Ñandú".to_string()));
+    assert_eq!(ident.span, Span::new(14..21, None));
+}
+
+#[test]
+fn errors_on_oversized_hex_literal() {
+    let source = format!("0x{}", "ff".repeat(33));
+    let tokens = lex(&source);
+    assert!(matches!(
+        tokens[0],
+        Err(LexicalError { kind: LexicalErrorKind::LiteralTooLarge(_), .. })
+    ));
+}
+
+#[test]
+fn errors_on_oversized_number() {
+    let source = format!("{}0", usize::MAX);
+    let tokens = lex(&source);
+    assert!(matches!(
+        tokens[0],
+        Err(LexicalError { kind: LexicalErrorKind::LiteralTooLarge(_), .. })
+    ));
+}
+
+#[test]
+fn errors_on_invalid_array_size() {
+    let tokens = lex("#define function f(uint256[99999999999999999999999]) view returns ()");
+    assert!(tokens.iter().any(|t| matches!(
+        t,
+        Err(LexicalError { kind: LexicalErrorKind::InvalidArraySize(_), .. })
+    )));
+}
+
+#[test]
+fn lexes_escapes_in_strings_past_the_first_character() {
+    let tokens = lex(r#"  "a\"b""#);
+    assert_eq!(tokens[1].as_ref().unwrap().kind, TokenKind::Str(r#"a\"b"#.to_string()));
+}
+
+#[test]
+fn lexes_truncated_sources_without_panicking() {
+    for source in ["0", "#", "returns", "'é", "\"😀", "/*", "0x", "uint256[", "#define 😀"] {
+        let _ = lex(source);
+    }
+}
@@ -0,0 +1,51 @@
+use huff_lexer::*;
+use huff_utils::prelude::*;
+
+fn lex(source: &str) -> (Vec<Token>, Vec<LexicalError<'_>>) {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    Lexer::new(flattened_source).lex_with_recovery()
+}
+
+#[test]
+fn reports_every_error_in_one_pass() {
+    let source = format!(
+        "#define macro MAIN() = takes(0) returns(0) {{\n    0x{} $foo\n    0x01 @bar add\n}}",
+        "ff".repeat(33)
+    );
+    let (tokens, errors) = lex(&source);
+
+    assert_eq!(errors.len(), 3);
+    assert!(matches!(errors[0].kind, LexicalErrorKind::LiteralTooLarge(_)));
+    assert_eq!(errors[1].kind, LexicalErrorKind::InvalidCharacter('$'));
+    assert_eq!(errors[2].kind, LexicalErrorKind::InvalidCharacter('@'));
+
+    // The rest of the malformed tokens are skipped, but lexing resumes afterwards
+    let kinds = tokens
+        .into_iter()
+        .map(|t| t.kind)
+        .filter(|k| !matches!(k, TokenKind::Whitespace))
+        .collect::<Vec<TokenKind>>();
+    assert!(!kinds.contains(&TokenKind::Ident("foo".to_string())));
+    assert!(!kinds.contains(&TokenKind::Ident("bar".to_string())));
+    assert_eq!(
+        kinds[kinds.len() - 3..],
+        [TokenKind::Opcode(Opcode::Add), TokenKind::CloseBrace, TokenKind::Eof]
+    );
+}
+
+#[test]
+fn recovers_at_delimiters() {
+    let (tokens, errors) = lex("#define macro MAIN(a$b) = takes(0) returns(0) {}");
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].kind, LexicalErrorKind::InvalidCharacter('$'));
+    assert!(tokens.iter().any(|t| t.kind == TokenKind::CloseParen));
+    assert_eq!(tokens.last().unwrap().kind, TokenKind::Eof);
+}
+
+#[test]
+fn lexes_valid_sources_without_errors() {
+    let (tokens, errors) = lex("#define macro MAIN() = takes(0) returns(0) { 0x01 }");
+    assert!(errors.is_empty());
+    assert_eq!(tokens.last().unwrap().kind, TokenKind::Eof);
+}
@@ -0,0 +1,36 @@
+use huff_lexer::*;
+use huff_utils::prelude::*;
+
+fn lex(source: &str) -> Vec<Token> {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    Lexer::new(flattened_source)
+        .map(|t| t.unwrap())
+        .filter(|t| !matches!(t.kind, TokenKind::Whitespace | TokenKind::Eof))
+        .collect()
+}
+
+#[test]
+fn concatenates_adjacent_strings() {
+    let source = r#""transfer(" "address,uint256"
+        ")""#;
+    let tokens = lex(source);
+    assert_eq!(tokens.len(), 1);
+    assert_eq!(tokens[0].kind, TokenKind::Str("transfer(address,uint256)".to_string()));
+    assert_eq!(tokens[0].span, Span::new(0..source.len(), None));
+
+    let tokens = lex("'Ma' 'th'");
+    assert_eq!(tokens[0].kind, TokenKind::Str("Math".to_string()));
+}
+
+#[test]
+fn separated_strings_are_not_concatenated() {
+    let tokens = lex(r#""a", "b" 'c'"#);
+    let strings = tokens
+        .iter()
+        .filter_map(|t| match &t.kind {
+            TokenKind::Str(s) => Some(s.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(strings, vec!["a", "b", "c"]);
+}
@@ -4,14 +4,17 @@
 #![forbid(unsafe_code)]
 #![forbid(where_clauses_object_safety)]
 
+use ethers_core::types::U256;
 use huff_utils::{
     ast::*,
     error::*,
-    prelude::{str_to_bytes32, FileSource, Span},
+    prelude::{
+        bytes32_to_string, str_to_bytes32, FileProvider, FileSource, FileSystemFileProvider, Span,
+    },
     token::{Token, TokenKind},
     types::*,
 };
-use std::path::Path;
+use std::{collections::HashMap, path::Path, sync::Arc};
 use tiny_keccak::{Hasher, Keccak};
 
 /// The Parser
@@ -27,13 +30,28 @@ pub struct Parser {
     pub base: Option<String>,
     /// A collection of current spans
     pub spans: Vec<Span>,
+    /// The provider used to validate import paths
+    pub file_provider: Arc<dyn FileProvider>,
 }
 
 impl Parser {
     /// Public associated function that instantiates a Parser.
     pub fn new(tokens: Vec<Token>, base: Option<String>) -> Self {
         let initial_token = tokens.get(0).unwrap().clone();
-        Self { tokens, cursor: 0, current_token: initial_token, base, spans: vec![] }
+        Self {
+            tokens,
+            cursor: 0,
+            current_token: initial_token,
+            base,
+            spans: vec![],
+            file_provider: Arc::new(FileSystemFileProvider),
+        }
+    }
+
+    /// Sets the provider used to validate import paths
+    pub fn with_file_provider(mut self, file_provider: Arc<dyn FileProvider>) -> Self {
+        self.file_provider = file_provider;
+        self
     }
 
     /// Resets the current token and cursor to the first token in the parser's token vec
@@ -46,72 +64,211 @@ impl Parser {
 
     /// Parse
     pub fn parse(&mut self) -> Result<Contract, ParserError> {
-        // Remove all whitespaces, newlines, and comments first
-        self.tokens
-            .retain(|token| !matches!(token.kind, TokenKind::Whitespace | TokenKind::Comment(_)));
+        self.parse_definitions(false).map_err(|mut errors| errors.remove(0))
+    }
+
+    /// Parses the contract, collecting every error rather than stopping at the first
+    ///
+    /// When a definition fails to parse, the parser skips ahead to the next `#define` and
+    /// continues, so multiple malformed definitions are all reported in one pass.
+    pub fn parse_with_recovery(&mut self) -> Result<Contract, Vec<ParserError>> {
+        self.parse_definitions(true)
+    }
+
+    /// Skips tokens until the start of the next definition or the end of the file
+    pub fn synchronize(&mut self) {
+        while !self.check(TokenKind::Eof) && !self.check(TokenKind::Define) {
+            self.consume();
+        }
+    }
+
+    /// Parses all imports and definitions, recovering at definition boundaries if `recover` is set
+    fn parse_definitions(&mut self, recover: bool) -> Result<Contract, Vec<ParserError>> {
+        // Remove all whitespaces, newlines, and comments first, keeping the warnings disabled by
+        // `// huff-disable: <warning>` directives keyed by the index of the following `#define`,
+        // the memory regions declared by `// huff-memory: <start>..<end>` directives and the
+        // return size declared by a `// huff-returns: <bytes>` directive
+        let mut suppressions: HashMap<usize, Vec<String>> = HashMap::new();
+        let mut memory_regions: Vec<(usize, usize)> = vec![];
+        let mut return_size: Option<usize> = None;
+        let mut pending: Vec<String> = vec![];
+        let mut tokens = vec![];
+        for token in std::mem::take(&mut self.tokens) {
+            match &token.kind {
+                TokenKind::Whitespace => continue,
+                TokenKind::Comment(c) => {
+                    pending.extend(Parser::disabled_warnings(c));
+                    memory_regions.extend(Parser::memory_regions(c));
+                    return_size = Parser::return_size(c).or(return_size);
+                    continue
+                }
+                TokenKind::Define if !pending.is_empty() => {
+                    suppressions.insert(tokens.len(), std::mem::take(&mut pending));
+                }
+                _ => pending.clear(),
+            }
+            tokens.push(token);
+        }
+        self.tokens = tokens;
 
         // Reset the initial token
         self.reset();
 
         // Initialize an empty Contract
-        let mut contract = Contract::default();
+        let mut contract = Contract { memory_regions, return_size, ..Default::default() };
+
+        let mut errors = vec![];
 
         // First iterate over imports
         while !self.check(TokenKind::Eof) && !self.check(TokenKind::Define) {
-            contract.imports.push(self.parse_imports()?);
-            tracing::info!(target: "parser", "SUCCESSFULLY PARSED IMPORTS");
+            match self.parse_imports() {
+                Ok(import) => {
+                    contract.imports.push(import);
+                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED IMPORTS");
+                }
+                Err(e) if recover => {
+                    errors.push(e);
+                    self.synchronize();
+                }
+                Err(e) => return Err(vec![e]),
+            }
         }
 
         // Iterate over tokens and construct the Contract aka AST
         while !self.check(TokenKind::Eof) {
             // Reset our spans
             self.spans = vec![];
+            let suppressed_warnings = suppressions.remove(&self.cursor).unwrap_or_default();
 
-            // first token should be keyword "#define"
-            self.match_kind(TokenKind::Define)?;
-
-            // match to fucntion, constant, macro, or event
-            match self.current_token.kind {
-                TokenKind::Function => {
-                    let func = self.parse_function()?;
-                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED FUNCTION {}", func.name);
-                    contract.functions.push(func);
-                }
-                TokenKind::Event => {
-                    let ev = self.parse_event()?;
-                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED EVENT {}", ev.name);
-                    contract.events.push(ev);
-                }
-                TokenKind::Constant => {
-                    let c = self.parse_constant()?;
-                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED CONSTANT {}", c.name);
-                    contract.constants.push(c);
-                }
-                TokenKind::Macro => {
-                    let m = self.parse_macro()?;
-                    tracing::info!(target: "parser", "SUCCESSFULLY PARSED MACRO {}", m.name);
-                    contract.macros.push(m);
-                }
-                TokenKind::JumpTable | TokenKind::JumpTablePacked | TokenKind::CodeTable => {
-                    contract.tables.push(self.parse_table()?);
-                }
-                _ => {
-                    tracing::error!(
-                        target: "parser",
-                        "Invalid definition. Must be a function, event, constant, or macro. Got: {}",
-                        self.current_token.kind
-                    );
-                    return Err(ParserError {
-                        kind: ParserErrorKind::InvalidDefinition,
-                        spans: AstSpan(self.spans.clone()),
-                    })
+            if let Err(e) = self.parse_definition(&mut contract, suppressed_warnings) {
+                if !recover {
+                    return Err(vec![e])
                 }
-            };
+                errors.push(e);
+                self.synchronize();
+            }
         }
 
+        if !errors.is_empty() {
+            return Err(errors)
+        }
+
+        // Constants may be defined after the code tables that inline them
+        contract.size_code_tables();
+
         Ok(contract)
     }
 
+    /// Parses a single `#define` definition into the contract
+    fn parse_definition(
+        &mut self,
+        contract: &mut Contract,
+        suppressed_warnings: Vec<String>,
+    ) -> Result<(), ParserError> {
+        // first token should be keyword "#define"
+        self.match_kind(TokenKind::Define)?;
+
+        // match to fucntion, constant, macro, or event
+        match self.current_token.kind {
+            TokenKind::Function => {
+                let func = self.parse_function()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED FUNCTION {}", func.name);
+                contract.functions.push(func);
+            }
+            TokenKind::Event => {
+                let ev = self.parse_event()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED EVENT {}", ev.name);
+                contract.events.push(ev);
+            }
+            TokenKind::Constant => {
+                let c = self.parse_constant()?;
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED CONSTANT {}", c.name);
+                contract.constants.push(c);
+            }
+            TokenKind::Macro => {
+                let m = MacroDefinition { suppressed_warnings, ..self.parse_macro()? };
+                tracing::info!(target: "parser", "SUCCESSFULLY PARSED MACRO {}", m.name);
+                contract.macros.push(m);
+            }
+            TokenKind::JumpTable | TokenKind::JumpTablePacked | TokenKind::CodeTable => {
+                contract.tables.push(self.parse_table()?);
+            }
+            _ => {
+                tracing::error!(
+                    target: "parser",
+                    "Invalid definition. Must be a function, event, constant, or macro. Got: {}",
+                    self.current_token.kind
+                );
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidDefinition,
+                    spans: AstSpan(self.spans.clone()),
+                })
+            }
+        };
+        Ok(())
+    }
+
+    /// Returns the warnings named by a `// huff-disable: <warning>, ...` comment
+    fn disabled_warnings(comment: &str) -> Vec<String> {
+        comment
+            .trim_start_matches('/')
+            .trim()
+            .strip_prefix("huff-disable:")
+            .map(|warnings| {
+                warnings
+                    .split(',')
+                    .map(|w| w.trim().to_string())
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The big endian word of a constant's value
+    fn u256_literal(value: U256) -> Literal {
+        let mut literal = [0u8; 32];
+        value.to_big_endian(&mut literal);
+        literal
+    }
+
+    /// Parses a number in a directive comment, `0x` prefixed hex or decimal
+    fn directive_number(number: &str) -> Option<usize> {
+        match number.trim().strip_prefix("0x") {
+            Some(hex) => usize::from_str_radix(hex, 16).ok(),
+            None => number.trim().parse::<usize>().ok(),
+        }
+    }
+
+    /// Returns the return size in bytes declared by a `// huff-returns: <bytes>` comment
+    fn return_size(comment: &str) -> Option<usize> {
+        comment
+            .trim_start_matches('/')
+            .trim()
+            .strip_prefix("huff-returns:")
+            .and_then(Parser::directive_number)
+    }
+
+    /// Returns the memory regions declared by a `// huff-memory: <start>..<end>, ...` comment.
+    ///
+    /// Malformed or empty regions are skipped.
+    fn memory_regions(comment: &str) -> Vec<(usize, usize)> {
+        comment
+            .trim_start_matches('/')
+            .trim()
+            .strip_prefix("huff-memory:")
+            .map(|regions| {
+                regions
+                    .split(',')
+                    .filter_map(|region| {
+                        let (start, end) = region.split_once("..")?;
+                        Some((Parser::directive_number(start)?, Parser::directive_number(end)?))
+                            .filter(|(start, end)| start < end)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     /// Parses Contract Imports
     pub fn parse_imports(&mut self) -> Result<FilePath, ParserError> {
         // First token should be keyword "#include"
@@ -147,7 +304,7 @@ impl Parser {
         let path = Path::new(&p);
 
         // Validate that a file @ the path exists
-        if !(path.exists() && path.is_file() && path.to_str().unwrap().ends_with(".huff")) {
+        if !(self.file_provider.exists(&p) && p.ends_with(".huff")) {
             tracing::error!(target: "parser", "INVALID IMPORT PATH: {:?}", path.to_str());
             let new_spans = self.spans.clone();
             self.spans = vec![];
@@ -299,7 +456,13 @@ impl Parser {
         // Parse the event's parameters
         let parameters: Vec<Argument> = self.parse_args(true, true, true)?;
 
-        Ok(Event { name, parameters, span: AstSpan(self.spans.clone()) })
+        // Anonymous events are emitted without their signature as topic0
+        let anonymous = self.check(TokenKind::Anonymous);
+        if anonymous {
+            self.consume();
+        }
+
+        Ok(Event { name, parameters, anonymous, span: AstSpan(self.spans.clone()) })
     }
 
     /// Parse a constant.
@@ -331,10 +494,78 @@ impl Parser {
                 self.consume();
                 ConstVal::FreeStoragePointer(FreeStoragePointer {})
             }
+            TokenKind::Immutable => {
+                self.consume();
+                ConstVal::Immutable
+            }
             TokenKind::Literal(l) => {
                 self.consume();
                 ConstVal::Literal(l)
             }
+            // Decimal values are stored as the same 32-byte literal as their hex form,
+            // optionally scaled by an ether unit suffix (`wei`, `gwei` or `ether`)
+            TokenKind::Decimal(digits) => {
+                let kind = self.current_token.kind.clone();
+                self.consume();
+                let unit = match &self.current_token.kind {
+                    TokenKind::Ident(unit) if unit == "wei" => Some(U256::one()),
+                    TokenKind::Ident(unit) if unit == "gwei" => Some(U256::exp10(9)),
+                    TokenKind::Ident(unit) if unit == "ether" => Some(U256::exp10(18)),
+                    _ => None,
+                };
+                if unit.is_some() {
+                    self.consume();
+                }
+                let value = U256::from_dec_str(&digits)
+                    .ok()
+                    .and_then(|n| n.checked_mul(unit.unwrap_or_else(U256::one)));
+                match value {
+                    Some(value) => ConstVal::Literal(Parser::u256_literal(value)),
+                    None => {
+                        tracing::error!(target: "parser", "DECIMAL CONSTANT EXCEEDS 32 BYTES: {}", kind);
+                        let new_spans = self.spans.clone();
+                        self.spans = vec![];
+                        return Err(ParserError {
+                            kind: ParserErrorKind::InvalidConstantValue(kind),
+                            spans: AstSpan(new_spans),
+                        })
+                    }
+                }
+            }
+            // Arrays of literals, inlined back to back into code tables
+            TokenKind::OpenBracket => {
+                self.consume();
+                let mut elements = vec![];
+                while !self.check(TokenKind::CloseBracket) {
+                    let element = match self.current_token.kind.clone() {
+                        TokenKind::Literal(l) => Some(l),
+                        TokenKind::Decimal(digits) => {
+                            U256::from_dec_str(&digits).ok().map(Parser::u256_literal)
+                        }
+                        _ => None,
+                    };
+                    let element = match element {
+                        Some(element) => element,
+                        None => {
+                            let kind = self.current_token.kind.clone();
+                            tracing::error!(target: "parser", "TOKEN MISMATCH - EXPECTED Literal IN CONSTANT ARRAY, GOT: {}", kind);
+                            let new_spans = self.spans.clone();
+                            self.spans = vec![];
+                            return Err(ParserError {
+                                kind: ParserErrorKind::InvalidConstantValue(kind),
+                                spans: AstSpan(new_spans),
+                            })
+                        }
+                    };
+                    self.consume();
+                    elements.push(element);
+                    if !self.check(TokenKind::CloseBracket) {
+                        self.match_kind(TokenKind::Comma)?;
+                    }
+                }
+                self.match_kind(TokenKind::CloseBracket)?;
+                ConstVal::Array(elements)
+            }
             kind => {
                 tracing::error!(target: "parser", "TOKEN MISMATCH - EXPECTED FreeStoragePointer OR Literal, GOT: {}", self.current_token.kind);
                 let new_spans = self.spans.clone();
@@ -351,7 +582,8 @@ impl Parser {
         self.spans = vec![];
 
         // Return the Constant Definition
-        Ok(ConstantDefinition { name, value, span: AstSpan(new_spans) })
+        let span = AstSpan(new_spans);
+        Ok(ConstantDefinition { name, value, source_file: span.source_file(), span })
     }
 
     /// Parses a macro.
@@ -369,16 +601,31 @@ impl Parser {
         let macro_takes: usize = self.parse_single_arg()?;
         self.match_kind(TokenKind::Returns)?;
         let macro_returns: usize = self.parse_single_arg()?;
+        let (mut outlined, mut facet) = (false, false);
+        loop {
+            if self.check(TokenKind::Outline) {
+                outlined = true;
+            } else if self.check(TokenKind::Facet) {
+                facet = true;
+            } else {
+                break
+            }
+            self.consume();
+        }
         let macro_statements: Vec<Statement> = self.parse_body()?;
 
-        Ok(MacroDefinition::new(
-            macro_name,
-            macro_arguments,
-            macro_statements,
-            macro_takes,
-            macro_returns,
-            self.spans.clone(),
-        ))
+        Ok(MacroDefinition {
+            outlined,
+            facet,
+            ..MacroDefinition::new(
+                macro_name,
+                macro_arguments,
+                macro_statements,
+                macro_takes,
+                macro_returns,
+                self.spans.clone(),
+            )
+        })
     }
 
     /// Parse the body of a macro.
@@ -473,7 +720,7 @@ impl Parser {
                 TokenKind::BuiltinFunction(f) => {
                     let mut curr_spans = vec![self.current_token.span.clone()];
                     self.match_kind(TokenKind::BuiltinFunction(String::default()))?;
-                    let args = self.parse_args(true, false, false)?;
+                    let args = self.parse_builtin_args()?;
                     args.iter().for_each(|a| curr_spans.extend_from_slice(&a.span.0));
                     tracing::info!(target: "parser", "PARSING MACRO BODY: [BUILTIN FN: {}({:?})]", f, args);
                     statements.push(Statement {
@@ -642,7 +889,16 @@ impl Parser {
                 arg.name = Some(self.match_kind(TokenKind::Ident("x".to_string()))?.to_string())
             }
 
-            // multiple args possible
+            // An empty argument, e.g. `(a,,b)`, is an error, but a trailing comma isn't
+            if arg_spans.is_empty() {
+                tracing::error!(target: "parser", "EMPTY ARGUMENT, GOT: {}", self.current_token.kind);
+                return Err(ParserError {
+                    kind: ParserErrorKind::InvalidArgs(self.current_token.kind.clone()),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
+
+            // multiple args possible, optionally followed by a trailing comma
             if self.check(TokenKind::Comma) {
                 self.consume();
             }
@@ -656,6 +912,49 @@ impl Parser {
         Ok(args)
     }
 
+    /// Parses the arguments of a builtin function call.
+    ///
+    /// Arguments are either identifiers (macros, tables, functions), plain numbers, strings or
+    /// literals, e.g. `__calldata_offset(transfer, 1)` or `__proxy_slot("admin")`. The comparison
+    /// operators of an `__assert(SLOT < 0x20)` condition are kept as arguments of their own.
+    pub fn parse_builtin_args(&mut self) -> Result<Vec<Argument>, ParserError> {
+        let mut args: Vec<Argument> = Vec::new();
+        self.match_kind(TokenKind::OpenParen)?;
+        while !self.check(TokenKind::CloseParen) {
+            let arg_span = self.current_token.span.clone();
+            let name = match self.current_token.kind.clone() {
+                TokenKind::Ident(ident) => ident,
+                TokenKind::Num(num) => num.to_string(),
+                TokenKind::Str(s) => s,
+                TokenKind::Literal(l) => bytes32_to_string(&l, true),
+                TokenKind::LeftAngle => "<".to_string(),
+                TokenKind::RightAngle => ">".to_string(),
+                TokenKind::Assign => "=".to_string(),
+                kind => {
+                    tracing::error!(target: "parser", "INVALID BUILTIN FUNCTION ARGUMENT: {}", kind);
+                    return Err(ParserError {
+                        kind: ParserErrorKind::InvalidArgs(kind),
+                        spans: AstSpan(vec![arg_span]),
+                    })
+                }
+            };
+            self.consume();
+            args.push(Argument {
+                name: Some(name),
+                span: AstSpan(vec![arg_span]),
+                ..Default::default()
+            });
+
+            // multiple args possible
+            if self.check(TokenKind::Comma) {
+                self.consume();
+            }
+        }
+        // consume close parenthesis
+        self.match_kind(TokenKind::CloseParen)?;
+        Ok(args)
+    }
+
     /// Parses the following : (x)
     pub fn parse_single_arg(&mut self) -> Result<usize, ParserError> {
         self.match_kind(TokenKind::OpenParen)?;
@@ -740,6 +1039,10 @@ impl Parser {
 
         // Parse the core table
         let table_statements: Vec<Statement> = self.parse_table_body()?;
+
+        // The table size is its length in bytes, *not* its number of entries:
+        // 2 bytes per packed jump table entry, 32 bytes per jump table entry and the raw
+        // length of the code for code tables.
         let size = match kind {
             TableKind::JumpTablePacked => table_statements.len() * 0x02,
             TableKind::JumpTable => table_statements.len() * 0x20,
@@ -749,6 +1052,9 @@ impl Parser {
                     .map(|s| {
                         if let StatementType::LabelCall(l) = &s.ty {
                             l.len()
+                        } else if let StatementType::Constant(_) = &s.ty {
+                            // Sized once all constants are parsed
+                            0_usize
                         } else {
                             // TODO: Throw an error here.
                             tracing::error!(
@@ -768,7 +1074,7 @@ impl Parser {
             table_name,
             kind,
             table_statements,
-            str_to_bytes32(size.to_string().as_str()),
+            str_to_bytes32(format!("{:x}", size).as_str()),
             AstSpan(self.spans.clone()),
         ))
     }
@@ -790,6 +1096,13 @@ impl Parser {
                     });
                     self.consume();
                 }
+                TokenKind::OpenBracket => {
+                    let (constant, const_span) = self.parse_constant_push()?;
+                    statements.push(Statement {
+                        ty: StatementType::Constant(constant),
+                        span: AstSpan(vec![const_span]),
+                    });
+                }
                 kind => {
                     tracing::error!("Invalid Table Body Token: {:?}", self.current_token.kind);
                     return Err(ParserError {
@@ -804,16 +1117,18 @@ impl Parser {
         Ok(statements)
     }
 
-    /// Parses a constant push.
+    /// Parses a constant push, returning the constant's name and the span of the whole
+    /// `[NAME]` push.
     pub fn parse_constant_push(&mut self) -> Result<(String, Span), ParserError> {
+        let open_span = self.current_token.span.clone();
         self.match_kind(TokenKind::OpenBracket)?;
         match self.current_token.kind.clone() {
             TokenKind::Ident(const_str) => {
                 // Consume the Ident and Validate Close Bracket
-                let iden_span = self.current_token.span.clone();
                 self.consume();
+                let close_span = self.current_token.span.clone();
                 self.match_kind(TokenKind::CloseBracket)?;
-                Ok((const_str, iden_span))
+                Ok((const_str, open_span.merge(&close_span)))
             }
             kind => {
                 let new_spans = self.spans.clone();
@@ -877,6 +1192,14 @@ impl Parser {
                 let _ = self.parse_primitive_type(prim);
                 Ok(token)
             }
+            // Fixed point numbers are declared by Solidity but can't be used
+            TokenKind::Ident(ty) if ty.starts_with("fixed") || ty.starts_with("ufixed") => {
+                tracing::error!(target: "parser", "UNSUPPORTED TYPE: {}", ty);
+                Err(ParserError {
+                    kind: ParserErrorKind::UnsupportedType(ty),
+                    spans: AstSpan(vec![self.current_token.span.clone()]),
+                })
+            }
             kind => Err(ParserError {
                 kind: ParserErrorKind::InvalidArgs(kind),
                 spans: AstSpan(vec![self.current_token.span.clone()]),
@@ -18,8 +18,78 @@ fn build_abi_from_ast() {
     let abi = Abi::from(contract);
 
     assert_eq!(
-        abi.functions.get("test").unwrap().inputs[0].kind,
+        abi.functions.get("test").unwrap()[0].inputs[0].kind,
         FunctionParamType::Array(Box::new(FunctionParamType::Uint(256)), vec![2, 0])
     );
-    assert_eq!(abi.functions.get("test").unwrap().inputs[1].kind, FunctionParamType::String);
+    assert_eq!(abi.functions.get("test").unwrap()[0].inputs[1].kind, FunctionParamType::String);
+}
+
+#[test]
+fn keeps_overloaded_functions() {
+    let source = "#define function foo(uint256) nonpayable returns ()\n#define function foo(address) nonpayable returns ()";
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    // Both overloads keep their own selector
+    assert_eq!(contract.functions[0].signature, [0x2f, 0xbe, 0xbd, 0x38]);
+    assert_eq!(contract.functions[1].signature, [0xfd, 0xf8, 0x0b, 0xda]);
+
+    // And both appear in the ABI
+    let abi = Abi::from(contract);
+    let overloads = abi.functions.get("foo").unwrap();
+    assert_eq!(overloads.len(), 2);
+    assert_eq!(overloads[0].inputs[0].kind, FunctionParamType::Uint(256));
+    assert_eq!(overloads[1].inputs[0].kind, FunctionParamType::Address);
+}
+
+#[test]
+fn keeps_named_outputs() {
+    let source =
+        "#define function balanceOf(address owner) view returns (uint256 balance, bool ok)";
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    let abi = Abi::from(contract);
+
+    let outputs = &abi.functions.get("balanceOf").unwrap()[0].outputs;
+    assert_eq!(outputs[0].name, "balance");
+    assert_eq!(outputs[0].kind, FunctionParamType::Uint(256));
+    assert_eq!(outputs[1].name, "ok");
+    assert_eq!(outputs[1].kind, FunctionParamType::Bool);
+}
+
+#[test]
+fn matches_solc_abi_json() {
+    // Generated by `solc --abi` for the equivalent Solidity interface:
+    //
+    // interface Token {
+    //     event Transfer(address indexed from, address indexed to, uint256 value);
+    //     function balanceOf(address owner) external view returns (uint256);
+    //     function transfer(address to, uint256 amount) external returns (bool);
+    //     function batch(uint256[2][] calldata ids, string calldata memo) external payable;
+    // }
+    let solc_abi = include_str!("fixtures/solc_abi.json");
+
+    let source = r#"
+        #define function transfer(address to, uint256 amount) nonpayable returns (bool)
+        #define function balanceOf(address owner) view returns (uint256)
+        #define function batch(uint256[2][] ids, string memo) payable returns ()
+        #define event Transfer(address indexed from, address indexed to, uint256 value)
+    "#;
+
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+    let abi = Abi::from(contract);
+
+    assert_eq!(serde_json::to_string(&abi.to_solc_json()).unwrap(), solc_abi.trim());
 }
@@ -0,0 +1,75 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse().unwrap()
+}
+
+#[test]
+fn parses_comments_between_invocation_args() {
+    let contract = parse(
+        r#"
+        #define macro FOO(slot, value) = takes(0) returns(0) {
+            <value> <slot> sstore
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            FOO(0x01 /* slot */, 0x02)
+            FOO(/* slot */ 0x03, // value
+                0x04)
+        }
+    "#,
+    );
+
+    let main = contract.find_macro_by_name("MAIN").unwrap();
+    let args = main
+        .statements
+        .iter()
+        .map(|s| match &s.ty {
+            StatementType::MacroInvocation(mi) => mi.args.clone(),
+            ty => panic!("unexpected statement {:?}", ty),
+        })
+        .collect::<Vec<_>>();
+    let literal = |n: u8| MacroArg::Literal(str_to_bytes32(&format!("{:02x}", n)));
+    assert_eq!(args, [vec![literal(1), literal(2)], vec![literal(3), literal(4)]]);
+}
+
+#[test]
+fn parses_comments_between_definition_args() {
+    let contract = parse(
+        r#"
+        #define macro FOO(slot /* storage slot */, value) = takes(0) returns(0) {
+            <value> <slot> sstore
+        }
+    "#,
+    );
+
+    let params = &contract.find_macro_by_name("FOO").unwrap().parameters;
+    let names = params.iter().map(|p| p.name.clone().unwrap()).collect::<Vec<_>>();
+    assert_eq!(names, ["slot", "value"]);
+}
+
+#[test]
+fn parses_comments_between_builtin_args() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            __linked(/* the token library */ "Token")
+        }
+    "#,
+    );
+
+    let main = contract.find_macro_by_name("MAIN").unwrap();
+    match &main.statements[0].ty {
+        StatementType::BuiltinFunctionCall(bf) => {
+            assert_eq!(bf.args.len(), 1);
+            assert_eq!(bf.args[0].name.as_deref(), Some("Token"));
+        }
+        ty => panic!("unexpected statement {:?}", ty),
+    }
+}
@@ -18,6 +18,7 @@ fn test_parses_free_storage_pointer_constant() {
         ConstantDefinition {
             name: "FSP_LOCATION".to_string(),
             value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            source_file: None,
             span: AstSpan(vec![
                 Span { start: 0, end: 7, file: None },
                 Span { start: 8, end: 16, file: None },
@@ -50,6 +51,7 @@ fn test_parses_literal_constant() {
         ConstantDefinition {
             name: "LITERAL".to_string(),
             value: ConstVal::Literal(arr),
+            source_file: None,
             span: AstSpan(vec![
                 Span { start: 0, end: 7, file: None },
                 Span { start: 8, end: 16, file: None },
@@ -60,3 +62,104 @@ fn test_parses_literal_constant() {
         }
     );
 }
+
+#[test]
+fn test_parses_decimal_constant() {
+    let parse_value = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let contract = parser.parse().unwrap();
+        assert_eq!(parser.current_token.kind, TokenKind::Eof);
+        contract.constants[0].value.clone()
+    };
+
+    let decimal = parse_value("#define constant DECIMAL = 255");
+    assert_eq!(decimal, ConstVal::Literal(str_to_bytes32("ff")));
+    assert_eq!(decimal, parse_value("#define constant DECIMAL = 0xff"));
+}
+
+#[test]
+fn test_parses_word_sized_decimal_constants() {
+    let parse = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let tokens = Lexer::new(flattened_source).map(|x| x.unwrap()).collect::<Vec<Token>>();
+        Parser::new(tokens, None).parse()
+    };
+
+    // 2^64 doesn't fit a usize
+    let contract = parse("#define constant BIG = 18446744073709551616").unwrap();
+    assert_eq!(contract.constants[0].value, ConstVal::Literal(str_to_bytes32("10000000000000000")));
+
+    let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    let contract = parse(&format!("#define constant MAX = {}", max)).unwrap();
+    assert_eq!(contract.constants[0].value, ConstVal::Literal([0xff; 32]));
+
+    // Beyond a word, directly or once scaled by a unit
+    let too_large =
+        "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+    let err = parse(&format!("#define constant OVER = {}", too_large)).unwrap_err();
+    assert_eq!(
+        err.kind,
+        ParserErrorKind::InvalidConstantValue(TokenKind::Decimal(too_large.into()))
+    );
+    let err = parse(&format!("#define constant OVER = {} ether", max)).unwrap_err();
+    assert_eq!(err.kind, ParserErrorKind::InvalidConstantValue(TokenKind::Decimal(max.into())));
+}
+
+#[test]
+fn test_parses_ether_unit_constants() {
+    let parse_value = |source: &str| {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        let contract = parser.parse().unwrap();
+        assert_eq!(parser.current_token.kind, TokenKind::Eof);
+        contract.constants[0].value.clone()
+    };
+
+    assert_eq!(
+        parse_value("#define constant ONE = 1 ether"),
+        ConstVal::Literal(str_to_bytes32("de0b6b3a7640000"))
+    );
+    assert_eq!(
+        parse_value("#define constant ONE = 1 gwei"),
+        ConstVal::Literal(str_to_bytes32("3b9aca00"))
+    );
+    assert_eq!(
+        parse_value("#define constant ONE = 100 wei"),
+        ConstVal::Literal(str_to_bytes32("64"))
+    );
+    // Units are applied to the following definitions independently
+    let source = "#define constant A = 1 ether\n#define constant B = 1";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let tokens = Lexer::new(flattened_source).map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let contract = Parser::new(tokens, None).parse().unwrap();
+    assert_eq!(contract.constants[1].value, ConstVal::Literal(str_to_bytes32("01")));
+}
+
+#[test]
+fn test_parses_immutable_constant() {
+    let source = "#define constant OWNER = IMMUTABLE()\n#define constant IMMUTABLE_ISH = 0x01";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let tokens = Lexer::new(flattened_source).map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let contract = Parser::new(tokens, None).parse().unwrap();
+
+    assert_eq!(contract.constants[0].value, ConstVal::Immutable);
+    assert_eq!(contract.constants[1].name, "IMMUTABLE_ISH");
+}
+
+#[test]
+fn test_parses_constant_array() {
+    let source = "#define constant VALUES = [0x01, 0x02, 0xff00]";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let tokens = Lexer::new(flattened_source).map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let contract = Parser::new(tokens, None).parse().unwrap();
+
+    assert_eq!(
+        contract.constants[0].value,
+        ConstVal::Array(vec![str_to_bytes32("01"), str_to_bytes32("02"), str_to_bytes32("ff00")])
+    );
+}
@@ -0,0 +1,52 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let tokens = Lexer::new(flattened_source).map(|x| x.unwrap()).collect::<Vec<Token>>();
+    Parser::new(tokens, None).parse().unwrap()
+}
+
+const SOURCE: &str = r#"
+    #define function transfer(address, uint256) nonpayable returns (bool)
+    #define function balanceOf(address) view returns (uint256)
+    #define event Transfer(address indexed, address indexed, uint256)
+    #define constant OWNER = FREE_STORAGE_POINTER()
+    #define constant LIMIT = 0x10
+"#;
+
+#[test]
+fn accesses_declarations() {
+    let contract = parse(SOURCE);
+
+    let functions = contract.functions().iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(functions, vec!["transfer", "balanceOf"]);
+    let events = contract.events().iter().map(|e| e.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(events, vec!["Transfer"]);
+    let constants = contract.constants().iter().map(|c| c.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(constants, vec!["OWNER", "LIMIT"]);
+}
+
+#[test]
+fn finds_functions_by_name() {
+    let contract = parse(SOURCE);
+
+    let balance_of = contract.find_function_by_name("balanceOf").unwrap();
+    assert_eq!(balance_of.fn_type, FunctionType::View);
+    assert_eq!(balance_of, contract.functions()[1]);
+    assert!(contract.find_function_by_name("approve").is_none());
+}
+
+#[test]
+fn finds_events_by_name() {
+    let contract = parse(SOURCE);
+
+    let transfer = contract.find_event_by_name("Transfer").unwrap();
+    assert_eq!(transfer.parameters.len(), 3);
+    assert!(contract.find_event_by_name("Approval").is_none());
+
+    // Functions and events don't share a namespace
+    assert!(contract.find_event_by_name("transfer").is_none());
+    assert!(contract.find_function_by_name("Transfer").is_none());
+}
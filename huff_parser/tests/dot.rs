@@ -0,0 +1,60 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+#[test]
+fn renders_macro_graph_as_dot() {
+    let source = r#"
+        #define constant OWNER = FREE_STORAGE_POINTER()
+
+        #define macro HELPER() = takes(0) returns(0) {
+            caller [OWNER] sstore
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            HELPER()
+            HELPER()
+        }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let dot = contract.to_dot();
+    assert!(dot.starts_with("digraph Contract {"));
+    assert!(dot.ends_with('}'));
+    assert!(dot.contains("\"OWNER\" [shape=box];"));
+    assert!(dot.contains("\"HELPER\" -> \"OWNER\" [style=dashed];"));
+
+    // Repeated invocations produce a single edge
+    assert_eq!(dot.matches("\"MAIN\" -> \"HELPER\";").count(), 1);
+}
+
+#[test]
+fn renders_edges_inside_labels() {
+    let source = r#"
+        #define constant LIMIT = 0x10
+
+        #define macro HELPER() = takes(0) returns(0) {
+            0x01 pop
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            loop:
+                HELPER()
+                [LIMIT] pop
+                loop jump
+        }
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    let dot = contract.to_dot();
+    assert!(dot.contains("\"MAIN\" -> \"HELPER\";"));
+    assert!(dot.contains("\"MAIN\" -> \"LIMIT\" [style=dashed];"));
+}
@@ -1,6 +1,6 @@
 use huff_lexer::*;
 use huff_parser::*;
-use huff_utils::{ast::Event, prelude::*};
+use huff_utils::{abi::Abi, ast::Event, prelude::*};
 
 #[test]
 fn test_parse_event() {
@@ -35,6 +35,7 @@ fn test_parse_event() {
                         ]),
                     },
                 ],
+                anonymous: false,
                 span: AstSpan(vec![
                     // "#define"
                     Span { start: 0, end: 7, file: None },
@@ -87,6 +88,7 @@ fn test_parse_event() {
                         ]),
                     },
                 ],
+                anonymous: false,
                 span: AstSpan(vec![
                     // "#define"
                     Span { start: 0, end: 7, file: None },
@@ -135,6 +137,7 @@ fn test_parse_event() {
                         ]),
                     },
                 ],
+                anonymous: false,
                 span: AstSpan(vec![
                     // "#define"
                     Span { start: 0, end: 7, file: None },
@@ -174,3 +177,24 @@ fn test_parse_event() {
         assert_eq!(event, expected);
     }
 }
+
+#[test]
+fn test_anonymous_event_abi() {
+    let source = r#"
+        #define event Deposit(address indexed from, uint256 amount) anonymous
+        #define event Withdraw(address indexed to, uint256 amount)
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    assert!(contract.events[0].anonymous);
+    assert!(!contract.events[1].anonymous);
+
+    let abi = Abi::from(contract);
+    assert!(abi.events.get("Deposit").unwrap().anonymous);
+    assert!(!abi.events.get("Withdraw").unwrap().anonymous);
+    assert_eq!(abi.to_solc_json()[0]["anonymous"], true);
+}
@@ -211,3 +211,45 @@ fn cannot_parse_invalid_function_definition() {
     let mut parser = Parser::new(tokens, None);
     parser.parse().unwrap();
 }
+
+#[test]
+fn unsized_integers_default_to_256_bits() {
+    let source = r#"
+        #define function transfer(address,uint) nonpayable returns ()
+        #define function transfer(address,uint256) nonpayable returns ()
+        #define function mint(int[],uint[2]) nonpayable returns (int)
+    "#;
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    let contract = parser.parse().unwrap();
+
+    assert_eq!(contract.functions[0].signature, contract.functions[1].signature);
+    assert_eq!(contract.functions[0].signature, [0xa9, 0x05, 0x9c, 0xbb]);
+    assert_eq!(contract.functions[0].inputs[1].arg_type, Some("uint256".to_string()));
+
+    let mint = &contract.functions[2];
+    assert_eq!(mint.inputs[0].arg_type, Some("int256[]".to_string()));
+    assert_eq!(mint.inputs[1].arg_type, Some("uint256[2]".to_string()));
+    assert_eq!(mint.outputs[0].arg_type, Some("int256".to_string()));
+}
+
+#[test]
+fn rejects_fixed_point_types() {
+    let sources = [
+        ("#define function price(fixed128x18) view returns (uint256)", "fixed128x18"),
+        ("#define function price(uint256) view returns (ufixed)", "ufixed"),
+    ];
+
+    for (source, ty) in sources {
+        let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+        let lexer = Lexer::new(flattened_source);
+        let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+        let mut parser = Parser::new(tokens, None);
+        assert_eq!(
+            parser.parse().unwrap_err().kind,
+            ParserErrorKind::UnsupportedType(ty.to_string())
+        );
+    }
+}
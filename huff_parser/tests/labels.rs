@@ -100,6 +100,10 @@ fn multiline_labels() {
         ],
         takes: 3,
         returns: 0,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![
             // "#define"
             Span { start: 5, end: 12, file: None },
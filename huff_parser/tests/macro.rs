@@ -18,6 +18,10 @@ fn empty_macro() {
         statements: vec![],
         takes: 0,
         returns: 4,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![
             Span { start: 0, end: 7, file: None },
             Span { start: 8, end: 13, file: None },
@@ -79,6 +83,10 @@ fn macro_with_simple_body() {
         ],
         takes: 3,
         returns: 0,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![
             Span { start: 0, end: 7, file: None },
             Span { start: 8, end: 13, file: None },
@@ -154,7 +162,7 @@ fn macro_with_arg_calls() {
             },
             Statement {
                 ty: StatementType::Constant("BALANCE_LOCATION".to_string()),
-                span: AstSpan(vec![Span { start: 265, end: 281, file: None }]),
+                span: AstSpan(vec![Span { start: 264, end: 282, file: None }]),
             },
             Statement {
                 ty: StatementType::MacroInvocation(MacroInvocation {
@@ -212,7 +220,7 @@ fn macro_with_arg_calls() {
             },
             Statement {
                 ty: StatementType::Constant("BALANCE_LOCATION".to_string()),
-                span: AstSpan(vec![Span { start: 982, end: 998, file: None }]),
+                span: AstSpan(vec![Span { start: 981, end: 999, file: None }]),
             },
             Statement {
                 ty: StatementType::MacroInvocation(MacroInvocation {
@@ -235,6 +243,10 @@ fn macro_with_arg_calls() {
         ],
         takes: 3,
         returns: 3,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![
             Span { start: 34, end: 41, file: None },
             Span { start: 42, end: 47, file: None },
@@ -419,6 +431,10 @@ fn macro_labels() {
         ],
         takes: 0,
         returns: 0,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![
             Span { start: 5, end: 12, file: None },
             Span { start: 13, end: 18, file: None },
@@ -558,6 +574,10 @@ fn macro_invocation_with_arg_call() {
         ],
         takes: 0,
         returns: 0,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![
             Span { start: 5, end: 12, file: None },
             Span { start: 13, end: 18, file: None },
@@ -641,6 +661,10 @@ fn macro_with_builtin_fn_call() {
         }],
         takes: 0,
         returns: 0,
+        outlined: false,
+        facet: false,
+        source_file: None,
+        suppressed_warnings: vec![],
         span: AstSpan(vec![
             Span { start: 5, end: 12, file: None },
             Span { start: 13, end: 18, file: None },
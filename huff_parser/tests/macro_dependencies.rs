@@ -0,0 +1,74 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse().unwrap()
+}
+
+#[test]
+fn collects_transitive_macro_dependencies() {
+    let contract = parse(
+        r#"
+        #define macro LEAF() = takes(0) returns(0) {
+            0x01 pop
+        }
+
+        #define macro MIDDLE() = takes(0) returns(0) {
+            LEAF() LEAF()
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            MIDDLE()
+            LEAF()
+        }
+    "#,
+    );
+
+    assert_eq!(contract.macro_dependencies("MAIN").unwrap(), vec!["MIDDLE", "LEAF"]);
+    assert_eq!(contract.macro_dependencies("MIDDLE").unwrap(), vec!["LEAF"]);
+    assert!(contract.macro_dependencies("LEAF").unwrap().is_empty());
+}
+
+#[test]
+fn collects_invocations_inside_labels() {
+    let contract = parse(
+        r#"
+        #define macro HELPER() = takes(0) returns(0) {
+            0x01 pop
+        }
+
+        #define macro MAIN() = takes(0) returns(0) {
+            loop:
+                HELPER()
+                loop jump
+        }
+    "#,
+    );
+
+    assert_eq!(contract.macro_dependencies("MAIN").unwrap(), vec!["HELPER"]);
+}
+
+#[test]
+fn reports_missing_macros() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            MISSING()
+        }
+    "#,
+    );
+
+    assert_eq!(
+        contract.macro_dependencies("MAIN").unwrap_err().kind,
+        CodegenErrorKind::InvalidMacroInvocation("MISSING".to_string())
+    );
+    assert_eq!(
+        contract.macro_dependencies("NOPE").unwrap_err().kind,
+        CodegenErrorKind::MissingMacroDefinition("NOPE".to_string())
+    );
+}
@@ -0,0 +1,130 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+use std::{path::PathBuf, sync::Arc};
+
+fn parse(source: &str) -> Contract {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse().unwrap()
+}
+
+fn parse_file(source: &str, path: &str) -> Contract {
+    let file = Arc::new(FileSource {
+        path: path.to_string(),
+        source: Some(source.to_string()),
+        ..Default::default()
+    });
+    let flattened_source = FullFileSource {
+        source,
+        file: Some(Arc::clone(&file)),
+        spans: vec![(file, Span { start: 0, end: source.len() + 1, file: None })],
+    };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse().unwrap()
+}
+
+#[test]
+fn merges_disjoint_contracts() {
+    let first = parse(
+        r#"
+        #define function transfer(address,uint256) nonpayable returns ()
+        #define constant OWNER = FREE_STORAGE_POINTER()
+        #define macro TRANSFER() = takes(0) returns(0) {
+            [OWNER] sload
+        }
+    "#,
+    );
+    let second = parse(
+        r#"
+        #define event Transfer(address,address,uint256)
+        #define constant BALANCES = 0x01
+        #define macro MAIN() = takes(0) returns(0) {
+            TRANSFER()
+        }
+    "#,
+    );
+
+    let merged = Contract::merge(vec![first, second]).unwrap();
+    assert_eq!(
+        merged.macros.iter().map(|m| m.name.as_str()).collect::<Vec<_>>(),
+        vec!["TRANSFER", "MAIN"]
+    );
+    assert_eq!(
+        merged.constants.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+        vec!["OWNER", "BALANCES"]
+    );
+    assert_eq!(merged.functions.len(), 1);
+    assert_eq!(merged.events.len(), 1);
+}
+
+#[test]
+fn merge_rejects_duplicate_definitions() {
+    let first = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x01
+        }
+    "#,
+    );
+    let second = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            0x02
+        }
+    "#,
+    );
+
+    let err = Contract::merge(vec![first, second]).unwrap_err();
+    assert_eq!(err.kind, CodegenErrorKind::DuplicateDefinition("MAIN".to_string()));
+}
+
+#[test]
+fn merged_definitions_retain_their_source_file() {
+    let first = parse_file(
+        r#"
+        #define constant OWNER = 0x01
+        #define macro OWNER_OF() = takes(0) returns(1) {
+            [OWNER] sload
+        }
+    "#,
+        "./src/Owned.huff",
+    );
+    let second = parse_file(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            OWNER_OF()
+        }
+    "#,
+        "./src/Main.huff",
+    );
+
+    let merged = Contract::merge(vec![first, second]).unwrap();
+    assert_eq!(merged.macros[0].source_file, Some(PathBuf::from("./src/Owned.huff")));
+    assert_eq!(merged.macros[1].source_file, Some(PathBuf::from("./src/Main.huff")));
+    assert_eq!(merged.constants[0].source_file, Some(PathBuf::from("./src/Owned.huff")));
+}
+
+#[test]
+fn merge_sizes_code_tables_inlining_other_contracts_constants() {
+    let first = parse(
+        r#"
+        #define table CODE {
+            [MAGIC]
+        }
+    "#,
+    );
+    let second = parse(
+        r#"
+        #define constant MAGIC = 0xdeadbeef
+    "#,
+    );
+    assert_eq!(first.tables[0].size, str_to_bytes32("00"));
+
+    let merged = Contract::merge(vec![first, second]).unwrap();
+    assert_eq!(merged.tables[0].size, str_to_bytes32("04"));
+}
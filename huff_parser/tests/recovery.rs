@@ -0,0 +1,43 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Result<Contract, Vec<ParserError>> {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse_with_recovery()
+}
+
+#[test]
+fn reports_every_malformed_definition() {
+    let source = r#"
+        #define constant OWNER = FREE_STORAGE_POINTER()
+        #define constant BROKEN = takes
+        #define macro MAIN() = takes(0) returns(0) {
+            0x00 sload
+        }
+        #define bogus
+        #define function transfer(address,uint256) nonpayable returns ()
+    "#;
+
+    let errors = parse(source).unwrap_err();
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(errors[0].kind, ParserErrorKind::InvalidConstantValue(_)));
+    assert_eq!(errors[1].kind, ParserErrorKind::InvalidDefinition);
+}
+
+#[test]
+fn parses_valid_sources_like_parse() {
+    let source = r#"
+        #define constant OWNER = FREE_STORAGE_POINTER()
+        #define macro MAIN() = takes(0) returns(0) {
+            [OWNER] sload
+        }
+    "#;
+
+    let contract = parse(source).unwrap();
+    assert_eq!(contract.constants.len(), 1);
+    assert_eq!(contract.macros.len(), 1);
+}
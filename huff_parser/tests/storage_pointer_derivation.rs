@@ -21,6 +21,7 @@ fn derives_storage_pointers() {
         ConstantDefinition {
             name: "FSP_LOCATION".to_string(),
             value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            source_file: None,
             span: AstSpan(vec![
                 Span { start: 0, end: 7, file: None },
                 Span { start: 8, end: 16, file: None },
@@ -37,6 +38,7 @@ fn derives_storage_pointers() {
         ConstantDefinition {
             name: "FSP_LOCATION_2".to_string(),
             value: ConstVal::FreeStoragePointer(FreeStoragePointer {}),
+            source_file: None,
             span: AstSpan(vec![
                 Span { start: 55, end: 62, file: None },
                 Span { start: 63, end: 71, file: None },
@@ -53,6 +55,7 @@ fn derives_storage_pointers() {
         ConstantDefinition {
             name: "NUM".to_string(),
             value: ConstVal::Literal(str_to_bytes32("a57B")),
+            source_file: None,
             span: AstSpan(vec![
                 Span { start: 112, end: 119, file: None },
                 Span { start: 120, end: 128, file: None },
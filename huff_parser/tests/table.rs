@@ -43,7 +43,7 @@ fn table_with_no_body() {
 #[test]
 fn table_with_body() {
     // TODO: Code tables are not yet supported
-    let table_kinds = [(TokenKind::JumpTable, "96"), (TokenKind::JumpTablePacked, "06")];
+    let table_kinds = [(TokenKind::JumpTable, "60"), (TokenKind::JumpTablePacked, "06")];
 
     for (kind, expected_size) in table_kinds {
         let source = &format!(
@@ -112,3 +112,42 @@ fn table_with_body() {
         assert_eq!(parser.current_token.kind, TokenKind::Eof);
     }
 }
+
+#[test]
+fn jump_table_captures_inline_labels() {
+    let source = "#define jumptable JUMP_TABLE { lab_0 lab_1 lab_2 }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+
+    let mut parser = Parser::new(tokens, None);
+    let table_definition = parser.parse().unwrap().tables[0].clone();
+
+    assert_eq!(table_definition.name, "JUMP_TABLE");
+    assert_eq!(table_definition.kind, TableKind::JumpTable);
+    assert_eq!(
+        table_definition.statements.iter().map(|s| s.ty.clone()).collect::<Vec<StatementType>>(),
+        vec![
+            StatementType::LabelCall("lab_0".to_string()),
+            StatementType::LabelCall("lab_1".to_string()),
+            StatementType::LabelCall("lab_2".to_string()),
+        ]
+    );
+    assert_eq!(parser.current_token.kind, TokenKind::Eof);
+}
+
+#[test]
+fn table_with_constant_references() {
+    let source = "#define constant C = 0x0102\n#define table CODE_TABLE { [C] [C] }";
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+
+    let mut parser = Parser::new(tokens, None);
+    let table = parser.parse().unwrap().tables[0].clone();
+
+    assert!(table.statements.iter().all(|s| s.ty == StatementType::Constant("C".to_string())));
+    assert_eq!(table.statements.len(), 2);
+    // Constants are sized at their literal width
+    assert_eq!(table.size, str_to_bytes32("04"));
+}
@@ -0,0 +1,69 @@
+use huff_lexer::*;
+use huff_parser::*;
+use huff_utils::prelude::*;
+
+fn parse(source: &str) -> Result<Contract, ParserError> {
+    let flattened_source = FullFileSource { source, file: None, spans: vec![] };
+    let lexer = Lexer::new(flattened_source);
+    let tokens = lexer.into_iter().map(|x| x.unwrap()).collect::<Vec<Token>>();
+    let mut parser = Parser::new(tokens, None);
+    parser.parse()
+}
+
+#[test]
+fn parses_trailing_comma_in_macro_parameters() {
+    let contract = parse(
+        r#"
+        #define macro FOO(a, b,) = takes(0) returns(0) {
+            <a> <b> add
+        }
+    "#,
+    )
+    .unwrap();
+
+    let params = &contract.find_macro_by_name("FOO").unwrap().parameters;
+    let names = params.iter().map(|p| p.name.clone().unwrap()).collect::<Vec<_>>();
+    assert_eq!(names, ["a", "b"]);
+}
+
+#[test]
+fn parses_trailing_comma_in_macro_invocation() {
+    let contract = parse(
+        r#"
+        #define macro MAIN() = takes(0) returns(0) {
+            FOO(0x01, 0x02,)
+        }
+    "#,
+    )
+    .unwrap();
+
+    let main = contract.find_macro_by_name("MAIN").unwrap();
+    match &main.statements[0].ty {
+        StatementType::MacroInvocation(mi) => assert_eq!(
+            mi.args,
+            [MacroArg::Literal(str_to_bytes32("01")), MacroArg::Literal(str_to_bytes32("02"))]
+        ),
+        ty => panic!("unexpected statement {:?}", ty),
+    }
+}
+
+#[test]
+fn parses_trailing_comma_in_abi_definitions() {
+    let contract = parse(
+        r#"
+        #define function transfer(address to, uint256 amount,) nonpayable returns (bool,)
+        #define event Transfer(address indexed from, address indexed to, uint256 value,)
+    "#,
+    )
+    .unwrap();
+
+    assert_eq!(contract.functions[0].inputs.len(), 2);
+    assert_eq!(contract.functions[0].outputs.len(), 1);
+    assert_eq!(contract.events[0].parameters.len(), 3);
+}
+
+#[test]
+fn rejects_a_lone_comma() {
+    assert!(parse("#define macro MAIN() = takes(0) returns(0) { FOO(,) }").is_err());
+    assert!(parse("#define macro FOO(a,,) = takes(0) returns(0) {}").is_err());
+}
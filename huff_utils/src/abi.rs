@@ -28,6 +28,8 @@
 //!     }],
 //!     events: vec![],
 //!     tables: vec![],
+//!     memory_regions: vec![],
+//!     return_size: None,
 //! };
 //!
 //! // Create an ABI using that generate contract
@@ -36,6 +38,7 @@
 //! ```
 
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::collections::BTreeMap;
 
 use crate::ast::{self, FunctionType};
@@ -47,8 +50,8 @@ use crate::ast::{self, FunctionType};
 pub struct Abi {
     /// The constructor
     pub constructor: Option<Constructor>,
-    /// A list of functions and their definitions
-    pub functions: BTreeMap<String, Function>,
+    /// A list of functions and their definitions, grouped by name to keep overloads
+    pub functions: BTreeMap<String, Vec<Function>>,
     /// A list of events and their definitions
     pub events: BTreeMap<String, Event>,
     /// If the contract defines receive logic
@@ -62,6 +65,67 @@ impl Abi {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Renders the ABI in the JSON shape emitted by solc.
+    ///
+    /// Entries are sorted by type and then name, and each entry's keys are in solc's
+    /// alphabetical order, so the output can be compared byte for byte with `solc --abi`.
+    pub fn to_solc_json(&self) -> Value {
+        let mut entries: Vec<(&str, &str, Value)> = vec![];
+        if let Some(constructor) = &self.constructor {
+            entries.push((
+                "constructor",
+                "",
+                json!({
+                    "inputs": constructor.inputs.iter().map(FunctionParam::to_solc_json).collect::<Vec<_>>(),
+                    "stateMutability": "nonpayable",
+                    "type": "constructor",
+                }),
+            ));
+        }
+        for event in self.events.values() {
+            entries.push((
+                "event",
+                &event.name,
+                json!({
+                    "anonymous": event.anonymous,
+                    "inputs": event.inputs.iter().map(EventParam::to_solc_json).collect::<Vec<_>>(),
+                    "name": event.name,
+                    "type": "event",
+                }),
+            ));
+        }
+        if self.fallback {
+            entries.push((
+                "fallback",
+                "",
+                json!({ "stateMutability": "payable", "type": "fallback" }),
+            ));
+        }
+        for function in self.functions.values().flatten() {
+            entries.push((
+                "function",
+                &function.name,
+                json!({
+                    "inputs": function.inputs.iter().map(FunctionParam::to_solc_json).collect::<Vec<_>>(),
+                    "name": function.name,
+                    "outputs": function.outputs.iter().map(FunctionParam::to_solc_json).collect::<Vec<_>>(),
+                    "stateMutability": function.state_mutability.to_solc_string(),
+                    "type": "function",
+                }),
+            ));
+        }
+        if self.receive {
+            entries.push((
+                "receive",
+                "",
+                json!({ "stateMutability": "payable", "type": "receive" }),
+            ));
+        }
+
+        entries.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        Value::Array(entries.into_iter().map(|(_, _, entry)| entry).collect())
+    }
 }
 
 // Allows for simple ABI Generation by directly translating the AST
@@ -82,7 +146,7 @@ impl From<ast::Contract> for Abi {
         // Translate contract functions
         // Excluding constructor
         contract
-            .functions
+            .functions()
             .iter()
             .filter(|function: &&ast::Function| function.name != "CONSTRUCTOR")
             .map(|function| {
@@ -114,12 +178,12 @@ impl From<ast::Contract> for Abi {
                 )
             })
             .for_each(|val| {
-                let _ = functions.insert(val.0, val.1);
+                functions.entry(val.0).or_insert_with(Vec::new).push(val.1);
             });
 
         // Translate contract events
         contract
-            .events
+            .events()
             .iter()
             .map(|event| {
                 (
@@ -132,10 +196,10 @@ impl From<ast::Contract> for Abi {
                             .map(|argument| EventParam {
                                 name: argument.name.clone().unwrap_or_default(),
                                 kind: argument.arg_type.clone().unwrap_or_default().into(),
-                                indexed: false, // TODO: This is not present in `argument`
+                                indexed: argument.indexed,
                             })
                             .collect(),
-                        anonymous: false,
+                        anonymous: event.anonymous,
                     },
                 )
             })
@@ -228,6 +292,47 @@ pub struct FunctionParam {
     pub internal_type: Option<String>,
 }
 
+impl FunctionParam {
+    /// Renders the parameter in the JSON shape emitted by solc
+    pub fn to_solc_json(&self) -> Value {
+        solc_param_json(&self.name, &self.kind, self.internal_type.as_deref(), None)
+    }
+}
+
+impl EventParam {
+    /// Renders the parameter in the JSON shape emitted by solc
+    pub fn to_solc_json(&self) -> Value {
+        solc_param_json(&self.name, &self.kind, None, Some(self.indexed))
+    }
+}
+
+/// Builds a solc ABI parameter, keeping solc's alphabetical key order
+fn solc_param_json(
+    name: &str,
+    kind: &FunctionParamType,
+    internal_type: Option<&str>,
+    indexed: Option<bool>,
+) -> Value {
+    let mut param = serde_json::Map::new();
+    if let Some(components) = kind.tuple_components() {
+        param.insert(
+            "components".to_string(),
+            Value::Array(components.iter().map(|c| solc_param_json("", c, None, None)).collect()),
+        );
+    }
+    if let Some(indexed) = indexed {
+        param.insert("indexed".to_string(), Value::Bool(indexed));
+    }
+    let solc_type = kind.to_solc_string();
+    param.insert(
+        "internalType".to_string(),
+        Value::String(internal_type.map(str::to_string).unwrap_or_else(|| solc_type.clone())),
+    );
+    param.insert("name".to_string(), Value::String(name.to_string()));
+    param.insert("type".to_string(), Value::String(solc_type));
+    Value::Object(param)
+}
+
 /// #### FunctionParamType
 ///
 /// The type of a function parameter
@@ -254,6 +359,35 @@ pub enum FunctionParamType {
 }
 
 impl FunctionParamType {
+    /// The canonical type name used in ABI JSON and selectors, e.g. `uint256[2][]`
+    pub fn to_solc_string(&self) -> String {
+        match self {
+            Self::Address => "address".to_string(),
+            Self::Bytes => "bytes".to_string(),
+            Self::Int(size) => format!("int{}", size),
+            Self::Uint(size) => format!("uint{}", size),
+            Self::Bool => "bool".to_string(),
+            Self::String => "string".to_string(),
+            Self::Array(inner, sizes) => {
+                sizes.iter().fold(inner.to_solc_string(), |acc, size| match size {
+                    0 => format!("{}[]", acc),
+                    n => format!("{}[{}]", acc, n),
+                })
+            }
+            Self::FixedBytes(size) => format!("bytes{}", size),
+            Self::Tuple(_) => "tuple".to_string(),
+        }
+    }
+
+    /// The members of a tuple, or of the tuples in an array of tuples
+    fn tuple_components(&self) -> Option<&Vec<FunctionParamType>> {
+        match self {
+            Self::Tuple(components) => Some(components),
+            Self::Array(inner, _) => inner.tuple_components(),
+            _ => None,
+        }
+    }
+
     /// Convert string to type
     pub fn convert_string_to_type(string: &str) -> Result<Self, Box<dyn std::error::Error>> {
         let input = string.to_string().to_lowercase();
@@ -2,11 +2,15 @@
 //!
 //! The artifacts generated from codegen.
 
+use ethers_core::{
+    types::Address,
+    utils::{hex, keccak256},
+};
 use serde::{Deserialize, Serialize};
-use std::{fs, path::Path, sync::Arc};
+use std::{collections::BTreeMap, fs, path::Path, sync::Arc};
 
 pub use crate::abi::Abi;
-use crate::prelude::FileSource;
+use crate::prelude::{str_to_vec, AstSpan, BytecodeRes, Bytes, FileSource, Jump};
 
 /// A Codegen Artifact
 #[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
@@ -19,6 +23,15 @@ pub struct Artifact {
     pub runtime: String,
     /// The abi
     pub abi: Option<Abi>,
+    /// The version of the compiler that produced the artifact
+    #[serde(default)]
+    pub compiler_version: String,
+    /// The keccak256 hash of the flattened source the artifact was compiled from
+    #[serde(default)]
+    pub source_hash: String,
+    /// The runtime offset of each label, for debuggers to name jump destinations
+    #[serde(default)]
+    pub symbols: BTreeMap<String, usize>,
 }
 
 impl Artifact {
@@ -32,4 +45,151 @@ impl Artifact {
         }
         fs::write(file_path, serialized_artifact)
     }
+
+    /// Estimates the gas used by a transaction deploying the artifact.
+    ///
+    /// Sums the intrinsic cost of a contract creation transaction (21000 + 32000), the calldata
+    /// cost of the creation bytecode (4 gas per zero byte, 16 per non-zero byte), the EIP-3860
+    /// initcode cost (2 gas per 32 byte word) and the code deposit cost (200 gas per byte of
+    /// runtime). Execution of the constructor body itself isn't included.
+    pub fn deploy_gas_estimate(&self) -> u64 {
+        let decode = |code: &str| str_to_vec(code.strip_prefix("0x").unwrap_or(code));
+        let initcode = decode(&self.bytecode).unwrap_or_default();
+        let runtime_len = decode(&self.runtime).map(|r| r.len()).unwrap_or_default() as u64;
+
+        let calldata: u64 = initcode.iter().map(|b| if *b == 0 { 4 } else { 16 }).sum();
+        let initcode_words = (initcode.len() as u64).div_ceil(32);
+
+        21000 + 32000 + calldata + 2 * initcode_words + 200 * runtime_len
+    }
+
+    /// The keccak256 hash of the runtime bytecode, the `EXTCODEHASH` of the deployed contract.
+    ///
+    /// Returns `None` if the runtime isn't valid hex, as when libraries are still unlinked.
+    /// Immutables are patched in at deployment, so their deployed codehash differs from this one.
+    pub fn runtime_codehash(&self) -> Option<[u8; 32]> {
+        let runtime = self.runtime.strip_prefix("0x").unwrap_or(&self.runtime);
+        str_to_vec(runtime).ok().map(keccak256)
+    }
+
+    /// Patches a library's address over its `__linked` placeholders in the bytecode and runtime
+    pub fn link(&mut self, name: &str, address: Address) {
+        let placeholder = link_placeholder(name);
+        let address = hex::encode(address);
+        self.bytecode = self.bytecode.replace(&placeholder, &address);
+        self.runtime = self.runtime.replace(&placeholder, &address);
+    }
+
+    /// Returns the distinct library placeholders not yet [linked](Artifact::link), in the order
+    /// they appear in the bytecode
+    pub fn unlinked_references(&self) -> Vec<String> {
+        let mut references: Vec<String> = vec![];
+        let mut rest = self.bytecode.as_str();
+        while let Some(start) = rest.find("__$") {
+            let placeholder = rest[start..].get(..LINK_PLACEHOLDER_LEN).unwrap_or(&rest[start..]);
+            if !references.iter().any(|r| r == placeholder) {
+                references.push(placeholder.to_string());
+            }
+            rest = &rest[start + placeholder.len()..];
+        }
+        references
+    }
+}
+
+/// An intermediate representation artifact, holding the code of MAIN and CONSTRUCTOR after macro
+/// expansion but before tables are laid out, for external optimizers to consume and re-emit.
+///
+/// Jump targets within the code are already resolved, so a consumer that moves code must update
+/// them along with the `labels` tables point at.
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct IrArtifact {
+    /// The expanded MAIN macro
+    pub main: IrEntry,
+    /// The expanded CONSTRUCTOR macro, if the contract has one
+    pub constructor: Option<IrEntry>,
+}
+
+/// The expanded code of an entry point macro
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct IrEntry {
+    /// The emitted code, as hex chunks in order with their byte offsets
+    pub code: Vec<IrChunk>,
+    /// The byte offsets of the labels, which table entries resolve to
+    pub labels: BTreeMap<String, usize>,
+    /// The `__tablestart` PUSH2s waiting for their table's offset
+    pub table_starts: Vec<IrTableStart>,
+}
+
+/// A chunk of emitted code
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct IrChunk {
+    /// The byte offset of the chunk
+    pub offset: usize,
+    /// The hex encoded bytes
+    pub bytes: String,
+}
+
+/// A `__tablestart` PUSH2 to fill with its table's offset once tables are laid out
+#[derive(Default, Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct IrTableStart {
+    /// The table name
+    pub table: String,
+    /// The byte offset of the PUSH2
+    pub offset: usize,
+}
+
+impl From<BytecodeRes> for IrEntry {
+    fn from(res: BytecodeRes) -> Self {
+        IrEntry {
+            code: res.bytes.into_iter().map(|(offset, b)| IrChunk { offset, bytes: b.0 }).collect(),
+            labels: res.label_indices,
+            table_starts: res
+                .table_instances
+                .into_iter()
+                .map(|j| IrTableStart { table: j.label, offset: j.bytecode_index })
+                .collect(),
+        }
+    }
+}
+
+impl From<IrEntry> for BytecodeRes {
+    fn from(entry: IrEntry) -> Self {
+        BytecodeRes {
+            bytes: entry.code.into_iter().map(|c| (c.offset, Bytes(c.bytes))).collect(),
+            label_indices: entry.labels,
+            table_instances: entry
+                .table_starts
+                .into_iter()
+                .map(|t| Jump {
+                    label: t.table,
+                    bytecode_index: t.offset,
+                    span: AstSpan(vec![]),
+                    scope_depth: 0,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The length in hex characters of a library placeholder, the width of an address
+const LINK_PLACEHOLDER_LEN: usize = 40;
+
+/// Returns the placeholder a `__linked("<name>")` library address is emitted as until it is
+/// linked.
+///
+/// Like solc, the placeholder is `__$<hash>$__`, where the hash is the first 34 hex characters
+/// of `keccak256(name)`. It isn't valid hex, so unlinked bytecode can't be deployed by accident.
+pub fn link_placeholder(name: &str) -> String {
+    format!("__${}$__", &hex::encode(keccak256(name))[..LINK_PLACEHOLDER_LEN - 6])
+}
+
+/// Replaces library placeholders with zero addresses, so unlinked bytecode can be decoded
+pub fn mask_link_placeholders(bytecode: &str) -> String {
+    let mut masked = bytecode.to_string();
+    while let Some(start) = masked.find("__$") {
+        let end = (start + LINK_PLACEHOLDER_LEN).min(masked.len());
+        masked.replace_range(start..end, &"0".repeat(end - start));
+    }
+    masked
 }
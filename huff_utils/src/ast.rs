@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 use crate::{
     bytecode::*,
     bytes_util::*,
-    error::CodegenError,
+    error::{CodegenError, CodegenErrorKind},
     evm::Opcode,
     prelude::{Span, TokenKind},
 };
@@ -63,6 +63,11 @@ impl AstSpan {
         })
     }
 
+    /// Returns the path of the first file these spans belong to
+    pub fn source_file(&self) -> Option<PathBuf> {
+        self.0.iter().find_map(|s| s.file.as_ref().map(|f| PathBuf::from(&f.path)))
+    }
+
     /// Print just the file for missing
     pub fn file(&self) -> String {
         self.0.iter().fold("".to_string(), |acc, span| match &span.file {
@@ -94,6 +99,12 @@ pub struct Contract {
     pub events: Vec<Event>,
     /// Tables
     pub tables: Vec<TableDefinition>,
+    /// Memory regions declared with `// huff-memory: <start>..<end>` directives, as half open
+    /// `(start, end)` byte ranges
+    pub memory_regions: Vec<(usize, usize)>,
+    /// The number of bytes MAIN is expected to return, declared with a
+    /// `// huff-returns: <bytes>` directive
+    pub return_size: Option<usize>,
 }
 
 impl Contract {
@@ -107,6 +118,41 @@ impl Contract {
         }
     }
 
+    /// Returns the first function that matches the provided name
+    pub fn find_function_by_name(&self, name: &str) -> Option<Function> {
+        if let Some(f) = self.functions.iter().find(|f| f.name == name) {
+            Some(f.clone())
+        } else {
+            tracing::warn!("Failed to find function \"{}\" in contract", name);
+            None
+        }
+    }
+
+    /// Returns the first event that matches the provided name
+    pub fn find_event_by_name(&self, name: &str) -> Option<Event> {
+        if let Some(e) = self.events.iter().find(|e| e.name == name) {
+            Some(e.clone())
+        } else {
+            tracing::warn!("Failed to find event \"{}\" in contract", name);
+            None
+        }
+    }
+
+    /// The function declarations
+    pub fn functions(&self) -> &[Function] {
+        &self.functions
+    }
+
+    /// The event declarations
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// The constant definitions
+    pub fn constants(&self) -> &[ConstantDefinition] {
+        &self.constants
+    }
+
     /// Returns the first table that matches the provided name
     pub fn find_table_by_name(&self, name: &str) -> Option<TableDefinition> {
         if let Some(t) = self.tables.iter().find(|t| t.name == name) {
@@ -117,8 +163,206 @@ impl Contract {
         }
     }
 
+    /// Merges multiple contracts into a single contract.
+    ///
+    /// Definitions are concatenated in order and code tables resized, as they may inline
+    /// constants from another contract. Errors if two contracts define a macro, constant, table
+    /// or event with the same name, or a function with the same signature.
+    pub fn merge(others: Vec<Contract>) -> Result<Contract, CodegenError> {
+        let duplicate = |name: &str, span: &AstSpan| CodegenError {
+            kind: CodegenErrorKind::DuplicateDefinition(name.to_string()),
+            span: span.clone(),
+            token: None,
+        };
+
+        let mut merged = Contract::default();
+        for contract in others {
+            for m in contract.macros {
+                if merged.macros.iter().any(|d| d.name == m.name) {
+                    return Err(duplicate(&m.name, &m.span))
+                }
+                merged.macros.push(m);
+            }
+            for c in contract.constants {
+                if merged.constants.iter().any(|d| d.name == c.name) {
+                    return Err(duplicate(&c.name, &c.span))
+                }
+                merged.constants.push(c);
+            }
+            for f in contract.functions {
+                if merged.functions.iter().any(|d| d.signature == f.signature) {
+                    return Err(duplicate(&f.name, &f.span))
+                }
+                merged.functions.push(f);
+            }
+            for e in contract.events {
+                if merged.events.iter().any(|d| d.name == e.name) {
+                    return Err(duplicate(&e.name, &e.span))
+                }
+                merged.events.push(e);
+            }
+            for t in contract.tables {
+                if merged.tables.iter().any(|d| d.name == t.name) {
+                    return Err(duplicate(&t.name, &t.span))
+                }
+                merged.tables.push(t);
+            }
+            merged.invocations.extend(contract.invocations);
+            for r in contract.memory_regions {
+                if !merged.memory_regions.contains(&r) {
+                    merged.memory_regions.push(r);
+                }
+            }
+            merged.return_size = merged.return_size.or(contract.return_size);
+            for i in contract.imports {
+                if !merged.imports.contains(&i) {
+                    merged.imports.push(i);
+                }
+            }
+        }
+        merged.size_code_tables();
+        Ok(merged)
+    }
+
+    /// Returns the names of all macros transitively invoked by the named macro.
+    ///
+    /// Names are returned in the order they are first invoked, without duplicates.
+    pub fn macro_dependencies(&self, name: &str) -> Result<Vec<String>, CodegenError> {
+        let macro_def = self.find_macro_by_name(name).ok_or_else(|| CodegenError {
+            kind: CodegenErrorKind::MissingMacroDefinition(name.to_string()),
+            span: AstSpan(vec![Span { start: 0, end: 0, file: None }]),
+            token: None,
+        })?;
+        let mut dependencies = vec![];
+        self.recurse_macro_dependencies(&macro_def, &mut dependencies)?;
+        Ok(dependencies)
+    }
+
+    /// Recurse down a Macro Definition collecting the names of invoked macros
+    fn recurse_macro_dependencies(
+        &self,
+        macro_def: &MacroDefinition,
+        dependencies: &mut Vec<String>,
+    ) -> Result<(), CodegenError> {
+        for statement in flatten_statements(&macro_def.statements) {
+            if let StatementType::MacroInvocation(mi) = &statement.ty {
+                if dependencies.contains(&mi.macro_name) {
+                    continue
+                }
+                let invoked =
+                    self.macros.iter().find(|md| md.name.eq(&mi.macro_name)).ok_or_else(|| {
+                        CodegenError {
+                            kind: CodegenErrorKind::InvalidMacroInvocation(mi.macro_name.clone()),
+                            span: mi.span.clone(),
+                            token: None,
+                        }
+                    })?;
+                dependencies.push(mi.macro_name.clone());
+                self.recurse_macro_dependencies(invoked, dependencies)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the macro invocation graph as a Graphviz DOT digraph.
+    ///
+    /// Macros are drawn as ellipses with solid invocation edges, constants as boxes with dashed
+    /// usage edges.
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec!["digraph Contract {".to_string()];
+        for m in &self.macros {
+            lines.push(format!("    \"{}\";", m.name));
+        }
+        for c in &self.constants {
+            lines.push(format!("    \"{}\" [shape=box];", c.name));
+        }
+        for m in &self.macros {
+            let edges = flatten_statements(&m.statements)
+                .into_iter()
+                .filter_map(|s| match &s.ty {
+                    StatementType::MacroInvocation(mi) => {
+                        Some(format!("    \"{}\" -> \"{}\";", m.name, mi.macro_name))
+                    }
+                    StatementType::Constant(c) => {
+                        Some(format!("    \"{}\" -> \"{}\" [style=dashed];", m.name, c))
+                    }
+                    _ => None,
+                })
+                .unique();
+            lines.extend(edges);
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
+    }
+
+    /// Sizes each code table from its code and the constants it inlines at their literal
+    /// width.
+    ///
+    /// Rerun whenever constant values change, e.g. once storage pointers are derived or constants
+    /// injected, since a table's size is pushed by `__tablesize`.
+    pub fn size_code_tables(&mut self) {
+        for table in self.tables.iter_mut().filter(|t| t.kind == TableKind::CodeTable) {
+            let code_size = table
+                .statements
+                .iter()
+                .map(|s| match &s.ty {
+                    StatementType::LabelCall(code) => code.len(),
+                    _ => 0,
+                })
+                .sum::<usize>() /
+                2;
+            let constants_size = table
+                .statements
+                .iter()
+                .filter_map(|s| match &s.ty {
+                    StatementType::Constant(name) => {
+                        self.constants.iter().find(|c| c.name.eq(name))
+                    }
+                    _ => None,
+                })
+                .map(|c| match &c.value {
+                    ConstVal::Literal(l) => bytes32_to_string(l, false).len() / 2,
+                    ConstVal::Array(elements) => {
+                        elements.iter().map(|l| bytes32_to_string(l, false).len() / 2).sum()
+                    }
+                    // Pointers are sized once derived, immutables have no value to inline
+                    ConstVal::FreeStoragePointer(_) | ConstVal::Immutable => 0,
+                })
+                .sum::<usize>();
+            table.size = str_to_bytes32(format!("{:x}", code_size + constants_size).as_str());
+        }
+    }
+
     /// Derives the FreeStoragePointers into their bytes32 representation
     pub fn derive_storage_pointers(&mut self) {
+        let storage_pointers = self.storage_pointers();
+
+        // Set all the constants to their new values
+        for c in &mut self.constants {
+            match storage_pointers
+                .iter()
+                .filter(|pointer| pointer.0.eq(&c.name))
+                .collect::<Vec<&(String, [u8; 32])>>()
+                .get(0)
+            {
+                Some(p) => {
+                    *c = ConstantDefinition {
+                        name: c.name.to_string(),
+                        value: ConstVal::Literal(p.1),
+                        source_file: c.source_file.clone(),
+                        span: c.span.clone(),
+                    };
+                }
+                None => {
+                    tracing::warn!(target: "ast", "SET STORAGE POINTER BUT FAILED TO SET DERIVED CONSTANT VALUE FOR \"{}\"", c.name)
+                }
+            }
+        }
+    }
+
+    /// Returns the values of the constants referenced from CONSTRUCTOR and MAIN, assigning
+    /// FreeStoragePointers consecutive slots from 0 in the order they are first referenced
+    pub fn storage_pointers(&self) -> Vec<(String, [u8; 32])> {
         let mut storage_pointers: Vec<(String, [u8; 32])> = Vec::new();
         let mut last_assigned_free_pointer = 0;
 
@@ -148,27 +392,7 @@ impl Contract {
 
         tracing::debug!(target: "ast", "Generate Storage pointers: {:?}", storage_pointers);
         tracing::debug!(target: "ast", "ALL AST CONSTANTS: {:?}", storage_pointers);
-
-        // Set all the constants to their new values
-        for c in &mut self.constants {
-            match storage_pointers
-                .iter()
-                .filter(|pointer| pointer.0.eq(&c.name))
-                .collect::<Vec<&(String, [u8; 32])>>()
-                .get(0)
-            {
-                Some(p) => {
-                    *c = ConstantDefinition {
-                        name: c.name.to_string(),
-                        value: ConstVal::Literal(p.1),
-                        span: c.span.clone(),
-                    };
-                }
-                None => {
-                    tracing::warn!(target: "ast", "SET STORAGE POINTER BUT FAILED TO SET DERIVED CONSTANT VALUE FOR \"{}\"", c.name)
-                }
-            }
-        }
+        storage_pointers
     }
 
     /// Recurse down an AST Macro Definition to set Storage Pointers
@@ -215,14 +439,18 @@ impl Contract {
                         {
                             Some(c) => {
                                 let new_value = match c.value {
-                                    ConstVal::Literal(l) => l,
+                                    ConstVal::Literal(l) => Some(l),
                                     ConstVal::FreeStoragePointer(_) => {
                                         let old_p = *last_p;
                                         *last_p += 1;
-                                        str_to_bytes32(&format!("{}", old_p))
+                                        Some(str_to_bytes32(&format!("{}", old_p)))
                                     }
+                                    // Immutables are only known once deployed
+                                    ConstVal::Immutable | ConstVal::Array(_) => None,
                                 };
-                                storage_pointers.push((const_name.to_string(), new_value));
+                                if let Some(new_value) = new_value {
+                                    storage_pointers.push((const_name.to_string(), new_value));
+                                }
                             }
                             None => {
                                 tracing::warn!(target: "ast", "CONSTANT \"{}\" NOT FOUND IN AST CONSTANTS", const_name)
@@ -247,8 +475,21 @@ impl Contract {
                 }
                 StatementType::BuiltinFunctionCall(bfc) => {
                     tracing::debug!(target: "ast", "Deriving Storage Pointrs: Found builtin function {:?}", bfc.kind);
+                    let mut inserted = 0;
                     for a in &bfc.args {
                         if let Some(name) = &a.name {
+                            // Constant arguments, e.g. a `__mapping_slot` base, are references
+                            if self.constants.iter().any(|c| c.name == *name) {
+                                inserted += 1;
+                                statements.insert(
+                                    i + inserted,
+                                    Statement {
+                                        ty: StatementType::Constant(name.clone()),
+                                        span: a.span.clone(),
+                                    },
+                                );
+                                continue
+                            }
                             match self
                                 .macros
                                 .iter()
@@ -327,6 +568,18 @@ pub enum FunctionType {
     Pure,
 }
 
+impl FunctionType {
+    /// The state mutability as written in solc ABI JSON
+    pub fn to_solc_string(&self) -> &'static str {
+        match self {
+            FunctionType::View => "view",
+            FunctionType::Payable => "payable",
+            FunctionType::NonPayable => "nonpayable",
+            FunctionType::Pure => "pure",
+        }
+    }
+}
+
 /// An Event Signature
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Event {
@@ -334,6 +587,8 @@ pub struct Event {
     pub name: String,
     /// The parameters of the event
     pub parameters: Vec<Argument>,
+    /// Whether the event is emitted without its signature as the first topic
+    pub anonymous: bool,
     /// The event span
     pub span: AstSpan,
 }
@@ -347,7 +602,7 @@ pub struct TableDefinition {
     pub kind: TableKind,
     /// The table's statements
     pub statements: Vec<Statement>,
-    /// Size of table
+    /// Size of the table in bytes
     pub size: Literal,
     /// The table span
     pub span: AstSpan,
@@ -402,13 +657,22 @@ pub struct MacroDefinition {
     pub takes: usize,
     /// The return size
     pub returns: usize,
+    /// Whether the macro is emitted once and jumped to, rather than inlined at each invocation
+    pub outlined: bool,
+    /// Whether the macro is the entry point of a standalone facet runtime, see
+    /// `Codegen::generate_facet_artifacts`
+    pub facet: bool,
+    /// The file the Macro was defined in
+    pub source_file: Option<PathBuf>,
+    /// Warnings disabled for this macro with `// huff-disable: <warning>` directives
+    pub suppressed_warnings: Vec<String>,
     /// The Span of the Macro Definition
     pub span: AstSpan,
 }
 
 impl ToIRBytecode<CodegenError> for MacroDefinition {
     fn to_irbytecode(&self) -> Result<IRBytecode, CodegenError> {
-        let inner_irbytes: Vec<IRBytes> = MacroDefinition::to_irbytes(&self.statements);
+        let inner_irbytes: Vec<IRBytes> = MacroDefinition::to_irbytes(&self.statements)?;
         Ok(IRBytecode(inner_irbytes))
     }
 }
@@ -423,15 +687,70 @@ impl MacroDefinition {
         returns: usize,
         spans: Vec<Span>,
     ) -> Self {
-        MacroDefinition { name, parameters, statements, takes, returns, span: AstSpan(spans) }
+        let span = AstSpan(spans);
+        MacroDefinition {
+            name,
+            parameters,
+            statements,
+            takes,
+            returns,
+            outlined: false,
+            facet: false,
+            source_file: span.source_file(),
+            suppressed_warnings: vec![],
+            span,
+        }
     }
 
     /// Translate statements into IRBytes
-    pub fn to_irbytes(statements: &[Statement]) -> Vec<IRBytes> {
+    ///
+    /// Errors if an explicit PUSHn isn't followed by a literal that fits its immediate, or by a
+    /// constant.
+    pub fn to_irbytes(statements: &[Statement]) -> Result<Vec<IRBytes>, CodegenError> {
         let mut inner_irbytes: Vec<IRBytes> = vec![];
 
-        statements.iter().for_each(|statement| {
+        let mut statements = statements.iter().peekable();
+        while let Some(statement) = statements.next() {
             match &statement.ty {
+                // An explicit PUSHn takes the following literal or constant as its immediate,
+                // padded to n bytes
+                StatementType::Opcode(o) if o.push_width().is_some() => {
+                    let width = o.push_width().unwrap_or_default();
+                    if let Some(Statement { ty: StatementType::Constant(name), .. }) =
+                        statements.peek()
+                    {
+                        inner_irbytes.push(IRBytes {
+                            ty: IRByteType::PushedConstant(name.to_string(), width),
+                            span: statement.span.clone(),
+                        });
+                        statements.next();
+                        continue
+                    }
+                    let Some(Statement { ty: StatementType::Literal(l), .. }) = statements.peek()
+                    else {
+                        tracing::error!(target: "ast", "{} IS NOT FOLLOWED BY A LITERAL OR CONSTANT", o);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::MissingPushImmediate(width),
+                            span: statement.span.clone(),
+                            token: None,
+                        })
+                    };
+                    let hex_literal = bytes32_to_string(l, false);
+                    if hex_literal.len() / 2 > width {
+                        tracing::error!(target: "ast", "LITERAL 0x{} IS TOO WIDE FOR {}", hex_literal, o);
+                        return Err(CodegenError {
+                            kind: CodegenErrorKind::PushWidthMismatch(width, hex_literal.len() / 2),
+                            span: statement.span.clone(),
+                            token: None,
+                        })
+                    }
+                    let bytes = format!("{}{}", o.string(), pad_n_bytes(&hex_literal, width));
+                    statements.next();
+                    inner_irbytes.push(IRBytes {
+                        ty: IRByteType::Bytes(Bytes(bytes)),
+                        span: statement.span.clone(),
+                    });
+                }
                 StatementType::Literal(l) => {
                     let hex_literal: String = bytes32_to_string(l, false);
                     let push_bytes = format!("{:02x}{}", 95 + hex_literal.len() / 2, hex_literal);
@@ -491,7 +810,7 @@ impl MacroDefinition {
                     });
 
                     // Recurse label statements to IRBytes Bytes
-                    inner_irbytes.append(&mut MacroDefinition::to_irbytes(&l.inner));
+                    inner_irbytes.append(&mut MacroDefinition::to_irbytes(&l.inner)?);
                 }
                 StatementType::BuiltinFunctionCall(builtin) => {
                     inner_irbytes.push(IRBytes {
@@ -503,9 +822,9 @@ impl MacroDefinition {
                     });
                 }
             }
-        });
+        }
 
-        inner_irbytes
+        Ok(inner_irbytes)
     }
 }
 
@@ -542,6 +861,11 @@ pub enum ConstVal {
     Literal(Literal),
     /// A Free Storage Pointer
     FreeStoragePointer(FreeStoragePointer),
+    /// An immutable, set by the constructor and read from code at runtime
+    Immutable,
+    /// An array of literals, only usable in code tables where its elements are inlined back to
+    /// back
+    Array(Vec<Literal>),
 }
 
 /// A Constant Definition
@@ -551,6 +875,8 @@ pub struct ConstantDefinition {
     pub name: String,
     /// The Constant value
     pub value: ConstVal,
+    /// The file the Constant was defined in
+    pub source_file: Option<PathBuf>,
     /// The Span of the Constant Definition
     pub span: AstSpan,
 }
@@ -583,11 +909,70 @@ pub struct BuiltinFunctionCall {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum BuiltinFunctionKind {
     /// Table size function
+    ///
+    /// Pushes the size of the table in bytes (entries * 0x20 for jump tables, entries * 0x02
+    /// for packed jump tables), not the number of entries.
     Tablesize,
     /// Code size function
+    ///
+    /// `__codesize(MACRO)` pushes the size of the code the macro expands to. Anything laid out
+    /// after that code is left out: the bodies of `@outline` macros, tables, the version
+    /// metadata and constructor arguments. For `MAIN`, this is the executable code only.
     Codesize,
     /// Table start function
     Tablestart,
+    /// Calldata offset function
+    ///
+    /// `__calldata_offset(FUNCTION, index)` pushes the calldata offset of the ABI head of the
+    /// function's parameter at `index`, including the 4 byte selector.
+    CalldataOffset,
+    /// EIP-1967 proxy slot function
+    ///
+    /// `__proxy_slot("implementation")` pushes the standard storage slot
+    /// `keccak256("eip1967.proxy.implementation") - 1`. The `admin`, `beacon` and `rollback`
+    /// slots are supported as well.
+    ProxySlot,
+    /// Compile time assertion
+    ///
+    /// `__assert(SLOT < 0x20)` fails compilation unless the condition holds. The condition
+    /// compares two constants, literals or numbers with `<`, `<=`, `>`, `>=` or `==`, or checks a
+    /// single one is non-zero. Nothing is pushed.
+    Assert,
+    /// Immutable setter
+    ///
+    /// `__set_immutable(OWNER)` pops a value in the constructor and stages it for every `[OWNER]`
+    /// read in the runtime, where `OWNER` is defined as `IMMUTABLE()`. Values are staged in
+    /// memory at `0x20 * index`, in definition order, so the constructor must leave that memory
+    /// untouched afterwards.
+    SetImmutable,
+    /// Library address function
+    ///
+    /// `__linked("Math")` pushes a 20 byte placeholder for the address of the `Math` library,
+    /// patched once deployed with [Artifact::link](crate::artifact::Artifact::link).
+    Linked,
+    /// Program counter function
+    ///
+    /// `__pc()` pushes its own offset in the final bytecode, like `pc` but as a PUSH2 that is
+    /// resolved alongside jump labels.
+    Pc,
+    /// Mapping slot function
+    ///
+    /// `__mapping_slot(BALANCES_SLOT, 0x01)` pushes the storage slot of a mapping entry,
+    /// `keccak256(key . baseSlot)` with both padded to 32 bytes. The base slot and key must be
+    /// constants, literals or numbers; keys only known at runtime have to be hashed with `sha3`.
+    MappingSlot,
+    /// Minimal proxy function
+    ///
+    /// `__minimal_proxy(IMPLEMENTATION)` emits the 45 byte EIP-1167 clone runtime, delegating
+    /// every call to the implementation. The implementation is a constant, literal or number, or
+    /// a library name emitted as a placeholder like `__linked`. The clone jumps to its own offset
+    /// `0x2b`, so it must start the bytecode and is an error anywhere else.
+    MinimalProxy,
+    /// Non-payable guard function
+    ///
+    /// `__nonpayable_guard()` emits `callvalue iszero <ok> jumpi 0x00 dup1 revert ok:`, reverting
+    /// without data when the call carries ETH, as the check at the top of a non-payable function.
+    NonpayableGuard,
 }
 
 impl From<&str> for BuiltinFunctionKind {
@@ -596,11 +981,33 @@ impl From<&str> for BuiltinFunctionKind {
             "__tablesize" => BuiltinFunctionKind::Tablesize,
             "__codesize" => BuiltinFunctionKind::Codesize,
             "__tablestart" => BuiltinFunctionKind::Tablestart,
+            "__calldata_offset" => BuiltinFunctionKind::CalldataOffset,
+            "__proxy_slot" => BuiltinFunctionKind::ProxySlot,
+            "__assert" => BuiltinFunctionKind::Assert,
+            "__set_immutable" => BuiltinFunctionKind::SetImmutable,
+            "__linked" => BuiltinFunctionKind::Linked,
+            "__pc" => BuiltinFunctionKind::Pc,
+            "__mapping_slot" => BuiltinFunctionKind::MappingSlot,
+            "__minimal_proxy" => BuiltinFunctionKind::MinimalProxy,
+            "__nonpayable_guard" => BuiltinFunctionKind::NonpayableGuard,
             _ => panic!("Invalid Builtin Function Kind"), // TODO: Better error handling
         }
     }
 }
 
+/// Flattens statements in source order, descending into the bodies of labels
+fn flatten_statements(statements: &[Statement]) -> Vec<&Statement> {
+    statements
+        .iter()
+        .flat_map(|s| match &s.ty {
+            StatementType::Label(l) => {
+                std::iter::once(s).chain(flatten_statements(&l.inner)).collect::<Vec<_>>()
+            }
+            _ => vec![s],
+        })
+        .collect()
+}
+
 /// A Statement
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Statement {
@@ -30,14 +30,37 @@ pub enum IRByteType {
     Statement(Statement),
     /// A Constant to be referenced
     Constant(String),
+    /// A Constant pushed by an explicit PUSHn, padded to the push's width in bytes
+    PushedConstant(String, usize),
     /// An Arg Call needs to use the calling macro context
     ArgCall(String),
 }
 
+impl Display for IRBytes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.ty {
+            IRByteType::Bytes(b) => write!(f, "BYTES: {}", b.0),
+            IRByteType::Statement(s) => write!(f, "STATEMENT: {}", s.ty),
+            IRByteType::Constant(name) => write!(f, "CONSTANT: {}", name),
+            IRByteType::PushedConstant(name, width) => {
+                write!(f, "PUSH{} CONSTANT: {}", width, name)
+            }
+            IRByteType::ArgCall(name) => write!(f, "ARG CALL: {}", name),
+        }
+    }
+}
+
 /// Full Intermediate Bytecode Representation
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct IRBytecode(pub Vec<IRBytes>);
 
+/// Prints one IRByte per line, e.g. `BYTES: 6001` or `CONSTANT: OWNER`
+impl Display for IRBytecode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0.iter().map(|b| b.to_string()).collect::<Vec<_>>().join("\n"))
+    }
+}
+
 /// ToIRBytecode
 ///
 /// Converts a stateful object to intermediate bytecode
@@ -76,6 +99,8 @@ pub struct BytecodeRes {
     pub unmatched_jumps: Jumps,
     /// Table Instances
     pub table_instances: Jumps,
+    /// Macros expanded directly by this macro
+    pub expansions: Vec<MacroExpansion>,
 }
 
 impl Display for BytecodeRes {
@@ -96,6 +121,19 @@ impl Display for BytecodeRes {
     }
 }
 
+/// A node in the tree of macro expansions, recording where a macro's code was placed
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MacroExpansion {
+    /// The name of the expanded macro
+    pub macro_name: String,
+    /// Offset of the first byte of the expansion
+    pub start: usize,
+    /// Offset one past the last byte of the expansion
+    pub end: usize,
+    /// Macros invoked by this macro, in invocation order
+    pub children: Vec<MacroExpansion>,
+}
+
 /// A Jump
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Jump {
@@ -105,6 +143,11 @@ pub struct Jump {
     pub bytecode_index: usize,
     /// The Jump Span
     pub span: AstSpan,
+    /// How many expansions above the jump's own macro the label is defined in.
+    ///
+    /// Labels passed as macro arguments belong to the scope they were passed from, every other
+    /// label must be defined in the jump's own macro.
+    pub scope_depth: usize,
 }
 
 /// Type for a vec of `Jump`s
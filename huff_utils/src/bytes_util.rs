@@ -1,3 +1,5 @@
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+
 /// Convert a string slice to a `[u8; 32]`
 /// Pads zeros to the left of significant bytes in the `[u8; 32]` slice.
 /// i.e. 0xa57b becomes `[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -47,8 +49,12 @@ pub fn format_even_bytes(hex: String) -> String {
 }
 
 /// Convert string slice to Vec<u8>, size not capped
-pub fn str_to_vec(s: &str) -> Result<Vec<u8>, std::num::ParseIntError> {
-    let bytes: Result<Vec<u8>, _> =
-        (0..s.len()).step_by(2).map(|c| u8::from_str_radix(&s[c..c + 2], 16)).collect();
+///
+/// Errors if the string isn't made of whole hex bytes.
+pub fn str_to_vec(s: &str) -> Result<Vec<u8>, core::num::ParseIntError> {
+    let bytes: Result<Vec<u8>, _> = (0..s.len())
+        .step_by(2)
+        .map(|c| u8::from_str_radix(s.get(c..c + 2).unwrap_or_default(), 16))
+        .collect();
     bytes
 }
@@ -0,0 +1,275 @@
+use crate::evm::Opcode;
+use ethers_core::utils::hex;
+
+/// A straight-line run of instructions with a single entry and exit
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// The offset of the first instruction in the block
+    pub start: usize,
+    /// The offset just past the last instruction in the block
+    pub end: usize,
+    /// The start offsets of the blocks control can flow to, in ascending order
+    pub successors: Vec<usize>,
+}
+
+/// A control flow graph over bytecode
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Cfg {
+    /// The basic blocks, ordered by start offset
+    pub blocks: Vec<BasicBlock>,
+}
+
+impl Cfg {
+    /// Returns the block starting at the given offset
+    pub fn block_at(&self, start: usize) -> Option<&BasicBlock> {
+        self.blocks.iter().find(|b| b.start == start)
+    }
+}
+
+/// Builds a control flow graph from a hex encoded bytecode string.
+///
+/// Blocks are split at JUMPDESTs and after JUMP, JUMPI and halting instructions. A jump's target
+/// is only added as a successor when it is pushed immediately before the jump and lands on a
+/// JUMPDEST; dynamic jumps have no resolved jump successor. Bytecode that isn't valid hex
+/// produces an empty graph.
+pub fn build_cfg(bytecode: &str) -> Cfg {
+    let bytes = match hex::decode(bytecode.strip_prefix("0x").unwrap_or(bytecode)) {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::warn!(target: "cfg", "Failed to decode bytecode: {:?}", e);
+            return Cfg::default()
+        }
+    };
+
+    // Decode instructions as (offset, opcode, static push value)
+    let mut instructions: Vec<(usize, u8, Option<usize>)> = vec![];
+    let mut pc = 0;
+    while pc < bytes.len() {
+        let op = bytes[pc];
+        if (0x60..=0x7f).contains(&op) {
+            let end = (pc + 1 + (op - 0x5f) as usize).min(bytes.len());
+            let value = bytes[pc + 1..end]
+                .iter()
+                .fold(0usize, |acc, b| acc.saturating_mul(256).saturating_add(*b as usize));
+            instructions.push((pc, op, Some(value)));
+            pc = end;
+        } else {
+            instructions.push((pc, op, None));
+            pc += 1;
+        }
+    }
+    let is_jumpdest = |offset: usize| instructions.iter().any(|i| i.0 == offset && i.1 == 0x5b);
+
+    let mut blocks: Vec<BasicBlock> = vec![];
+    let mut start = 0;
+    for (i, &(offset, op, _)) in instructions.iter().enumerate() {
+        // A JUMPDEST always begins a new block
+        if op == 0x5b && offset != start {
+            blocks.push(BasicBlock { start, end: offset, successors: vec![offset] });
+            start = offset;
+        }
+
+        let next = instructions.get(i + 1).map(|n| n.0);
+        let static_target =
+            i.checked_sub(1).and_then(|p| instructions[p].2).filter(|t| is_jumpdest(*t));
+        let successors = match op {
+            // JUMP
+            0x56 => static_target.into_iter().collect(),
+            // JUMPI
+            0x57 => static_target.into_iter().chain(next).collect(),
+            // STOP, RETURN, REVERT, INVALID, SELFDESTRUCT
+            0x00 | 0xf3 | 0xfd | 0xfe | 0xff => vec![],
+            _ => continue,
+        };
+
+        let end = next.unwrap_or(bytes.len());
+        blocks.push(BasicBlock { start, end, successors });
+        start = end;
+    }
+    if start < bytes.len() {
+        blocks.push(BasicBlock { start, end: bytes.len(), successors: vec![] });
+    }
+
+    for block in &mut blocks {
+        block.successors.sort_unstable();
+        block.successors.dedup();
+    }
+
+    Cfg { blocks }
+}
+
+/// A JUMPI whose jump and fall-through paths reach the same block with different stack depths
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchImbalance {
+    /// The offset of the JUMPI
+    pub offset: usize,
+    /// The stack depth the jump path arrives with, relative to the depth after the JUMPI
+    pub jump_depth: isize,
+    /// The stack depth the fall-through path arrives with, relative to the depth after the JUMPI
+    pub fallthrough_depth: isize,
+}
+
+/// The net number of items an opcode pushes onto the stack
+fn stack_delta(op: u8) -> isize {
+    match op {
+        // ADDMOD, MULMOD
+        0x08 | 0x09 => -2,
+        // Binary arithmetic, comparison, bitwise and SHA3
+        0x01..=0x07 | 0x0a..=0x14 | 0x16..=0x18 | 0x1a..=0x1d | 0x20 => -1,
+        // ISZERO, NOT, BALANCE, CALLDATALOAD, EXTCODESIZE, EXTCODEHASH, BLOCKHASH, MLOAD, SLOAD
+        0x15 | 0x19 | 0x31 | 0x35 | 0x3b | 0x3f | 0x40 | 0x51 | 0x54 => 0,
+        // CALLDATACOPY, CODECOPY, RETURNDATACOPY
+        0x37 | 0x39 | 0x3e => -3,
+        // EXTCODECOPY
+        0x3c => -4,
+        // Environment and block information, PC, MSIZE, GAS, PUSH0 - PUSH32
+        0x30..=0x48 | 0x58..=0x5a | 0x5f..=0x7f => 1,
+        // POP, JUMP, SELFDESTRUCT
+        0x50 | 0x56 | 0xff => -1,
+        // MSTORE, MSTORE8, SSTORE, JUMPI, RETURN, REVERT
+        0x52 | 0x53 | 0x55 | 0x57 | 0xf3 | 0xfd => -2,
+        // DUP1 - DUP16
+        0x80..=0x8f => 1,
+        // LOG0 - LOG4
+        0xa0..=0xa4 => -2 - (op - 0xa0) as isize,
+        // CREATE
+        0xf0 => -2,
+        // CALL, CALLCODE
+        0xf1 | 0xf2 => -6,
+        // DELEGATECALL, STATICCALL
+        0xf4 | 0xfa => -5,
+        // CREATE2
+        0xf5 => -3,
+        _ => 0,
+    }
+}
+
+/// Simulates a block entered with the given stack depth, returning the depth on exit and the
+/// offset of the block's last instruction
+fn simulate_block(bytes: &[u8], block: &BasicBlock, depth: isize) -> (isize, usize) {
+    let mut exit_depth = depth;
+    let mut last = block.start;
+    let mut pc = block.start;
+    while pc < block.end {
+        let op = bytes[pc];
+        exit_depth += stack_delta(op);
+        last = pc;
+        pc += match op {
+            0x60..=0x7f => 1 + (op - 0x5f) as usize,
+            _ => 1,
+        };
+    }
+    (exit_depth, last)
+}
+
+/// Finds JUMPIs whose two paths rejoin with different stack depths.
+///
+/// Stack depths are simulated across the basic blocks of the [Cfg], starting from an empty
+/// stack at offset 0. Each block is only simulated for the first path reaching it, so a
+/// conflicting later path is reported against the JUMPI it last branched at. This is a heuristic:
+/// dynamic jumps are not followed, and paths that never rejoin are not compared.
+pub fn find_unbalanced_branches(bytecode: &str) -> Vec<BranchImbalance> {
+    let Ok(bytes) = hex::decode(bytecode.strip_prefix("0x").unwrap_or(bytecode)) else {
+        return vec![]
+    };
+    let cfg = build_cfg(bytecode);
+
+    // The stack depth on entry to each visited block, and the (JUMPI offset, depth after the
+    // JUMPI, jump taken) branch the path came through
+    type Branch = Option<(usize, isize, bool)>;
+    let mut entries: Vec<(usize, isize, Branch)> = vec![];
+    let mut worklist: Vec<(usize, isize, Branch)> =
+        cfg.blocks.first().map(|b| (b.start, 0, None)).into_iter().collect();
+    let mut unbalanced: Vec<BranchImbalance> = vec![];
+
+    while let Some((start, depth, branch)) = worklist.pop() {
+        if let Some(&(_, seen_depth, _)) = entries.iter().find(|e| e.0 == start) {
+            if let Some((offset, base, taken)) = branch.filter(|_| seen_depth != depth) {
+                let (jump_depth, fallthrough_depth) = match taken {
+                    true => (depth - base, seen_depth - base),
+                    false => (seen_depth - base, depth - base),
+                };
+                if !unbalanced.iter().any(|u| u.offset == offset) {
+                    unbalanced.push(BranchImbalance { offset, jump_depth, fallthrough_depth });
+                }
+            }
+            continue
+        }
+        entries.push((start, depth, branch));
+        let Some(block) = cfg.block_at(start) else { continue };
+
+        let (exit_depth, last) = simulate_block(&bytes, block, depth);
+        let ends_in_jumpi = bytes.get(last) == Some(&0x57);
+        for &successor in &block.successors {
+            let branch = match ends_in_jumpi {
+                true => Some((last, exit_depth, successor != block.end)),
+                false => branch,
+            };
+            worklist.push((successor, exit_depth, branch));
+        }
+    }
+
+    unbalanced.sort_by_key(|u| u.offset);
+    unbalanced
+}
+
+/// Returns the stack depth control falls off the end of the bytecode with, or `None` if no path
+/// from offset 0 runs past the last instruction.
+///
+/// Like [find_unbalanced_branches], stack depths are simulated from an empty stack and each block
+/// is only simulated for the first path reaching it, and dynamic jumps are not followed.
+pub fn exit_stack_depth(bytecode: &str) -> Option<isize> {
+    let bytes = hex::decode(bytecode.strip_prefix("0x").unwrap_or(bytecode)).ok()?;
+    let cfg = build_cfg(bytecode);
+
+    let mut visited: Vec<usize> = vec![];
+    let mut worklist: Vec<(usize, isize)> =
+        cfg.blocks.first().map(|b| (b.start, 0)).into_iter().collect();
+    while let Some((start, depth)) = worklist.pop() {
+        if visited.contains(&start) {
+            continue
+        }
+        visited.push(start);
+        let Some(block) = cfg.block_at(start) else { continue };
+
+        let (exit_depth, last) = simulate_block(&bytes, block, depth);
+        let falls_through = !Opcode::from_byte(bytes[last]).is_some_and(|op| op.is_terminating());
+        if block.end == bytes.len() && falls_through {
+            return Some(exit_depth)
+        }
+        worklist.extend(block.successors.iter().map(|s| (*s, exit_depth)));
+    }
+    None
+}
+
+/// Whether some path from offset 0 runs past the instruction ending at `end` without halting or
+/// jumping away, as control does when it falls off the end of a macro's code.
+///
+/// Like [exit_stack_depth], dynamic jumps are not followed.
+pub fn runs_past(bytecode: &str, end: usize) -> bool {
+    let Ok(bytes) = hex::decode(bytecode.strip_prefix("0x").unwrap_or(bytecode)) else {
+        return false
+    };
+    let cfg = build_cfg(bytecode);
+
+    let mut visited: Vec<usize> = vec![];
+    let mut worklist: Vec<usize> = cfg.blocks.first().map(|b| b.start).into_iter().collect();
+    while let Some(start) = worklist.pop() {
+        if visited.contains(&start) {
+            continue
+        }
+        visited.push(start);
+        let Some(block) = cfg.block_at(start) else { continue };
+
+        if block.start < end && end < block.end {
+            return true
+        }
+        let (_, last) = simulate_block(&bytes, block, 0);
+        let falls_through = !Opcode::from_byte(bytes[last]).is_some_and(|op| op.is_terminating());
+        if block.end == end && falls_through {
+            return true
+        }
+        worklist.extend(block.successors.iter().copied());
+    }
+    false
+}
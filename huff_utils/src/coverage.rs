@@ -0,0 +1,69 @@
+use crate::{
+    disassembler::{disassemble, Instruction},
+    evm::Opcode,
+};
+use alloc::{collections::BTreeSet, vec::Vec};
+use core::fmt;
+
+/// Which instructions of a runtime were executed, for measuring test coverage at the bytecode
+/// level
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct OpcodeCoverage {
+    /// Each instruction of the runtime, and whether it was executed
+    pub instructions: Vec<(Instruction, bool)>,
+}
+
+impl OpcodeCoverage {
+    /// The number of executed instructions and the total number of instructions
+    pub fn covered(&self) -> (usize, usize) {
+        (self.instructions.iter().filter(|(_, hit)| *hit).count(), self.instructions.len())
+    }
+
+    /// The distinct opcodes executed at least once
+    pub fn executed_opcodes(&self) -> BTreeSet<Opcode> {
+        self.instructions.iter().filter(|(_, hit)| *hit).filter_map(|(i, _)| i.opcode).collect()
+    }
+
+    /// The distinct opcodes in the runtime that were never executed at any offset
+    pub fn missed_opcodes(&self) -> BTreeSet<Opcode> {
+        let executed = self.executed_opcodes();
+        self.instructions
+            .iter()
+            .filter_map(|(i, _)| i.opcode)
+            .filter(|o| !executed.contains(o))
+            .collect()
+    }
+
+    /// The instructions that were never executed
+    pub fn uncovered(&self) -> Vec<&Instruction> {
+        self.instructions.iter().filter(|(_, hit)| !*hit).map(|(i, _)| i).collect()
+    }
+}
+
+impl fmt::Display for OpcodeCoverage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (covered, total) = self.covered();
+        writeln!(f, "Executed {}/{} instructions", covered, total)?;
+        for i in self.uncovered() {
+            writeln!(f, "  not executed: {:04x}  {}", i.offset, i.mnemonic())?;
+        }
+        Ok(())
+    }
+}
+
+/// Reports which instructions of a hex encoded runtime were executed, given the program counters
+/// recorded while running it, e.g. from a revm inspector's `step` hook across a set of calls.
+///
+/// The runtime is disassembled linearly, so data appended after the code, such as jump tables or
+/// metadata, is counted as never executed instructions.
+pub fn opcode_coverage(runtime: &str, executed_pcs: &BTreeSet<usize>) -> OpcodeCoverage {
+    OpcodeCoverage {
+        instructions: disassemble(runtime)
+            .into_iter()
+            .map(|i| {
+                let hit = executed_pcs.contains(&i.offset);
+                (i, hit)
+            })
+            .collect(),
+    }
+}
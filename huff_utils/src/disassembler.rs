@@ -0,0 +1,56 @@
+use crate::{bytes_util::str_to_vec, evm::Opcode};
+use alloc::{format, string::String, vec::Vec};
+
+/// A single decoded instruction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// The offset of the instruction in the bytecode
+    pub offset: usize,
+    /// The opcode byte
+    pub byte: u8,
+    /// The decoded opcode, `None` for bytes that aren't an opcode
+    pub opcode: Option<Opcode>,
+    /// The PUSH immediate, truncated if the bytecode ends before it does
+    pub immediate: Vec<u8>,
+}
+
+impl Instruction {
+    /// The upper case mnemonic, e.g. `PUSH1`, or `UNKNOWN(0x0c)` for bytes that aren't an opcode
+    pub fn mnemonic(&self) -> String {
+        match &self.opcode {
+            Some(o) => format!("{:?}", o).to_uppercase(),
+            None => format!("UNKNOWN(0x{:02x})", self.byte),
+        }
+    }
+
+    /// The instruction's bytes as a hex string, opcode followed by immediate
+    pub fn hex(&self) -> String {
+        core::iter::once(&self.byte).chain(&self.immediate).map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+/// Decodes a hex encoded bytecode string into its instructions.
+///
+/// Bytecode that isn't valid hex decodes to no instructions.
+pub fn disassemble(bytecode: &str) -> Vec<Instruction> {
+    let Ok(bytes) = str_to_vec(bytecode.strip_prefix("0x").unwrap_or(bytecode)) else {
+        return Vec::new()
+    };
+
+    let mut instructions = Vec::new();
+    let mut pc = 0;
+    while pc < bytes.len() {
+        let byte = bytes[pc];
+        let opcode = Opcode::from_byte(byte);
+        let width = opcode.and_then(|o| o.push_width()).unwrap_or(0);
+        let end = (pc + 1 + width).min(bytes.len());
+        instructions.push(Instruction {
+            offset: pc,
+            byte,
+            opcode,
+            immediate: bytes[pc + 1..end].to_vec(),
+        });
+        pc = end;
+    }
+    instructions
+}
@@ -1,11 +1,12 @@
 use crate::{
+    evm::{Opcode, OPCODES_MAP},
     files::{Span, Spanned},
     io::UnpackError,
     prelude::{parse_extension, AstSpan},
     report::{Report, Reporter},
     token::TokenKind,
 };
-use std::{ffi::OsString, fmt, io::Write};
+use std::{ffi::OsString, fmt, io::Write, ptr};
 
 /// A Parser Error
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -55,6 +56,10 @@ pub enum ParserErrorKind {
     InvalidReturnArgs,
     /// Invalid import path
     InvalidImportPath(String),
+    /// Includes are nested deeper than the maximum include depth
+    IncludeDepthExceeded(usize),
+    /// A Solidity type that has no EVM representation, e.g. fixed point numbers
+    UnsupportedType(String),
 }
 
 /// A Lexing Error
@@ -85,6 +90,8 @@ pub enum LexicalErrorKind<'a> {
     InvalidArraySize(&'a str),
     /// Invalid Primitive EVM Type
     InvalidPrimitiveType(&'a str),
+    /// A literal that doesn't fit in its type
+    LiteralTooLarge(&'a str),
 }
 
 impl<'a> Spanned for LexicalError<'a> {
@@ -104,6 +111,7 @@ impl<'a, W: Write> Report<W> for LexicalError<'a> {
             LexicalErrorKind::InvalidPrimitiveType(str) => {
                 write!(f.out, "Invalid Primitive EVM Type '{}'", str)
             }
+            LexicalErrorKind::LiteralTooLarge(str) => write!(f.out, "Literal too large: '{}'", str),
         }
     }
 }
@@ -126,6 +134,15 @@ impl CodegenError {
     }
 }
 
+/// The source name of an opcode, e.g. `selfdestruct`, falling back to its hex byte
+fn opcode_name(opcode: &Opcode) -> String {
+    OPCODES_MAP
+        .entries()
+        .find(|(_, o)| *o == opcode)
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| opcode.string())
+}
+
 /// The Code Generation Error Kind
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum CodegenErrorKind {
@@ -151,6 +168,282 @@ pub enum CodegenErrorKind {
     InvalidMacroInvocation(String),
     /// Conversion Error for usize
     UsizeConversion(String),
+    /// A snippet failed to lex or parse
+    InvalidSnippet(String),
+    /// Bytecode could not be decoded
+    InvalidBytecode(String),
+    /// A jump targets an offset that is not a JUMPDEST
+    InvalidJumpTarget(usize),
+    /// The Function Definition is Missing
+    MissingFunctionDefinition(String),
+    /// Invalid Arguments passed to a builtin function
+    InvalidArguments(String),
+    /// A definition is declared more than once
+    DuplicateDefinition(String),
+    /// Two distinct functions share the same 4 byte selector
+    SelectorCollision(String, String),
+    /// The constructor and bootstrap can't deploy the runtime
+    MalformedConstructor(String),
+    /// A compile time `__assert` condition doesn't hold
+    AssertionFailed(String),
+    /// The constructor references a table that jumps to labels only defined in the runtime
+    TableScopeMismatch(String),
+    /// An opcode forbidden by the compilation's opcode policy is used
+    ForbiddenOpcode(Opcode),
+    /// A value needs a push wider than PUSH32, with the width in bytes
+    PushTooLarge(usize),
+    /// A constants JSON file isn't a map of constant names to values
+    InvalidConstantsJson(String),
+    /// The Codegen instance has no AST to generate from
+    MissingAst,
+    /// A free storage pointer is assigned the slot of an explicit slot constant, with the free
+    /// storage pointer's and the explicit constant's names
+    StorageSlotCollision(String, String),
+    /// A builtin that only works at the start of the bytecode is placed at a later offset, with
+    /// the builtin's name and offset
+    MisplacedBuiltin(String, usize),
+    /// An explicit PUSHn is given a value wider than its immediate, with the push's and the
+    /// value's widths in bytes
+    PushWidthMismatch(usize, usize),
+    /// An explicit PUSHn isn't followed by a literal or constant, with the push's width in bytes
+    MissingPushImmediate(usize),
+}
+
+/// A Code Generation Warning
+///
+/// Warnings do not stop compilation, but flag output that is likely to misbehave.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CodegenWarning {
+    /// The runtime bytecode is larger than the deployable code size limit
+    ExceedsCodeSizeLimit {
+        /// The runtime bytecode size in bytes
+        size: usize,
+        /// The code size limit in bytes
+        limit: usize,
+    },
+    /// A jump whose destination is computed at runtime rather than taken from a label
+    DynamicJump {
+        /// The macro containing the jump
+        macro_name: String,
+        /// The span of the jump opcode
+        span: AstSpan,
+    },
+    /// A deprecated or discouraged opcode is used
+    DeprecatedOpcode {
+        /// The deprecated opcode
+        opcode: Opcode,
+        /// The macro using the opcode
+        macro_name: String,
+        /// The span of the opcode
+        span: AstSpan,
+    },
+    /// A macro that is never invoked
+    UnusedMacro {
+        /// The unused macro
+        macro_name: String,
+        /// The span of the macro definition
+        span: AstSpan,
+    },
+    /// A memory access at a constant offset outside the scratch space and declared regions
+    UndeclaredMemoryAccess {
+        /// The accessing opcode
+        opcode: Opcode,
+        /// The constant memory offset
+        offset: usize,
+        /// The macro making the access
+        macro_name: String,
+        /// The span of the opcode
+        span: AstSpan,
+    },
+    /// An `mload` or `mstore` at a constant offset that isn't a multiple of 0x20
+    MisalignedMemoryAccess {
+        /// The accessing opcode
+        opcode: Opcode,
+        /// The constant memory offset
+        offset: usize,
+        /// The macro making the access
+        macro_name: String,
+        /// The span of the opcode
+        span: AstSpan,
+    },
+    /// A RETURN whose constant length differs from the declared return size
+    ReturnSizeMismatch {
+        /// The return size declared with `// huff-returns: <bytes>`
+        expected: usize,
+        /// The constant length passed to the RETURN
+        actual: usize,
+        /// The macro containing the RETURN
+        macro_name: String,
+        /// The span of the RETURN
+        span: AstSpan,
+    },
+    /// A JUMPI whose jump and fall-through paths rejoin with different stack depths
+    UnbalancedBranch {
+        /// The bytecode offset of the JUMPI
+        offset: usize,
+        /// The stack depth the jump path arrives with, relative to the depth after the JUMPI
+        jump_depth: isize,
+        /// The stack depth the fall-through path arrives with, relative to the depth after
+        /// the JUMPI
+        fallthrough_depth: isize,
+    },
+    /// The CONSTRUCTOR leaves items on the stack before the bootstrap code copies out the runtime
+    ConstructorStackResidue {
+        /// The number of items left on the stack
+        depth: isize,
+    },
+    /// A constant defined in source is replaced by an injected value
+    ConstantOverridden {
+        /// The overridden constant
+        name: String,
+        /// The span of the constant definition
+        span: AstSpan,
+    },
+    /// MAIN can run off the end of its code, which the EVM treats as STOP
+    MainFallsThrough {
+        /// The offset just past MAIN's code
+        offset: usize,
+    },
+}
+
+impl CodegenWarning {
+    /// The name used to suppress the warning with a `// huff-disable: <name>` directive
+    pub fn name(&self) -> &'static str {
+        match self {
+            CodegenWarning::ExceedsCodeSizeLimit { .. } => "code-size",
+            CodegenWarning::DynamicJump { .. } => "dynamic-jump",
+            CodegenWarning::DeprecatedOpcode { .. } => "deprecated-opcode",
+            CodegenWarning::UnusedMacro { .. } => "unused-macro",
+            CodegenWarning::UnbalancedBranch { .. } => "unbalanced-branch",
+            CodegenWarning::UndeclaredMemoryAccess { .. } => "memory-region",
+            CodegenWarning::MisalignedMemoryAccess { .. } => "memory-alignment",
+            CodegenWarning::ReturnSizeMismatch { .. } => "return-size",
+            CodegenWarning::ConstructorStackResidue { .. } => "constructor-stack",
+            CodegenWarning::ConstantOverridden { .. } => "constant-override",
+            CodegenWarning::MainFallsThrough { .. } => "main-fallthrough",
+        }
+    }
+
+    /// The macro the warning was raised in, if it is specific to one
+    pub fn macro_name(&self) -> Option<&str> {
+        match self {
+            CodegenWarning::ExceedsCodeSizeLimit { .. } |
+            CodegenWarning::ConstantOverridden { .. } |
+            CodegenWarning::UnbalancedBranch { .. } => None,
+            CodegenWarning::DynamicJump { macro_name, .. } |
+            CodegenWarning::DeprecatedOpcode { macro_name, .. } |
+            CodegenWarning::UnusedMacro { macro_name, .. } |
+            CodegenWarning::UndeclaredMemoryAccess { macro_name, .. } |
+            CodegenWarning::MisalignedMemoryAccess { macro_name, .. } |
+            CodegenWarning::ReturnSizeMismatch { macro_name, .. } => Some(macro_name),
+            CodegenWarning::ConstructorStackResidue { .. } => Some("CONSTRUCTOR"),
+            CodegenWarning::MainFallsThrough { .. } => Some("MAIN"),
+        }
+    }
+}
+
+/// Receives [CodegenWarning]s as they are raised, so long compilations can stream diagnostics
+/// instead of reading them all once code generation is done
+pub trait WarningSink: fmt::Debug + Send + Sync {
+    /// Handles a warning, called once for each warning that isn't suppressed
+    fn warn(&self, warning: &CodegenWarning);
+}
+
+/// Sinks are compared by identity
+impl PartialEq for dyn WarningSink {
+    fn eq(&self, other: &Self) -> bool {
+        ptr::addr_eq(self, other)
+    }
+}
+
+impl Eq for dyn WarningSink {}
+
+impl fmt::Display for CodegenWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodegenWarning::ExceedsCodeSizeLimit { size, limit } => write!(
+                f,
+                "Warning: Runtime bytecode is {} bytes, exceeding the code size limit of {} bytes",
+                size, limit
+            ),
+            CodegenWarning::DynamicJump { macro_name, span } => write!(
+                f,
+                "Warning: Jump in macro \"{}\" can't be statically resolved to a JUMPDEST\n{}",
+                macro_name,
+                span.error()
+            ),
+            CodegenWarning::DeprecatedOpcode { opcode, macro_name, span } => write!(
+                f,
+                "Warning: Macro \"{}\" uses deprecated opcode \"{}\"\n{}",
+                macro_name,
+                opcode_name(opcode),
+                span.error()
+            ),
+            CodegenWarning::UnusedMacro { macro_name, span } => {
+                write!(f, "Warning: Macro \"{}\" is never used\n{}", macro_name, span.error())
+            }
+            CodegenWarning::UndeclaredMemoryAccess { opcode, offset, macro_name, span } => {
+                write!(
+                    f,
+                    "Warning: Macro \"{}\" accesses memory at 0x{:x} with opcode \"{}\", outside \
+                     the scratch space and declared memory regions\n{}",
+                    macro_name,
+                    offset,
+                    OPCODES_MAP
+                        .entries()
+                        .find(|(_, o)| *o == opcode)
+                        .map(|(name, _)| name.to_string())
+                        .unwrap_or_else(|| opcode.string()),
+                    span.error()
+                )
+            }
+            CodegenWarning::MisalignedMemoryAccess { opcode, offset, macro_name, span } => {
+                write!(
+                    f,
+                    "Warning: Macro \"{}\" accesses memory at 0x{:x} with opcode \"{}\", which \
+                     isn't aligned to a 32 byte word\n{}",
+                    macro_name,
+                    offset,
+                    OPCODES_MAP
+                        .entries()
+                        .find(|(_, o)| *o == opcode)
+                        .map(|(name, _)| name.to_string())
+                        .unwrap_or_else(|| opcode.string()),
+                    span.error()
+                )
+            }
+            CodegenWarning::ReturnSizeMismatch { expected, actual, macro_name, span } => write!(
+                f,
+                "Warning: Macro \"{}\" returns {} bytes, but {} bytes are declared\n{}",
+                macro_name,
+                actual,
+                expected,
+                span.error()
+            ),
+            CodegenWarning::UnbalancedBranch { offset, jump_depth, fallthrough_depth } => write!(
+                f,
+                "Warning: Paths out of the JUMPI at offset {} rejoin with a stack depth of {} \
+                 when jumping but {} when falling through",
+                offset, jump_depth, fallthrough_depth
+            ),
+            CodegenWarning::ConstructorStackResidue { depth } => write!(
+                f,
+                "Warning: CONSTRUCTOR leaves {} item(s) on the stack before the bootstrap code",
+                depth
+            ),
+            CodegenWarning::ConstantOverridden { name, span } => write!(
+                f,
+                "Warning: Constant \"{}\" is overridden by an injected value\n{}",
+                name,
+                span.error()
+            ),
+            CodegenWarning::MainFallsThrough { offset } => write!(
+                f,
+                "Warning: MAIN can run off the end of its code at offset 0x{:x} without a terminating opcode",
+                offset
+            ),
+        }
+    }
 }
 
 impl Spanned for CodegenError {
@@ -185,6 +478,64 @@ impl<W: Write> Report<W> for CodegenError {
             CodegenErrorKind::UsizeConversion(input) => {
                 write!(f.out, "Usize Conversion Failed for \"{}\"", input)
             }
+            CodegenErrorKind::InvalidSnippet(reason) => {
+                write!(f.out, "Invalid Snippet: {}", reason)
+            }
+            CodegenErrorKind::InvalidBytecode(reason) => {
+                write!(f.out, "Invalid Bytecode: {}", reason)
+            }
+            CodegenErrorKind::InvalidJumpTarget(target) => {
+                write!(f.out, "Jump target {:#x} is not a JUMPDEST", target)
+            }
+            CodegenErrorKind::MissingFunctionDefinition(func) => {
+                write!(f.out, "Missing Function \"{}\" Definition!", func)
+            }
+            CodegenErrorKind::InvalidArguments(msg) => {
+                write!(f.out, "Invalid Arguments: {}", msg)
+            }
+            CodegenErrorKind::DuplicateDefinition(name) => {
+                write!(f.out, "Duplicate Definition \"{}\"", name)
+            }
+            CodegenErrorKind::SelectorCollision(first, second) => {
+                write!(f.out, "Selector Collision Between \"{}\" And \"{}\"", first, second)
+            }
+            CodegenErrorKind::MalformedConstructor(reason) => {
+                write!(f.out, "Malformed Constructor: {}", reason)
+            }
+            CodegenErrorKind::AssertionFailed(msg) => write!(f.out, "Assertion Failed: {}", msg),
+            CodegenErrorKind::TableScopeMismatch(table) => {
+                write!(
+                    f.out,
+                    "Table \"{}\" used by the constructor jumps to runtime labels!",
+                    table
+                )
+            }
+            CodegenErrorKind::ForbiddenOpcode(op) => {
+                write!(f.out, "Forbidden Opcode: \"{}\"", opcode_name(op))
+            }
+            CodegenErrorKind::PushTooLarge(width) => {
+                write!(f.out, "Push Too Large: {} bytes exceeds PUSH32", width)
+            }
+            CodegenErrorKind::InvalidConstantsJson(msg) => {
+                write!(f.out, "Invalid Constants JSON: {}", msg)
+            }
+            CodegenErrorKind::MissingAst => write!(f.out, "Missing AST!"),
+            CodegenErrorKind::StorageSlotCollision(pointer, explicit) => write!(
+                f.out,
+                "Storage Slot Collision: \"{}\" is assigned the slot of \"{}\"",
+                pointer, explicit
+            ),
+            CodegenErrorKind::MisplacedBuiltin(name, offset) => write!(
+                f.out,
+                "Misplaced Builtin: \"{}\" must start the bytecode, but is at offset {:#x}",
+                name, offset
+            ),
+            CodegenErrorKind::PushWidthMismatch(width, needed) => {
+                write!(f.out, "PUSH{} can't hold a {} byte value", width, needed)
+            }
+            CodegenErrorKind::MissingPushImmediate(width) => {
+                write!(f.out, "PUSH{} must be followed by a literal or constant", width)
+            }
         }
     }
 }
@@ -245,6 +596,15 @@ impl<'a> fmt::Display for CompilerError<'a> {
                         le.span.source_seg()
                     )
                 }
+                LexicalErrorKind::LiteralTooLarge(lit) => {
+                    write!(
+                        f,
+                        "\nError: Literal Too Large: \"{}\" {}{}\n",
+                        lit,
+                        le.span.identifier(),
+                        le.span.source_seg()
+                    )
+                }
             },
             CompilerError::FileUnpackError(ue) => match ue {
                 UnpackError::InvalidDirectory(id) => {
@@ -352,6 +712,17 @@ impl<'a> fmt::Display for CompilerError<'a> {
                 ParserErrorKind::InvalidImportPath(ip) => {
                     write!(f, "\nError: Invalid Import Path: \"{}\" \n{}\n", ip, pe.spans.error())
                 }
+                ParserErrorKind::IncludeDepthExceeded(depth) => {
+                    write!(
+                        f,
+                        "\nError: Includes Exceed The Maximum Depth Of {} \n{}\n",
+                        depth,
+                        pe.spans.error()
+                    )
+                }
+                ParserErrorKind::UnsupportedType(ty) => {
+                    write!(f, "\nError: Unsupported Type: \"{}\" \n{}\n", ty, pe.spans.error())
+                }
             },
             CompilerError::PathBufRead(os_str) => {
                 write!(
@@ -409,6 +780,101 @@ impl<'a> fmt::Display for CompilerError<'a> {
                 CodegenErrorKind::UsizeConversion(_) => {
                     write!(f, "\nError: Usize Conversion\n{}\n", ce.span.error())
                 }
+                CodegenErrorKind::InvalidSnippet(reason) => {
+                    write!(f, "\nError: Invalid Snippet: {}\n{}\n", reason, ce.span.error())
+                }
+                CodegenErrorKind::InvalidBytecode(reason) => {
+                    write!(f, "\nError: Invalid Bytecode: {}\n", reason)
+                }
+                CodegenErrorKind::InvalidJumpTarget(target) => {
+                    write!(f, "\nError: Jump Target {:#x} Is Not A JUMPDEST\n", target)
+                }
+                CodegenErrorKind::MissingFunctionDefinition(func) => {
+                    write!(
+                        f,
+                        "\nError: Missing Function Definition For \"{}\"\n{}\n",
+                        func,
+                        ce.span.error()
+                    )
+                }
+                CodegenErrorKind::InvalidArguments(msg) => {
+                    write!(f, "\nError: Invalid Arguments: {}\n{}\n", msg, ce.span.error())
+                }
+                CodegenErrorKind::DuplicateDefinition(name) => {
+                    write!(f, "\nError: Duplicate Definition \"{}\"\n{}\n", name, ce.span.error())
+                }
+                CodegenErrorKind::SelectorCollision(first, second) => {
+                    write!(
+                        f,
+                        "\nError: Selector Collision Between \"{}\" And \"{}\"\n{}\n",
+                        first,
+                        second,
+                        ce.span.error()
+                    )
+                }
+                CodegenErrorKind::MalformedConstructor(reason) => {
+                    write!(f, "\nError: Malformed Constructor: {}\n{}\n", reason, ce.span.error())
+                }
+                CodegenErrorKind::AssertionFailed(msg) => {
+                    write!(f, "\nError: Assertion Failed: {}\n{}\n", msg, ce.span.error())
+                }
+                CodegenErrorKind::TableScopeMismatch(table) => {
+                    write!(
+                        f,
+                        "\nError: Table \"{}\" used by the constructor jumps to runtime labels\n{}\n",
+                        table,
+                        ce.span.error()
+                    )
+                }
+                CodegenErrorKind::ForbiddenOpcode(op) => {
+                    write!(
+                        f,
+                        "\nError: Forbidden Opcode: \"{}\"\n{}\n",
+                        opcode_name(op),
+                        ce.span.error()
+                    )
+                }
+                CodegenErrorKind::PushTooLarge(width) => {
+                    write!(
+                        f,
+                        "\nError: Push Too Large: {} bytes exceeds PUSH32\n{}\n",
+                        width,
+                        ce.span.error()
+                    )
+                }
+                CodegenErrorKind::InvalidConstantsJson(msg) => {
+                    write!(f, "\nError: Invalid Constants JSON: {}\n{}\n", msg, ce.span.error())
+                }
+                CodegenErrorKind::MissingAst => {
+                    write!(f, "\nError: Missing AST\n{}\n", ce.span.error())
+                }
+                CodegenErrorKind::StorageSlotCollision(pointer, explicit) => write!(
+                    f,
+                    "\nError: Storage Slot Collision: \"{}\" is assigned the slot of \"{}\"\n{}\n",
+                    pointer,
+                    explicit,
+                    ce.span.error()
+                ),
+                CodegenErrorKind::MisplacedBuiltin(name, offset) => write!(
+                    f,
+                    "\nError: Misplaced Builtin: \"{}\" must start the bytecode, but is at offset {:#x}\n{}\n",
+                    name,
+                    offset,
+                    ce.span.error()
+                ),
+                CodegenErrorKind::PushWidthMismatch(width, needed) => write!(
+                    f,
+                    "\nError: PUSH{} Can't Hold A {} Byte Value\n{}\n",
+                    width,
+                    needed,
+                    ce.span.error()
+                ),
+                CodegenErrorKind::MissingPushImmediate(width) => write!(
+                    f,
+                    "\nError: PUSH{} Must Be Followed By A Literal Or Constant\n{}\n",
+                    width,
+                    ce.span.error()
+                ),
             },
             CompilerError::FailedCompiles(v) => {
                 v.iter().for_each(|ce| {
@@ -1,12 +1,16 @@
+use alloc::string::{String, ToString};
+use core::fmt;
 use phf::phf_map;
-use std::fmt;
 use strum_macros::EnumString;
 
 /// All the EVM opcodes as a static array
 /// They are arranged in a particular order such that all the opcodes that have common
 /// prefixes are ordered by decreasing length to avoid mismatch when lexing.
 /// Example : [origin, or] or [push32, ..., push3]
-pub const OPCODES: [&str; 142] = [
+///
+/// Some opcodes are known by more than one mnemonic (`sha3`/`keccak256` and
+/// `difficulty`/`prevrandao`). Every alias is listed here and maps to the same `Opcode`.
+pub const OPCODES: [&str; 144] = [
     "lt",
     "gt",
     "slt",
@@ -19,6 +23,7 @@ pub const OPCODES: [&str; 142] = [
     "xor",
     "not",
     "sha3",
+    "keccak256",
     "address",
     "balance",
     "caller",
@@ -33,6 +38,7 @@ pub const OPCODES: [&str; 142] = [
     "timestamp",
     "number",
     "difficulty",
+    "prevrandao",
     "gaslimit",
     "chainid",
     "selfbalance",
@@ -164,6 +170,7 @@ pub static OPCODES_MAP: phf::Map<&'static str, Opcode> = phf_map! {
     "xor" => Opcode::Xor,
     "not" => Opcode::Not,
     "sha3" => Opcode::Sha3,
+    "keccak256" => Opcode::Sha3,
     "address" => Opcode::Address,
     "balance" => Opcode::Balance,
     "origin" => Opcode::Origin,
@@ -179,6 +186,7 @@ pub static OPCODES_MAP: phf::Map<&'static str, Opcode> = phf_map! {
     "timestamp" => Opcode::Timestamp,
     "number" => Opcode::Number,
     "difficulty" => Opcode::Difficulty,
+    "prevrandao" => Opcode::Difficulty,
     "gaslimit" => Opcode::Gaslimit,
     "chainid" => Opcode::Chainid,
     "selfbalance" => Opcode::Selfbalance,
@@ -357,6 +365,7 @@ pub enum Opcode {
     /// Arithmetic Shift Right Operation
     Sar,
     /// Compute the Keccak-256 hash of a 32-byte word
+    /// Can also be written as `keccak256`
     Sha3,
     /// Address of currently executing account
     Address,
@@ -397,6 +406,7 @@ pub enum Opcode {
     /// The Current Blocks Number
     Number,
     /// The Current Blocks Difficulty
+    /// Can also be written as `prevrandao`
     Difficulty,
     /// The Current Blocks Gas Limit
     Gaslimit,
@@ -744,6 +754,41 @@ impl Opcode {
         };
         opcode_str.to_string()
     }
+
+    /// Returns the opcode a bytecode byte encodes, if any
+    pub fn from_byte(byte: u8) -> Option<Opcode> {
+        OPCODES_MAP.values().find(|o| u8::from_str_radix(&o.string(), 16) == Ok(byte)).copied()
+    }
+
+    /// Whether control never continues to the next instruction, because the opcode halts (STOP,
+    /// RETURN, REVERT, INVALID or SELFDESTRUCT) or jumps unconditionally (JUMP)
+    pub fn is_terminating(&self) -> bool {
+        matches!(
+            self,
+            Opcode::Stop |
+                Opcode::Return |
+                Opcode::Revert |
+                Opcode::Invalid |
+                Opcode::Selfdestruct |
+                Opcode::Jump
+        )
+    }
+
+    /// Returns the immediate width in bytes if the opcode is a PUSH1 - PUSH32
+    pub fn push_width(&self) -> Option<usize> {
+        match u8::from_str_radix(&self.string(), 16) {
+            Ok(byte) if (0x60..=0x7f).contains(&byte) => Some((byte - 0x5f) as usize),
+            _ => None,
+        }
+    }
+
+    /// Whether the opcode is deprecated or discouraged.
+    ///
+    /// `callcode` is superseded by `delegatecall`, and `selfdestruct` no longer deletes code or
+    /// storage outside of the creating transaction since Cancun (EIP-6780).
+    pub fn is_deprecated(&self) -> bool {
+        matches!(self, Opcode::Callcode | Opcode::Selfdestruct)
+    }
 }
 
 impl fmt::Display for Opcode {
@@ -1,5 +1,13 @@
+use crate::io::UnpackError;
 use serde::{Deserialize, Serialize};
-use std::{cell::Ref, path::PathBuf, sync::Arc, time::SystemTime};
+use std::{
+    cell::Ref,
+    collections::HashMap,
+    fmt::Debug,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
 use uuid::Uuid;
 
 #[allow(clippy::to_string_in_format_args)]
@@ -145,6 +153,131 @@ impl FileSource {
     }
 }
 
+/// Provides file contents to the compiler when resolving sources and includes
+pub trait FileProvider: Debug + Send + Sync {
+    /// Reads the file at the given path into a string
+    fn read_to_string(&self, path: &str) -> Result<String, UnpackError>;
+
+    /// Returns whether a file exists at the given path
+    fn exists(&self, path: &str) -> bool;
+
+    /// Returns the names of the files directly inside the given directory, in any order.
+    ///
+    /// Providers that can't list directories match no files.
+    fn list_files(&self, dir: &str) -> Vec<String> {
+        let _ = dir;
+        vec![]
+    }
+}
+
+/// Expands an include path whose file name contains `*` or `?` wildcards into the matching files
+/// of its directory, sorted so the expansion is deterministic.
+///
+/// Only the file name may contain wildcards; `*` matches any run of characters and `?` a single
+/// character.
+pub fn expand_glob(pattern: &str, file_provider: &dyn FileProvider) -> Vec<String> {
+    let path = Path::new(pattern);
+    let (Some(dir), Some(file_pattern)) = (path.parent(), path.file_name()) else { return vec![] };
+    let file_pattern = file_pattern.to_string_lossy();
+
+    let mut matches: Vec<String> = file_provider
+        .list_files(&dir.to_string_lossy())
+        .into_iter()
+        .filter(|name| glob_matches(file_pattern.as_bytes(), name.as_bytes()))
+        .map(|name| dir.join(name).to_string_lossy().to_string())
+        .collect();
+    matches.sort();
+    matches
+}
+
+/// Whether a file name matches a pattern of `*` and `?` wildcards
+fn glob_matches(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.split_first(), name.split_first()) {
+        (None, None) => true,
+        (Some((b'*', rest)), _) => {
+            glob_matches(rest, name) || (!name.is_empty() && glob_matches(pattern, &name[1..]))
+        }
+        (Some((b'?', rest)), Some((_, name_rest))) => glob_matches(rest, name_rest),
+        (Some((p, rest)), Some((n, name_rest))) => p == n && glob_matches(rest, name_rest),
+        _ => false,
+    }
+}
+
+/// The default [FileProvider](FileProvider), reading from disk with `std::fs`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct FileSystemFileProvider;
+
+impl FileProvider for FileSystemFileProvider {
+    fn read_to_string(&self, path: &str) -> Result<String, UnpackError> {
+        std::fs::read_to_string(path).map_err(|_| UnpackError::MissingFile(path.to_string()))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        Path::new(path).is_file()
+    }
+
+    fn list_files(&self, dir: &str) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(dir) else { return vec![] };
+        entries
+            .flatten()
+            .filter(|e| e.path().is_file())
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect()
+    }
+}
+
+/// A [FileProvider](FileProvider) backed by an in-memory map of paths to sources
+///
+/// Paths are compared with any leading `./` stripped, so `./a.huff` and `a.huff` resolve to the
+/// same entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct InMemoryFileProvider {
+    /// The file sources, keyed by path
+    pub sources: HashMap<String, String>,
+}
+
+impl InMemoryFileProvider {
+    /// Public associated function to instantiate a new in-memory file provider
+    pub fn new(sources: HashMap<String, String>) -> Self {
+        Self {
+            sources: sources
+                .into_iter()
+                .map(|(path, source)| (Self::normalize(&path).to_string(), source))
+                .collect(),
+        }
+    }
+
+    fn normalize(path: &str) -> &str {
+        path.trim_start_matches("./")
+    }
+}
+
+impl FileProvider for InMemoryFileProvider {
+    fn read_to_string(&self, path: &str) -> Result<String, UnpackError> {
+        self.sources
+            .get(Self::normalize(path))
+            .cloned()
+            .ok_or_else(|| UnpackError::MissingFile(path.to_string()))
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.sources.contains_key(Self::normalize(path))
+    }
+
+    fn list_files(&self, dir: &str) -> Vec<String> {
+        let dir = Path::new(match Self::normalize(dir) {
+            "." => "",
+            dir => dir,
+        });
+        self.sources
+            .keys()
+            .map(Path::new)
+            .filter(|p| p.parent() == Some(dir))
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect()
+    }
+}
+
 use std::ops::{Add, Range};
 
 /// A Span is a section of a source file.
@@ -167,6 +300,17 @@ impl Span {
         Self { start, end, file }
     }
 
+    /// Returns the smallest span covering both spans, including any gap between them.
+    ///
+    /// The file is taken from `self`, falling back to `other`'s.
+    pub fn merge(&self, other: &Span) -> Span {
+        Span {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+            file: self.file.clone().or_else(|| other.file.clone()),
+        }
+    }
+
     /// Converts a span to a range.
     pub fn range(&self) -> Option<Range<usize>> {
         (*self != Self::EOF).then(|| self.start..self.end)
@@ -200,7 +344,7 @@ impl Span {
                             "\n     {}|\n  > {} | {}\n     {}|",
                             padding,
                             line_num,
-                            &s[line_start.to_owned()..line_end].replace('\n', ""),
+                            &s[line_start.to_owned()..line_end].replace(['\n', '\r'], ""),
                             padding
                         )
                     })
@@ -226,7 +370,7 @@ impl Add for Span {
     type Output = Span;
 
     fn add(self, rhs: Span) -> Self::Output {
-        Span::new(self.start..rhs.end, None)
+        self.merge(&rhs)
     }
 }
 
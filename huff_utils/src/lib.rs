@@ -1,39 +1,53 @@
 //! Utils
 //!
 //! Refactored utilities commonly used across the huff-rs project.
+//!
+//! Tokens, opcodes, primitive EVM types and byte utilities only need `alloc`, and build without
+//! the default `std` feature.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 #![warn(unused_extern_crates)]
 #![forbid(unsafe_code)]
 
+extern crate alloc;
+
 /// Abi Module
+#[cfg(feature = "std")]
 pub mod abi;
 
 /// Artifact Module
+#[cfg(feature = "std")]
 pub mod artifact;
 
 /// AST Module
+#[cfg(feature = "std")]
 pub mod ast;
 
 /// Bytecode Traits Module
+#[cfg(feature = "std")]
 pub mod bytecode;
 
 /// Token Module
 pub mod token;
 
 /// Lexing Error Module
+#[cfg(feature = "std")]
 pub mod error;
 
 /// EVM Module
 pub mod evm;
 
 /// Files Module
+#[cfg(feature = "std")]
 pub mod files;
 
 /// Lexical Reporting Module
+#[cfg(feature = "std")]
 pub mod report;
 
 /// IO Module
+#[cfg(feature = "std")]
 pub mod io;
 
 /// EVM Types Module
@@ -42,10 +56,21 @@ pub mod types;
 /// Bytes Util Module
 pub mod bytes_util;
 
+/// Disassembler Module
+pub mod disassembler;
+
+/// Bytecode Coverage Module
+pub mod coverage;
+
+/// Control Flow Graph Module
+#[cfg(feature = "std")]
+pub mod cfg;
+
 /// Prelude wraps common utilities.
 pub mod prelude {
+    #[cfg(feature = "std")]
     pub use crate::{
-        abi::*, artifact::*, ast::*, bytecode::*, bytes_util::*, error::*, evm::*, files::*, io::*,
-        report::*, token::*, types::*,
+        abi::*, artifact::*, ast::*, bytecode::*, cfg::*, error::*, files::*, io::*, report::*,
     };
+    pub use crate::{bytes_util::*, coverage::*, disassembler::*, evm::*, token::*, types::*};
 }
@@ -1,9 +1,17 @@
-use crate::{evm::Opcode, files::Span, types::PrimitiveEVMType};
-use std::{fmt, fmt::Write};
+#[cfg(feature = "std")]
+use crate::files::Span;
+use crate::{evm::Opcode, types::PrimitiveEVMType};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt, fmt::Write};
 
 type Literal = [u8; 32];
 
 /// A single Token
+#[cfg(feature = "std")]
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Token {
     /// The kind of token
@@ -12,6 +20,7 @@ pub struct Token {
     pub span: Span,
 }
 
+#[cfg(feature = "std")]
 impl Token {
     /// Public associated function that instantiates a Token.
     pub fn new(kind: TokenKind, span: Span) -> Self {
@@ -45,6 +54,10 @@ pub enum TokenKind {
     Takes,
     /// "returns" keyword
     Returns,
+    /// "@outline" macro decorator
+    Outline,
+    /// "@facet" macro decorator
+    Facet,
     /// "view" keyword
     View,
     /// "pure" keyword
@@ -55,8 +68,12 @@ pub enum TokenKind {
     NonPayable,
     /// "indexed" keyword
     Indexed,
+    /// "anonymous" keyword
+    Anonymous,
     /// "FREE_STORAGE_POINTER()" keyword
     FreeStoragePointer,
+    /// "IMMUTABLE()" keyword
+    Immutable,
     /// An Identifier
     Ident(String),
     /// Equal Sign
@@ -89,6 +106,9 @@ pub enum TokenKind {
     Colon,
     /// Number
     Num(usize),
+    /// A decimal number in a constant definition, kept as its digits since constants hold a
+    /// full word
+    Decimal(String),
     /// A Space
     Whitespace,
     /// A string literal
@@ -113,7 +133,9 @@ pub enum TokenKind {
     JumpTablePacked,
     /// A Code Table
     CodeTable,
-    /// A builtin function (__codesize, __tablesize, __tablestart)
+    /// A builtin function (__codesize, __tablesize, __tablestart, __calldata_offset,
+    /// __proxy_slot, __assert, __set_immutable, __linked, __pc, __mapping_slot,
+    /// __minimal_proxy, __nonpayable_guard)
     BuiltinFunction(String),
 }
 
@@ -134,9 +156,13 @@ impl fmt::Display for TokenKind {
             TokenKind::Payable => "payable",
             TokenKind::NonPayable => "nonpayable",
             TokenKind::Indexed => "indexed",
+            TokenKind::Anonymous => "anonymous",
             TokenKind::Takes => "takes",
             TokenKind::Returns => "returns",
+            TokenKind::Outline => "@outline",
+            TokenKind::Facet => "@facet",
             TokenKind::FreeStoragePointer => "FREE_STORAGE_POINTER()",
+            TokenKind::Immutable => "IMMUTABLE()",
             TokenKind::Ident(s) => return write!(f, "{}", s),
             TokenKind::Assign => "=",
             TokenKind::OpenParen => "(",
@@ -153,6 +179,7 @@ impl fmt::Display for TokenKind {
             TokenKind::Colon => ":",
             TokenKind::Comma => ",",
             TokenKind::Num(num) => return write!(f, "{}", num),
+            TokenKind::Decimal(digits) => digits,
             TokenKind::Whitespace => " ",
             TokenKind::Str(str) => str,
             TokenKind::Literal(l) => {
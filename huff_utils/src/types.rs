@@ -1,8 +1,19 @@
+#[cfg(feature = "std")]
 use crate::bytes_util::*;
-use ethers_core::abi::{ethereum_types::*, token::*, Tokenizable};
+use alloc::{format, string::String};
+use core::fmt;
+#[cfg(feature = "std")]
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use ethers_core::{
+    abi::{ethereum_types::*, token::*, Tokenizable},
+    types::I256,
+    utils::to_checksum,
+};
+#[cfg(feature = "std")]
 use lazy_static::lazy_static;
+#[cfg(feature = "std")]
 use regex::Regex;
-use std::{fmt, str::FromStr};
 
 /// Primitive EVM types
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
@@ -25,6 +36,8 @@ pub enum PrimitiveEVMType {
 
 /// Automatically converts an input string to a PrimitiveEVMType.
 /// Example : PrimitiveEVMType::from("uint256") => PrimitiveEVMType::Uint(256)
+///
+/// Unsized `uint` and `int` are aliases for `uint256` and `int256`, as in Solidity.
 impl TryFrom<String> for PrimitiveEVMType {
     type Error = String;
 
@@ -33,7 +46,9 @@ impl TryFrom<String> for PrimitiveEVMType {
             // Default to 256 if no size
             let size = match input.get(4..input.len()) {
                 Some(s) => match s.is_empty() {
-                    false => s.parse::<usize>().unwrap(),
+                    false => s
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid PrimitiveEVMType type: {}", input))?,
                     true => 256,
                 },
                 None => 256,
@@ -44,7 +59,9 @@ impl TryFrom<String> for PrimitiveEVMType {
             // Default to 256 if no size
             let size = match input.get(3..input.len()) {
                 Some(s) => match s.is_empty() {
-                    false => s.parse::<usize>().unwrap(),
+                    false => s
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid PrimitiveEVMType type: {}", input))?,
                     true => 256,
                 },
                 None => 256,
@@ -52,7 +69,10 @@ impl TryFrom<String> for PrimitiveEVMType {
             return Ok(PrimitiveEVMType::Int(size))
         }
         if input.starts_with("bytes") && input.len() != 5 {
-            let size = input.get(5..input.len()).unwrap().parse::<usize>().unwrap();
+            let size = input
+                .get(5..input.len())
+                .and_then(|s| s.parse::<usize>().ok())
+                .ok_or_else(|| format!("Invalid PrimitiveEVMType type: {}", input))?;
             return Ok(PrimitiveEVMType::Bytes(size))
         }
         if input.starts_with("bool") {
@@ -89,15 +109,84 @@ impl fmt::Display for PrimitiveEVMType {
 }
 
 // Array of regex to matching fancier EVM types
+#[cfg(feature = "std")]
 lazy_static! {
     /// Array of regex to matching fancier EVM types
     pub static ref EVM_TYPE_ARRAY_REGEX: Regex = Regex::new(r"((u|)int[0-9]*|address|bool|bytes|string|bytes[0-9]*)\[[0-9]*\]").unwrap();
 }
 
 /// Wrap ether-rs Token to allow to derive the TryFrom trait
-#[derive(Clone)]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
 pub struct EToken(pub Token);
 
+#[cfg(feature = "std")]
+impl EToken {
+    /// Converts an input string to an EToken using the declared type of the argument.
+    ///
+    /// Unsigned integers accept decimal or `0x` prefixed hex and must fit in the declared width.
+    /// Signed integers are encoded in two's complement, so `-1` as an `int256` is all `0xff`.
+    /// Addresses must be 20 bytes of hex, and a warning is emitted if a mixed-case address fails
+    /// its EIP-55 checksum. Booleans accept `true`, `false`, `1` and `0`. Strings are taken
+    /// verbatim and dynamic bytes are parsed from hex.
+    /// Types without specific handling fall back to [EToken::try_from].
+    pub fn try_from_typed(input: String, ty: &PrimitiveEVMType) -> Result<Self, String> {
+        match ty {
+            PrimitiveEVMType::Uint(size) => {
+                let value = match input.strip_prefix("0x") {
+                    Some(hex) => U256::from_str_radix(hex, 16).map_err(|e| e.to_string())?,
+                    None => U256::from_dec_str(&input).map_err(|e| e.to_string())?,
+                };
+                if value.bits() > *size {
+                    return Err(format!("Value {} does not fit in uint{}", input, size))
+                }
+                Ok(EToken(Token::Uint(value)))
+            }
+            PrimitiveEVMType::Int(size) => {
+                let value = I256::from_dec_str(&input).map_err(|e| e.to_string())?;
+                if value.bits() > *size as u32 {
+                    return Err(format!("Value {} does not fit in int{}", input, size))
+                }
+                Ok(EToken(Token::Int(value.into_raw())))
+            }
+            PrimitiveEVMType::Address => {
+                let cleaned_input = input.strip_prefix("0x").unwrap_or(&input);
+                if cleaned_input.len() != 40 {
+                    return Err(format!(
+                        "Invalid address length for {}: expected 20 bytes, got {} hex characters",
+                        input,
+                        cleaned_input.len()
+                    ))
+                }
+                let address = H160::from_str(cleaned_input).map_err(|e| e.to_string())?;
+
+                // Mixed-case addresses are expected to carry an EIP-55 checksum
+                let is_mixed_case = cleaned_input.chars().any(|c| c.is_ascii_uppercase()) &&
+                    cleaned_input.chars().any(|c| c.is_ascii_lowercase());
+                if is_mixed_case && to_checksum(&address, None)[2..] != *cleaned_input {
+                    tracing::warn!(target: "types", "Address \"{}\" has an invalid EIP-55 checksum", input);
+                }
+                Ok(EToken(Token::Address(address)))
+            }
+            PrimitiveEVMType::Bool => match input.as_str() {
+                "true" | "1" => Ok(EToken(Token::Bool(true))),
+                "false" | "0" => Ok(EToken(Token::Bool(false))),
+                _ => Err(format!("Invalid bool: {}", input)),
+            },
+            PrimitiveEVMType::String => Ok(EToken(Token::String(input))),
+            PrimitiveEVMType::DynBytes => {
+                let cleaned_input = input.strip_prefix("0x").unwrap_or(&input);
+                if cleaned_input.len() % 2 == 1 {
+                    return Err(format!("Invalid bytes, odd number of hex characters: {}", input))
+                }
+                Ok(EToken(Token::Bytes(str_to_vec(cleaned_input).map_err(|e| e.to_string())?)))
+            }
+            _ => EToken::try_from(input),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
 impl TryFrom<String> for EToken {
     type Error = String;
 
@@ -105,6 +194,9 @@ impl TryFrom<String> for EToken {
         if input.starts_with("0x") {
             // remove 0x prefix
             let cleaned_input = input.get(2..input.len()).unwrap();
+            if !cleaned_input.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(format!("Invalid hex: {}", input))
+            }
             // either address or fixed bytes
             if cleaned_input.len() <= 64 {
                 // could be either address or fixed bytes
@@ -0,0 +1,50 @@
+use ethers_core::utils::{hex, keccak256};
+use huff_utils::artifact::{link_placeholder, Artifact};
+
+#[test]
+fn estimates_deploy_gas() {
+    // 11 byte bootstrap deploying a 5 byte runtime, with 2 zero bytes in all
+    let runtime = "6001600201";
+    let artifact = Artifact {
+        bytecode: format!("600580600b6000396000f3{}", runtime),
+        runtime: runtime.to_string(),
+        ..Default::default()
+    };
+
+    let calldata = 2 * 4 + 14 * 16;
+    let initcode = 2;
+    let deposit = 5 * 200;
+    assert_eq!(artifact.deploy_gas_estimate(), 53000 + calldata + initcode + deposit);
+
+    // Prefixed bytecode is estimated the same
+    let prefixed = Artifact {
+        bytecode: format!("0x{}", artifact.bytecode),
+        runtime: format!("0x{}", artifact.runtime),
+        ..Default::default()
+    };
+    assert_eq!(prefixed.deploy_gas_estimate(), artifact.deploy_gas_estimate());
+}
+
+#[test]
+fn hashes_runtime_code() {
+    let runtime = "6001600201";
+    let expected = keccak256(hex::decode(runtime).unwrap());
+
+    let artifact = Artifact { runtime: runtime.to_string(), ..Default::default() };
+    assert_eq!(artifact.runtime_codehash(), Some(expected));
+
+    let prefixed = Artifact { runtime: format!("0x{}", runtime), ..Default::default() };
+    assert_eq!(prefixed.runtime_codehash(), Some(expected));
+
+    // The EXTCODEHASH of an account with empty code
+    let empty = Artifact::default();
+    assert_eq!(
+        hex::encode(empty.runtime_codehash().unwrap()),
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+
+    // Unlinked libraries leave the runtime undeployable, so it has no codehash
+    let unlinked =
+        Artifact { runtime: format!("73{}", link_placeholder("SafeMath")), ..Default::default() };
+    assert_eq!(unlinked.runtime_codehash(), None);
+}
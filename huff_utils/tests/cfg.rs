@@ -0,0 +1,71 @@
+use huff_utils::prelude::*;
+
+#[test]
+fn builds_blocks_for_conditional_branch() {
+    // 0x00 calldataload dest jumpi 0x00 0x00 revert dest: 0x01 stop
+    let cfg = build_cfg("600035600b5760006000fd5b600100");
+
+    assert_eq!(
+        cfg.blocks,
+        vec![
+            BasicBlock { start: 0, end: 6, successors: vec![6, 11] },
+            BasicBlock { start: 6, end: 11, successors: vec![] },
+            BasicBlock { start: 11, end: 15, successors: vec![] },
+        ]
+    );
+}
+
+#[test]
+fn splits_fallthrough_into_jumpdest_and_leaves_dynamic_jumps_unresolved() {
+    // 0x01 dest: 0x00 calldataload jump
+    let cfg = build_cfg("0x60015b60003556");
+
+    assert_eq!(
+        cfg.blocks,
+        vec![
+            BasicBlock { start: 0, end: 2, successors: vec![2] },
+            BasicBlock { start: 2, end: 7, successors: vec![] },
+        ]
+    );
+    assert_eq!(cfg.block_at(2).unwrap().end, 7);
+}
+
+#[test]
+fn finds_branches_rejoining_with_different_depths() {
+    // 0x00 calldataload dest jumpi 0x01 dest: stop
+    let unbalanced = find_unbalanced_branches("6000356100095760015b00");
+    assert_eq!(
+        unbalanced,
+        vec![BranchImbalance { offset: 6, jump_depth: 0, fallthrough_depth: 1 }]
+    );
+
+    // 0x00 calldataload dest jumpi 0x01 pop dest: stop
+    assert!(find_unbalanced_branches("60003561000a576001505b00").is_empty());
+}
+
+#[test]
+fn simulates_depth_falling_off_the_end() {
+    // 0x01 0x02
+    assert_eq!(exit_stack_depth("60016002"), Some(2));
+
+    // 0x00 calldataload dest jumpi 0x01 pop dest:
+    assert_eq!(exit_stack_depth("60003561000a576001505b"), Some(0));
+
+    // 0x01 0x00 0x00 return
+    assert_eq!(exit_stack_depth("600160006000f3"), None);
+    assert_eq!(exit_stack_depth(""), None);
+}
+
+#[test]
+fn finds_paths_running_past_an_offset() {
+    // 0x01 0x02 stop
+    assert!(runs_past("6001600200", 2));
+    assert!(!runs_past("6001600200", 5));
+
+    // 0x00 calldataload dest jumpi stop dest: 0x01
+    assert!(runs_past("60003561000857005b6001", 11));
+
+    // 0x00 calldataload dest jumpi stop dest: 0x00 0x00 revert
+    assert!(!runs_past("60003561000857005b60006000fd", 14));
+    assert!(!runs_past("", 0));
+}
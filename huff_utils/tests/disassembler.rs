@@ -0,0 +1,30 @@
+use huff_utils::prelude::*;
+
+#[test]
+fn disassembles_instructions_with_immediates() {
+    let instructions = disassemble("0x60015b20");
+    assert_eq!(
+        instructions,
+        vec![
+            Instruction { offset: 0, byte: 0x60, opcode: Some(Opcode::Push1), immediate: vec![1] },
+            Instruction {
+                offset: 2,
+                byte: 0x5b,
+                opcode: Some(Opcode::Jumpdest),
+                immediate: vec![]
+            },
+            Instruction { offset: 3, byte: 0x20, opcode: Some(Opcode::Sha3), immediate: vec![] },
+        ]
+    );
+    assert_eq!(instructions[0].mnemonic(), "PUSH1");
+    assert_eq!(instructions[0].hex(), "6001");
+    assert_eq!(instructions[2].mnemonic(), "SHA3");
+}
+
+#[test]
+fn maps_bytes_to_opcodes() {
+    assert_eq!(Opcode::from_byte(0x7f), Some(Opcode::Push32));
+    assert_eq!(Opcode::from_byte(0xfd), Some(Opcode::Revert));
+    assert_eq!(Opcode::from_byte(0x0c), None);
+    assert!(disassemble("0xzz").is_empty());
+}
@@ -0,0 +1,112 @@
+use ethers_core::{
+    abi::Token,
+    types::{H160, U256},
+};
+use huff_utils::types::{EToken, PrimitiveEVMType};
+use std::str::FromStr;
+
+#[test]
+fn encodes_negative_int256_as_twos_complement() {
+    let token = EToken::try_from_typed("-1".to_string(), &PrimitiveEVMType::Int(256)).unwrap();
+    assert_eq!(token.0, Token::Int(U256::MAX));
+    assert_eq!(ethers_core::abi::encode(&[token.0]), vec![0xff; 32]);
+}
+
+#[test]
+fn encodes_signed_int_bounds() {
+    let min = EToken::try_from_typed("-128".to_string(), &PrimitiveEVMType::Int(8)).unwrap();
+    assert_eq!(min.0, Token::Int(U256::MAX - U256::from(127)));
+
+    let max = EToken::try_from_typed("+127".to_string(), &PrimitiveEVMType::Int(8)).unwrap();
+    assert_eq!(max.0, Token::Int(U256::from(127)));
+
+    assert!(EToken::try_from_typed("128".to_string(), &PrimitiveEVMType::Int(8)).is_err());
+    assert!(EToken::try_from_typed("-129".to_string(), &PrimitiveEVMType::Int(8)).is_err());
+    assert!(EToken::try_from_typed("abc".to_string(), &PrimitiveEVMType::Int(256)).is_err());
+}
+
+#[test]
+fn accepts_checksummed_address() {
+    let token = EToken::try_from_typed(
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(),
+        &PrimitiveEVMType::Address,
+    )
+    .unwrap();
+    assert_eq!(
+        token.0,
+        Token::Address(H160::from_str("5aaeb6053f3e94c9b9a09f33669435e7ef1beaed").unwrap())
+    );
+}
+
+#[test]
+fn rejects_malformed_address() {
+    let err = EToken::try_from_typed(
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA".to_string(),
+        &PrimitiveEVMType::Address,
+    )
+    .unwrap_err();
+    assert!(err.contains("Invalid address length"));
+
+    assert!(EToken::try_from_typed(
+        "0xzzAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".to_string(),
+        &PrimitiveEVMType::Address,
+    )
+    .is_err());
+}
+
+#[test]
+fn encodes_bool_forms() {
+    for (input, expected) in [("true", true), ("1", true), ("false", false), ("0", false)] {
+        let token = EToken::try_from_typed(input.to_string(), &PrimitiveEVMType::Bool).unwrap();
+        assert_eq!(token.0, Token::Bool(expected));
+
+        let mut word = vec![0u8; 32];
+        word[31] = expected as u8;
+        assert_eq!(ethers_core::abi::encode(&[token.0]), word);
+    }
+
+    let err = EToken::try_from_typed("2".to_string(), &PrimitiveEVMType::Bool).unwrap_err();
+    assert_eq!(err, "Invalid bool: 2");
+}
+
+#[test]
+fn encodes_string_and_dynamic_bytes() {
+    let string = EToken::try_from_typed("hello".to_string(), &PrimitiveEVMType::String).unwrap();
+    assert_eq!(string.0, Token::String("hello".to_string()));
+
+    let bytes = EToken::try_from_typed("0xdeadbeef".to_string(), &PrimitiveEVMType::DynBytes);
+    assert_eq!(bytes.unwrap().0, Token::Bytes(vec![0xde, 0xad, 0xbe, 0xef]));
+
+    assert!(EToken::try_from_typed("0xabc".to_string(), &PrimitiveEVMType::DynBytes).is_err());
+}
+
+#[test]
+fn checks_uint_bounds() {
+    let max = U256::from(u128::MAX);
+    let token = EToken::try_from_typed(max.to_string(), &PrimitiveEVMType::Uint(128)).unwrap();
+    assert_eq!(token.0, Token::Uint(max));
+
+    let hex = EToken::try_from_typed("0xff".to_string(), &PrimitiveEVMType::Uint(128)).unwrap();
+    assert_eq!(hex.0, Token::Uint(U256::from(255)));
+
+    let err =
+        EToken::try_from_typed((max + 1).to_string(), &PrimitiveEVMType::Uint(128)).unwrap_err();
+    assert!(err.contains("does not fit in uint128"));
+    assert!(EToken::try_from_typed("-1".to_string(), &PrimitiveEVMType::Uint(128)).is_err());
+}
+
+#[test]
+fn parses_unsized_integer_aliases() {
+    assert_eq!(PrimitiveEVMType::try_from("uint".to_string()), Ok(PrimitiveEVMType::Uint(256)));
+    assert_eq!(PrimitiveEVMType::try_from("int".to_string()), Ok(PrimitiveEVMType::Int(256)));
+    assert_eq!(PrimitiveEVMType::Uint(256).to_string(), "uint256");
+    assert!(PrimitiveEVMType::try_from("internal".to_string()).is_err());
+    assert!(PrimitiveEVMType::try_from("bytesx".to_string()).is_err());
+}
+
+#[test]
+fn rejects_malformed_hex_without_panicking() {
+    assert!(EToken::try_from("0xzz".to_string()).is_err());
+    assert!(EToken::try_from(format!("0x{}é", "ab".repeat(32))).is_err());
+    assert!(EToken::try_from_typed("0x😀".to_string(), &PrimitiveEVMType::DynBytes).is_err());
+}
@@ -0,0 +1,23 @@
+use huff_utils::prelude::*;
+
+#[test]
+fn terminating_opcodes() {
+    for opcode in [
+        Opcode::Stop,
+        Opcode::Return,
+        Opcode::Revert,
+        Opcode::Invalid,
+        Opcode::Selfdestruct,
+        Opcode::Jump,
+    ] {
+        assert!(opcode.is_terminating(), "{:?} should terminate", opcode);
+    }
+}
+
+#[test]
+fn non_terminating_opcodes() {
+    // JUMPI falls through when its condition is zero
+    for opcode in [Opcode::Jumpi, Opcode::Jumpdest, Opcode::Add, Opcode::Push1, Opcode::Call] {
+        assert!(!opcode.is_terminating(), "{:?} should not terminate", opcode);
+    }
+}
@@ -0,0 +1,33 @@
+use huff_utils::prelude::*;
+use std::sync::Arc;
+
+#[test]
+fn merges_adjacent_spans() {
+    let open = Span::new(4..5, None);
+    let ident = Span::new(5..12, None);
+    assert_eq!(open.merge(&ident), Span::new(4..12, None));
+    assert_eq!(ident.merge(&open), Span::new(4..12, None));
+}
+
+#[test]
+fn merges_non_adjacent_spans() {
+    let first = Span::new(10..14, None);
+    let last = Span::new(30..31, None);
+
+    // The gap between the spans is covered too
+    assert_eq!(first.merge(&last), Span::new(10..31, None));
+    assert_eq!(first.clone() + last, Span::new(10..31, None));
+
+    // A span inside the other merges to the outer one
+    assert_eq!(Span::new(0..40, None).merge(&first), Span::new(0..40, None));
+}
+
+#[test]
+fn merge_keeps_the_file() {
+    let file = Arc::new(FileSource { path: "./Token.huff".to_string(), ..Default::default() });
+    let with_file = Span::new(0..2, Some(Arc::clone(&file)));
+    let without_file = Span::new(5..7, None);
+
+    assert_eq!(with_file.merge(&without_file).file, Some(Arc::clone(&file)));
+    assert_eq!(without_file.merge(&with_file).file, Some(file));
+}